@@ -1,12 +1,15 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    coin, to_binary, Addr, BankMsg, Binary, Coin, CustomQuery, Decimal, Deps, DepsMut, Env, Event,
-    MessageInfo, Order, StdResult, Timestamp, Uint128,
+    coin, from_slice, to_binary, Addr, Attribute, BankMsg, Binary, Coin, CustomQuery, Decimal,
+    Deps, DepsMut, Env, Event, MessageInfo, Order, Reply, StdError, StdResult, Storage,
+    SubMsgResult, Timestamp, Uint128, Uint64, WasmMsg,
 };
 use cw2::set_contract_version;
+use cw20::{Cw20Coin, Cw20ExecuteMsg, Cw20ReceiveMsg, Denom};
 use cw_storage_plus::Bound;
 use cw_utils::{ensure_from_older_version, maybe_addr};
+use std::collections::BTreeMap;
 use tg4::{
     HooksResponse, Member, MemberChangedHookMsg, MemberDiff, MemberInfo, MemberListResponse,
     MemberResponse, TotalPointsResponse,
@@ -14,14 +17,22 @@ use tg4::{
 
 use crate::error::ContractError;
 use crate::migration::generate_pending_member_updates;
-use crate::migration::migrate_config;
+use crate::migration::{migrate_config, migrate_distribution};
 use crate::msg::{
-    DelegatedResponse, ExecuteMsg, HalflifeInfo, HalflifeResponse, InstantiateMsg, MigrateMsg,
-    PreauthResponse, QueryMsg, RewardsResponse, SudoMsg,
+    AutoWithdrawResponse, DelegatedResponse, DistributionDataResponse, DistributionsResponse,
+    ExecuteMsg, HalflifeInfo, HalflifeProgressResponse, HalflifeResponse, InstantiateMsg,
+    IssuancesResponse, IssuerResponse, IssuersResponse, MemberReward, MemberRewardHistoryResponse,
+    MigrateMsg, PreauthResponse, QueryMsg, ReceiveMsg, RewardsResponse, SudoMsg,
+    WithdrawAdjustmentDataResponse,
 };
 use crate::state::{
-    Distribution, Halflife, WithdrawAdjustment, DISTRIBUTION, HALFLIFE, PREAUTH_SLASHING,
-    SHARES_SHIFT, SLASHERS, WITHDRAW_ADJUSTMENT,
+    export, import, issuances, remove_grants, scale_grants, AutoWithdrawal, DecayMode,
+    Distribution, DistributionRecord, Grant, Halflife, HalflifeJob, Issuance, Issuer,
+    PendingWithdrawal, WithdrawAdjustment, AUTO_WITHDRAWALS, AUTO_WITHDRAW_CURSOR,
+    CW20_DISTRIBUTION, CW20_WITHDRAW_ADJUSTMENT, DEFAULT_HALFLIFE_BATCH, DELEGATED, DISTRIBUTION,
+    DISTRIBUTION_COUNT, DISTRIBUTION_HISTORY, GRANTS, GRANT_COUNT, HALFLIFE, HALFLIFE_JOB,
+    ISSUANCE_COUNT, ISSUED, ISSUERS, LAST_DECAY, MEMBER_GRANTS, PENDING_WITHDRAWALS,
+    PREAUTH_SLASHING, SHARES_SHIFT, SLASHERS, WITHDRAW_ADJUSTMENT,
 };
 use tg_bindings::{request_privileges, Privilege, PrivilegeChangeMsg, TgradeMsg, TgradeQuery};
 use tg_utils::{members, validate_portion, Duration, ADMIN, HOOKS, PREAUTH_HOOKS, TOTAL};
@@ -52,6 +63,9 @@ pub fn instantiate(
         env.block.height,
         env.block.time,
         msg.halflife,
+        msg.halflife_reduction,
+        msg.decay_mode,
+        msg.halflife_batch,
         msg.denom,
     )?;
 
@@ -70,6 +84,9 @@ pub fn create<Q: CustomQuery>(
     height: u64,
     time: Timestamp,
     halflife: Option<Duration>,
+    halflife_reduction: Option<Decimal>,
+    decay_mode: Option<DecayMode>,
+    halflife_batch: Option<u32>,
     denom: String,
 ) -> Result<(), ContractError> {
     let admin_addr = admin
@@ -83,17 +100,16 @@ pub fn create<Q: CustomQuery>(
     let data = Halflife {
         halflife,
         last_applied: time,
+        reduction: validate_reduction(halflife_reduction.unwrap_or_else(|| Decimal::percent(50)))?,
+        mode: decay_mode.unwrap_or_default(),
+        batch_size: halflife_batch.unwrap_or(DEFAULT_HALFLIFE_BATCH),
     };
+    if data.batch_size == 0 {
+        return Err(ContractError::InvalidHalflifeBatch {});
+    }
     HALFLIFE.save(deps.storage, &data)?;
 
-    let distribution = Distribution {
-        denom,
-        shares_per_point: Uint128::zero(),
-        shares_leftover: 0,
-        distributed_total: Uint128::zero(),
-        withdrawable_total: Uint128::zero(),
-    };
-    DISTRIBUTION.save(deps.storage, &distribution)?;
+    DISTRIBUTION.save(deps.storage, &denom, &Distribution::new(&denom))?;
 
     let mut total = 0u64;
 
@@ -106,13 +122,6 @@ pub fn create<Q: CustomQuery>(
             &MemberInfo::new(member.points),
             height,
         )?;
-
-        let adjustment = WithdrawAdjustment {
-            shares_correction: 0i128.into(),
-            withdrawn_rewards: Uint128::zero(),
-            delegated: member_addr.clone(),
-        };
-        WITHDRAW_ADJUSTMENT.save(deps.storage, &member_addr, &adjustment)?;
     }
     TOTAL.save(deps.storage, &total)?;
 
@@ -121,6 +130,13 @@ pub fn create<Q: CustomQuery>(
     Ok(())
 }
 
+pub(crate) fn validate_reduction(reduction: Decimal) -> Result<Decimal, ContractError> {
+    if reduction.is_zero() || reduction > Decimal::one() {
+        return Err(ContractError::InvalidReduction(reduction));
+    }
+    Ok(reduction)
+}
+
 // And declare a custom Error variant for the ones where you will want to make use of it
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn execute(
@@ -139,17 +155,39 @@ pub fn execute(
             admin.map(|admin| api.addr_validate(&admin)).transpose()?,
         )?),
         UpdateMembers { add, remove } => execute_update_members(deps, env, info, add, remove),
-        AddPoints { addr, points } => execute_add_points(deps, env, info, addr, points),
+        AddPoints {
+            addr,
+            points,
+            expires_at,
+        } => execute_add_points(deps, env, info, addr, points, expires_at),
+        AddPointsBatch { points } => execute_add_points_batch(deps, env, info, points),
         AddHook { addr } => execute_add_hook(deps, info, addr),
         RemoveHook { addr } => execute_remove_hook(deps, info, addr),
         DistributeRewards { sender } => execute_distribute_rewards(deps, env, info, sender),
-        WithdrawRewards { owner, receiver } => {
-            execute_withdraw_rewards(deps, info, owner, receiver)
-        }
+        WithdrawRewards {
+            owner,
+            receiver,
+            denom,
+        } => execute_withdraw_rewards(deps, env, info, owner, receiver, denom),
         DelegateWithdrawal { delegated } => execute_delegate_withdrawal(deps, info, delegated),
+        AutoWithdraw {
+            receiver,
+            min_amount,
+        } => execute_auto_withdraw(deps, info, receiver, min_amount),
+        CancelAutoWithdraw {} => execute_cancel_auto_withdraw(deps, info),
         AddSlasher { addr } => execute_add_slasher(deps, info, addr),
         RemoveSlasher { addr } => execute_remove_slasher(deps, info, addr),
         Slash { addr, portion } => execute_slash(deps, env, info, addr, portion),
+        Receive(msg) => execute_receive(deps, env, info, msg),
+        SetIssuer {
+            addr,
+            cap,
+            period,
+            allowlist,
+        } => execute_set_issuer(deps, info, addr, cap, period, allowlist),
+        RemoveIssuer { addr } => execute_remove_issuer(deps, info, addr),
+        AddRewardDenom { denom } => execute_add_reward_denom(deps, info, denom),
+        AddRewardToken { token } => execute_add_reward_token(deps, info, token),
     }
 }
 
@@ -159,27 +197,75 @@ pub fn execute_add_points<Q: CustomQuery>(
     info: MessageInfo,
     addr: String,
     points: u64,
+    expires_at: Option<Timestamp>,
 ) -> Result<Response, ContractError> {
     let mut res = Response::new()
         .add_attribute("action", "add_points")
         .add_attribute("to_member", addr.to_string())
         .add_attribute("amount", points.to_string());
 
-    ADMIN.assert_admin(deps.as_ref(), &info.sender)?;
+    if let Some(expires_at) = expires_at {
+        if expires_at <= env.block.time {
+            return Err(ContractError::InvalidExpiration {});
+        }
+        res = res.add_attribute("expires_at", expires_at.to_string());
+    }
 
-    let old_points = query_member(deps.as_ref(), addr.clone(), None)?;
+    let addr = deps.api.addr_validate(&addr)?;
+    let additions = vec![(addr.clone(), points)];
+    authorize_issuance(deps.branch(), &env, &info.sender, &additions)?;
 
     // make the local update
-    let diff = update_members(
-        deps.branch(),
-        env.block.height,
-        vec![Member {
-            addr,
-            points: old_points.points.unwrap_or_default() + points,
-            start_height: old_points.start_height,
-        }],
-        vec![],
-    )?;
+    let diff = add_points(deps.branch(), &env, additions)?;
+    if let Some(expires_at) = expires_at {
+        let id = GRANT_COUNT.may_load(deps.storage)?.unwrap_or_default() + 1;
+        GRANT_COUNT.save(deps.storage, &id)?;
+        MEMBER_GRANTS.save(deps.storage, (&addr, id), &expires_at.seconds())?;
+        GRANTS.save(
+            deps.storage,
+            (expires_at.seconds(), id),
+            &Grant { addr, points },
+        )?;
+    }
+    // call all registered hooks
+    res.messages = HOOKS.prepare_hooks(deps.storage, |h| {
+        diff.clone().into_cosmos_msg(h).map(SubMsg::new)
+    })?;
+    Ok(res)
+}
+
+/// Adds points to many members at once. Points for the same address are summed up, and all the
+/// changes are reported to hooks with a single message.
+pub fn execute_add_points_batch<Q: CustomQuery>(
+    mut deps: DepsMut<Q>,
+    env: Env,
+    info: MessageInfo,
+    points: Vec<(String, u64)>,
+) -> Result<Response, ContractError> {
+    let mut additions: BTreeMap<Addr, u64> = BTreeMap::new();
+    let mut total = Uint64::zero();
+    for (addr, points) in points {
+        let addr = deps.api.addr_validate(&addr)?;
+        let entry = additions.entry(addr).or_default();
+        *entry = Uint64::new(*entry)
+            .checked_add(Uint64::new(points))
+            .map_err(StdError::from)?
+            .u64();
+        total = total
+            .checked_add(Uint64::new(points))
+            .map_err(StdError::from)?;
+    }
+    let additions: Vec<_> = additions.into_iter().collect();
+
+    authorize_issuance(deps.branch(), &env, &info.sender, &additions)?;
+
+    let mut res = Response::new()
+        .add_attribute("action", "add_points_batch")
+        .add_attribute("members", additions.len().to_string())
+        .add_attribute("amount", total.to_string());
+
+    // make the local update
+    let diff = add_points(deps.branch(), &env, additions)?;
     // call all registered hooks
     res.messages = HOOKS.prepare_hooks(deps.storage, |h| {
         diff.clone().into_cosmos_msg(h).map(SubMsg::new)
@@ -187,6 +273,180 @@ pub fn execute_add_points<Q: CustomQuery>(
     Ok(res)
 }
 
+/// Checks if `sender` may award the points. The admin is not restricted, issuers are limited by
+/// their allowlists and caps, and their awards are recorded in the issuance log.
+fn authorize_issuance<Q: CustomQuery>(
+    deps: DepsMut<Q>,
+    env: &Env,
+    sender: &Addr,
+    additions: &[(Addr, u64)],
+) -> Result<(), ContractError> {
+    if ADMIN.is_admin(deps.as_ref(), sender)? {
+        return Ok(());
+    }
+    let issuer = ISSUERS.may_load(deps.storage, sender)?.ok_or_else(|| {
+        ContractError::Unauthorized("Sender is neither admin nor issuer".to_owned())
+    })?;
+
+    if let Some(allowlist) = &issuer.allowlist {
+        if let Some((addr, _)) = additions.iter().find(|(addr, _)| !allowlist.contains(addr)) {
+            return Err(ContractError::RecipientNotAllowed(addr.to_string()));
+        }
+    }
+
+    let mut issued = ISSUED.may_load(deps.storage, sender)?.unwrap_or_default();
+    let remaining = issuer
+        .cap
+        .saturating_sub(issued.in_period(issuer.period, env.block.time));
+    let total: u64 = additions.iter().map(|(_, points)| points).sum();
+    if total > remaining {
+        return Err(ContractError::IssuerCapExceeded { remaining });
+    }
+    issued.add(issuer.period, env.block.time, total);
+    ISSUED.save(deps.storage, sender, &issued)?;
+
+    let id = ISSUANCE_COUNT.may_load(deps.storage)?.unwrap_or_default() + 1;
+    let issuance = Issuance {
+        id,
+        issuer: sender.clone(),
+        recipients: additions.to_vec(),
+        points: total,
+        height: env.block.height,
+        time: env.block.time,
+    };
+    issuances().save(deps.storage, id, &issuance)?;
+    ISSUANCE_COUNT.save(deps.storage, &id)?;
+
+    Ok(())
+}
+
+pub fn execute_set_issuer<Q: CustomQuery>(
+    deps: DepsMut<Q>,
+    info: MessageInfo,
+    addr: String,
+    cap: u64,
+    period: Duration,
+    allowlist: Option<Vec<String>>,
+) -> Result<Response, ContractError> {
+    ADMIN.assert_admin(deps.as_ref(), &info.sender)?;
+
+    if period.seconds() == 0 {
+        return Err(ContractError::InvalidIssuerPeriod {});
+    }
+    let addr = deps.api.addr_validate(&addr)?;
+    let allowlist = allowlist
+        .map(|allowlist| {
+            allowlist
+                .iter()
+                .map(|addr| deps.api.addr_validate(addr))
+                .collect::<StdResult<Vec<_>>>()
+        })
+        .transpose()?;
+    let issuer = Issuer {
+        cap,
+        period,
+        allowlist,
+    };
+    ISSUERS.save(deps.storage, &addr, &issuer)?;
+
+    let res = Response::new()
+        .add_attribute("action", "set_issuer")
+        .add_attribute("issuer", &addr)
+        .add_attribute("cap", cap.to_string())
+        .add_attribute("sender", info.sender);
+    Ok(res)
+}
+
+pub fn execute_remove_issuer<Q: CustomQuery>(
+    deps: DepsMut<Q>,
+    info: MessageInfo,
+    addr: String,
+) -> Result<Response, ContractError> {
+    ADMIN.assert_admin(deps.as_ref(), &info.sender)?;
+
+    let addr = deps.api.addr_validate(&addr)?;
+    ISSUERS.remove(deps.storage, &addr);
+    ISSUED.remove(deps.storage, &addr);
+
+    let res = Response::new()
+        .add_attribute("action", "remove_issuer")
+        .add_attribute("issuer", &addr)
+        .add_attribute("sender", info.sender);
+    Ok(res)
+}
+
+pub fn execute_add_reward_denom<Q: CustomQuery>(
+    deps: DepsMut<Q>,
+    info: MessageInfo,
+    denom: String,
+) -> Result<Response, ContractError> {
+    ADMIN.assert_admin(deps.as_ref(), &info.sender)?;
+
+    if !DISTRIBUTION.has(deps.storage, &denom) {
+        DISTRIBUTION.save(deps.storage, &denom, &Distribution::new(&denom))?;
+    }
+
+    let res = Response::new()
+        .add_attribute("action", "add_reward_denom")
+        .add_attribute("denom", denom)
+        .add_attribute("sender", info.sender);
+    Ok(res)
+}
+
+pub fn execute_add_reward_token<Q: CustomQuery>(
+    deps: DepsMut<Q>,
+    info: MessageInfo,
+    token: String,
+) -> Result<Response, ContractError> {
+    ADMIN.assert_admin(deps.as_ref(), &info.sender)?;
+
+    let token = deps.api.addr_validate(&token)?;
+    if !CW20_DISTRIBUTION.has(deps.storage, &token) {
+        CW20_DISTRIBUTION.save(deps.storage, &token, &Distribution::new(token.as_str()))?;
+    }
+
+    let res = Response::new()
+        .add_attribute("action", "add_reward_token")
+        .add_attribute("token", token)
+        .add_attribute("sender", info.sender);
+    Ok(res)
+}
+
+/// Adds points to members, settling their pending decay first. `additions` are expected to have
+/// unique addresses.
+fn add_points<Q: CustomQuery>(
+    mut deps: DepsMut<Q>,
+    env: &Env,
+    additions: Vec<(Addr, u64)>,
+) -> Result<MemberChangedHookMsg, ContractError> {
+    let mut decays = vec![];
+    let mut updates = vec![];
+    for (addr, points) in additions {
+        decays.extend(apply_decay(deps.branch(), env, &addr)?);
+        let old = members().may_load(deps.storage, &addr)?;
+        let points = Uint64::new(old.as_ref().map(|mi| mi.points).unwrap_or_default())
+            .checked_add(Uint64::new(points))
+            .map_err(StdError::from)?;
+        updates.push(Member {
+            addr: addr.into(),
+            points: points.u64(),
+            start_height: old.and_then(|mi| mi.start_height),
+        });
+    }
+
+    // the decay applied above is accounted up to its own timestamp, which has to be kept
+    let mut diff = store_members(
+        deps.branch(),
+        env.block.height,
+        env.block.time,
+        updates,
+        vec![],
+        false,
+    )?;
+    diff.diffs.splice(0..0, decays);
+    Ok(diff)
+}
+
 pub fn execute_add_hook<Q: CustomQuery>(
     deps: DepsMut<Q>,
     info: MessageInfo,
@@ -248,7 +508,7 @@ pub fn execute_update_members<Q: CustomQuery>(
     ADMIN.assert_admin(deps.as_ref(), &info.sender)?;
 
     // make the local update
-    let diff = update_members(deps.branch(), env.block.height, add, remove)?;
+    let diff = update_members(deps.branch(), env.block.height, env.block.time, add, remove)?;
     // call all registered hooks
     res.messages = HOOKS.prepare_hooks(deps.storage, |h| {
         diff.clone().into_cosmos_msg(h).map(SubMsg::new)
@@ -274,20 +534,134 @@ pub fn execute_distribute_rewards<Q: CustomQuery>(
         .transpose()?
         .unwrap_or(info.sender);
 
-    let mut distribution = DISTRIBUTION.load(deps.storage)?;
+    let mut resp = Response::new()
+        .add_attribute("action", "distribute_rewards")
+        .add_attribute("sender", sender.as_str());
 
-    let withdrawable: u128 = distribution.withdrawable_total.into();
-    let balance: u128 = deps
-        .querier
-        .query_balance(env.contract.address, distribution.denom.clone())?
-        .amount
-        .into();
+    // Every reward denom held by the contract, and not yet assigned to members, is distributed.
+    // Other tokens are ignored, so sending dust in many denoms cannot make this unaffordable.
+    let distributions = DISTRIBUTION
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    let mut rewards = vec![];
+    let mut shares_per_point = vec![];
+    for (denom, mut distribution) in distributions {
+        let balance = deps
+            .querier
+            .query_balance(&env.contract.address, &denom)?
+            .amount;
+
+        let withdrawable: u128 = distribution.withdrawable_total.into();
+        let amount = balance.u128() - withdrawable;
+        if amount == 0 {
+            continue;
+        }
+
+        shares_per_point.push(distribute(&mut distribution, amount, total));
+        DISTRIBUTION.save(deps.storage, &denom, &distribution)?;
+
+        resp = resp
+            .add_attribute("denom", &distribution.denom)
+            .add_attribute("amount", amount.to_string());
+        rewards.push(coin(amount, distribution.denom));
+    }
+
+    if !rewards.is_empty() {
+        let record = DistributionRecord {
+            rewards,
+            shares_per_point,
+            ..new_record(&env, sender, total)
+        };
+        record_distribution(deps.storage, record)?;
+    }
+
+    Ok(resp)
+}
+
+/// Distributes cw20 tokens sent to this contract. Every transfer is distributed on its own, there
+/// is no need to call `DistributeRewards` for them.
+pub fn execute_receive<Q: CustomQuery>(
+    deps: DepsMut<Q>,
+    env: Env,
+    info: MessageInfo,
+    wrapper: Cw20ReceiveMsg,
+) -> Result<Response, ContractError> {
+    let total = TOTAL.load(deps.storage)? as u128;
+
+    // There are no shares in play - noone to distribute to
+    if total == 0 {
+        return Err(ContractError::NoMembersToDistributeTo {});
+    }
 
-    let amount = balance - withdrawable;
+    // Declared sender is optional, the original token sender by default
+    let sender = if wrapper.msg.is_empty() {
+        None
+    } else {
+        match from_slice(&wrapper.msg)? {
+            ReceiveMsg::DistributeRewards { sender } => sender,
+        }
+    };
+    let sender = sender.unwrap_or(wrapper.sender);
+    let sender = deps.api.addr_validate(&sender)?;
+
+    // The token is the contract calling us, only the reward tokens added by the admin are
+    // trusted to report the transfers truly
+    let token = info.sender;
+    let mut distribution = CW20_DISTRIBUTION
+        .may_load(deps.storage, &token)?
+        .ok_or_else(|| ContractError::UnknownRewardToken(token.to_string()))?;
+    let amount = wrapper.amount.u128();
+    let mut resp = Response::new()
+        .add_attribute("action", "distribute_rewards")
+        .add_attribute("sender", sender.as_str());
     if amount == 0 {
-        return Ok(Response::new());
+        return Ok(resp);
+    }
+
+    let shares_per_point = distribute(&mut distribution, amount, total);
+    CW20_DISTRIBUTION.save(deps.storage, &token, &distribution)?;
+
+    resp = resp
+        .add_attribute("cw20", token.as_str())
+        .add_attribute("amount", amount.to_string());
+
+    let record = DistributionRecord {
+        cw20_rewards: vec![Cw20Coin {
+            address: token.into(),
+            amount: wrapper.amount,
+        }],
+        cw20_shares_per_point: vec![shares_per_point],
+        ..new_record(&env, sender, total)
+    };
+    record_distribution(deps.storage, record)?;
+
+    Ok(resp)
+}
+
+/// Distribution record of the current block without any rewards, its id is assigned on storing
+fn new_record(env: &Env, sender: Addr, total_points: u128) -> DistributionRecord {
+    DistributionRecord {
+        id: 0,
+        height: env.block.height,
+        time: env.block.time,
+        sender,
+        total_points: total_points as u64,
+        rewards: vec![],
+        cw20_rewards: vec![],
+        shares_per_point: vec![],
+        cw20_shares_per_point: vec![],
     }
+}
 
+/// Stores the distribution in the history under the next id
+fn record_distribution(storage: &mut dyn Storage, record: DistributionRecord) -> StdResult<()> {
+    let id = DISTRIBUTION_COUNT.may_load(storage)?.unwrap_or_default() + 1;
+    DISTRIBUTION_COUNT.save(storage, &id)?;
+    DISTRIBUTION_HISTORY.save(storage, id, &DistributionRecord { id, ..record })
+}
+
+/// Assigns `amount` of rewards to `total` points, returning the shares assigned to a single point
+fn distribute(distribution: &mut Distribution, amount: u128, total: u128) -> Uint128 {
     let leftover: u128 = distribution.shares_leftover.into();
     let points = (amount << SHARES_SHIFT) + leftover;
     let points_per_share = points / total;
@@ -300,65 +674,179 @@ pub fn execute_distribute_rewards<Q: CustomQuery>(
     distribution.shares_per_point += Uint128::from(points_per_share);
     distribution.distributed_total += Uint128::from(amount);
     distribution.withdrawable_total += Uint128::from(amount);
-
-    DISTRIBUTION.save(deps.storage, &distribution)?;
-
-    let resp = Response::new()
-        .add_attribute("action", "distribute_rewards")
-        .add_attribute("sender", sender.as_str())
-        .add_attribute("denom", &distribution.denom)
-        .add_attribute("amount", amount.to_string());
-
-    Ok(resp)
+    points_per_share.into()
 }
 
 pub fn execute_withdraw_rewards<Q: CustomQuery>(
-    deps: DepsMut<Q>,
+    mut deps: DepsMut<Q>,
+    env: Env,
     info: MessageInfo,
     owner: Option<String>,
     receiver: Option<String>,
+    denom: Option<Denom>,
 ) -> Result<Response, ContractError> {
     let owner = owner.map_or_else(
         || Ok(info.sender.clone()),
         |owner| deps.api.addr_validate(&owner),
     )?;
 
-    let mut distribution = DISTRIBUTION.load(deps.storage)?;
-    let mut adjustment = WITHDRAW_ADJUSTMENT.load(deps.storage, &owner)?;
-
-    if ![&owner, &adjustment.delegated].contains(&&info.sender) {
+    let delegated = DELEGATED
+        .may_load(deps.storage, &owner)?
+        .unwrap_or_else(|| owner.clone());
+    if ![&owner, &delegated].contains(&&info.sender) {
         return Err(ContractError::Unauthorized(
             "Sender is neither owner or delegated".to_owned(),
         ));
     }
 
-    let reward = withdrawable_rewards(deps.as_ref(), &owner, &distribution, &adjustment)?;
+    // Decay doesn't change already assigned rewards, but has to be settled before further
+    // distributions
+    let decay = apply_decay(deps.branch(), &env, &owner)?;
+    let decay_msgs = match decay {
+        Some(decay) => HOOKS.prepare_hooks(deps.storage, |h| {
+            MemberChangedHookMsg::one(decay.clone())
+                .into_cosmos_msg(h)
+                .map(SubMsg::new)
+        })?,
+        None => vec![],
+    };
+
     let receiver = receiver
         .map(|receiver| deps.api.addr_validate(&receiver))
         .transpose()?
         .unwrap_or_else(|| info.sender.clone());
 
-    if reward.amount.is_zero() {
+    let (attrs, transfers) = withdraw(
+        deps.branch(),
+        &owner,
+        &receiver,
+        Uint128::zero(),
+        denom.as_ref(),
+    )?;
+    let msgs: Vec<_> = transfers.into_iter().map(|(msg, _)| msg).collect();
+    if msgs.is_empty() {
         // Just do nothing
-        return Ok(Response::new());
+        return Ok(Response::new().add_submessages(decay_msgs));
     }
 
-    adjustment.withdrawn_rewards += reward.amount;
-    WITHDRAW_ADJUSTMENT.save(deps.storage, &owner, &adjustment)?;
-    distribution.withdrawable_total -= reward.amount;
-    DISTRIBUTION.save(deps.storage, &distribution)?;
-
     let resp = Response::new()
         .add_attribute("action", "withdraw_rewards")
         .add_attribute("sender", info.sender.as_str())
         .add_attribute("owner", owner.as_str())
         .add_attribute("receiver", receiver.as_str())
-        .add_attribute("reward", &reward.denom)
-        .add_attribute("amount", reward.amount.to_string())
-        .add_submessage(SubMsg::new(BankMsg::Send {
+        .add_attributes(attrs)
+        .add_submessages(msgs)
+        .add_submessages(decay_msgs);
+
+    Ok(resp)
+}
+
+/// Withdraws all rewards assigned to `owner` to `receiver`, or only the ones in `only` denom if
+/// given, skipping tokens with amount below `min_amount`. Returns attributes describing withdrawn
+/// rewards, and messages sending them together with the rewards each of them sends.
+#[allow(clippy::type_complexity)]
+fn withdraw<Q: CustomQuery>(
+    deps: DepsMut<Q>,
+    owner: &Addr,
+    receiver: &Addr,
+    min_amount: Uint128,
+    only: Option<&Denom>,
+) -> Result<(Vec<Attribute>, Vec<(SubMsg, Vec<(Denom, Uint128)>)>), ContractError> {
+    let mut attrs = vec![];
+    let mut rewards = vec![];
+    let mut native = vec![];
+    let mut transfers = vec![];
+    for (denom, mut distribution) in all_distributions(deps.storage)? {
+        if only.is_some_and(|only| *only != denom) {
+            continue;
+        }
+        let mut adjustment = load_adjustment(deps.storage, owner, &denom)?;
+        let amount = withdrawable_amount(deps.as_ref(), owner, &distribution, &adjustment)?;
+        if amount.is_zero() || amount < min_amount {
+            continue;
+        }
+
+        adjustment.withdrawn_rewards += amount;
+        save_adjustment(deps.storage, owner, &denom, &adjustment)?;
+        distribution.withdrawable_total -= amount;
+        save_distribution(deps.storage, &denom, &distribution)?;
+
+        attrs.push(Attribute::new("reward", &distribution.denom));
+        attrs.push(Attribute::new("amount", amount.to_string()));
+        match &denom {
+            Denom::Native(native_denom) => {
+                rewards.push(coin(amount.u128(), native_denom));
+                native.push((denom, amount));
+            }
+            Denom::Cw20(token) => {
+                let msg = SubMsg::new(WasmMsg::Execute {
+                    contract_addr: token.to_string(),
+                    msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                        recipient: receiver.to_string(),
+                        amount,
+                    })?,
+                    funds: vec![],
+                });
+                transfers.push((msg, vec![(denom, amount)]));
+            }
+        }
+    }
+
+    let mut msgs = vec![];
+    if !rewards.is_empty() {
+        let msg = SubMsg::new(BankMsg::Send {
             to_address: receiver.to_string(),
-            amount: vec![reward],
-        }));
+            amount: rewards,
+        });
+        msgs.push((msg, native));
+    }
+    msgs.extend(transfers);
+
+    Ok((attrs, msgs))
+}
+
+pub fn execute_auto_withdraw<Q: CustomQuery>(
+    deps: DepsMut<Q>,
+    info: MessageInfo,
+    receiver: Option<String>,
+    min_amount: Uint128,
+) -> Result<Response, ContractError> {
+    if members().may_load(deps.storage, &info.sender)?.is_none() {
+        return Err(ContractError::NotMember {});
+    }
+
+    let receiver = receiver
+        .map(|receiver| deps.api.addr_validate(&receiver))
+        .transpose()?
+        .unwrap_or_else(|| info.sender.clone());
+
+    AUTO_WITHDRAWALS.save(
+        deps.storage,
+        &info.sender,
+        &AutoWithdrawal {
+            receiver: receiver.clone(),
+            min_amount,
+        },
+    )?;
+
+    let resp = Response::new()
+        .add_attribute("action", "auto_withdraw")
+        .add_attribute("owner", info.sender)
+        .add_attribute("receiver", receiver)
+        .add_attribute("min_amount", min_amount);
+
+    Ok(resp)
+}
+
+pub fn execute_cancel_auto_withdraw<Q: CustomQuery>(
+    deps: DepsMut<Q>,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    AUTO_WITHDRAWALS.remove(deps.storage, &info.sender);
+
+    let resp = Response::new()
+        .add_attribute("action", "cancel_auto_withdraw")
+        .add_attribute("owner", info.sender);
 
     Ok(resp)
 }
@@ -370,19 +858,7 @@ pub fn execute_delegate_withdrawal<Q: CustomQuery>(
 ) -> Result<Response, ContractError> {
     let delegated = deps.api.addr_validate(&delegated)?;
 
-    WITHDRAW_ADJUSTMENT.update(deps.storage, &info.sender, |data| -> StdResult<_> {
-        Ok(data.map_or_else(
-            || WithdrawAdjustment {
-                shares_correction: 0.into(),
-                withdrawn_rewards: Uint128::zero(),
-                delegated: delegated.clone(),
-            },
-            |mut data| {
-                data.delegated = delegated.clone();
-                data
-            },
-        ))
-    })?;
+    DELEGATED.save(deps.storage, &info.sender, &delegated)?;
 
     let resp = Response::new()
         .add_attribute("action", "delegate_withdrawal")
@@ -459,11 +935,13 @@ pub fn execute_slash<Q: CustomQuery>(
 
     validate_portion(portion)?;
 
-    let ppw: u128 = DISTRIBUTION.load(deps.storage)?.shares_per_point.into();
+    let decay = apply_decay(deps.branch(), &env, &addr)?;
+
+    let ppw = shares_per_point(deps.storage)?;
 
     let mut diff = 0i128;
 
-    members().update(
+    let updated = members().update(
         deps.storage,
         &addr,
         env.block.height,
@@ -481,27 +959,40 @@ pub fn execute_slash<Q: CustomQuery>(
             Ok(MemberInfo::new(new.u128() as _))
         },
     )?;
-    apply_points_correction(deps.branch(), &addr, ppw, diff)?;
+    scale_grants(
+        deps.storage,
+        &addr,
+        (updated.points as i128 - diff) as u64,
+        updated.points,
+    )?;
+    apply_points_correction(deps.branch(), &addr, &ppw, diff)?;
 
     TOTAL.update(deps.storage, |total| -> StdResult<_> {
         Ok((total as i128 + diff) as _)
     })?;
 
-    let res = Response::new()
+    let mut res = Response::new()
         .add_attribute("action", "slash")
         .add_attribute("addr", &addr)
         .add_attribute("sender", info.sender);
+    if let Some(decay) = decay {
+        res.messages = HOOKS.prepare_hooks(deps.storage, |h| {
+            MemberChangedHookMsg::one(decay.clone())
+                .into_cosmos_msg(h)
+                .map(SubMsg::new)
+        })?;
+    }
 
     Ok(res)
 }
 
-/// Calculates withdrawable_rewards from distribution and adjustment info.
-pub fn withdrawable_rewards<Q: CustomQuery>(
+/// Calculates withdrawable rewards from distribution and adjustment info.
+pub fn withdrawable_amount<Q: CustomQuery>(
     deps: Deps<Q>,
     owner: &Addr,
     distribution: &Distribution,
     adjustment: &WithdrawAdjustment,
-) -> StdResult<Coin> {
+) -> StdResult<Uint128> {
     let ppw: u128 = distribution.shares_per_point.into();
     let points: u128 = members()
         .may_load(deps.storage, owner)?
@@ -515,7 +1006,7 @@ pub fn withdrawable_rewards<Q: CustomQuery>(
     let amount = points as u128 >> SHARES_SHIFT;
     let amount = amount - withdrawn;
 
-    Ok(coin(amount, &distribution.denom))
+    Ok(amount.into())
 }
 
 pub fn sudo_add_member<Q: CustomQuery>(
@@ -529,7 +1020,13 @@ pub fn sudo_add_member<Q: CustomQuery>(
         .add_attribute("points", add.points.to_string());
 
     // make the local update
-    let diff = update_members(deps.branch(), env.block.height, vec![add], vec![])?;
+    let diff = update_members(
+        deps.branch(),
+        env.block.height,
+        env.block.time,
+        vec![add],
+        vec![],
+    )?;
     // call all registered hooks
     res.messages = HOOKS.prepare_hooks(deps.storage, |h| {
         diff.clone().into_cosmos_msg(h).map(SubMsg::new)
@@ -539,34 +1036,56 @@ pub fn sudo_add_member<Q: CustomQuery>(
 
 // the logic from execute_update_members extracted for easier import
 pub fn update_members<Q: CustomQuery>(
+    deps: DepsMut<Q>,
+    height: u64,
+    time: Timestamp,
+    to_add: Vec<Member>,
+    to_remove: Vec<String>,
+) -> Result<MemberChangedHookMsg, ContractError> {
+    store_members(deps, height, time, to_add, to_remove, true)
+}
+
+/// Stores the new points of `to_add` members and removes `to_remove` ones. In the continuous
+/// decay mode, points of new members start decaying at `time`, and so do the ones of existing
+/// members if `restart_decay` is set - otherwise their last decay timestamp is kept.
+fn store_members<Q: CustomQuery>(
     mut deps: DepsMut<Q>,
     height: u64,
+    time: Timestamp,
     to_add: Vec<Member>,
     to_remove: Vec<String>,
+    restart_decay: bool,
 ) -> Result<MemberChangedHookMsg, ContractError> {
     let mut total = TOTAL.load(deps.storage)?;
     let mut diffs: Vec<MemberDiff> = vec![];
 
-    let ppw: u128 = DISTRIBUTION.load(deps.storage)?.shares_per_point.into();
+    let ppw = shares_per_point(deps.storage)?;
+    let continuous = HALFLIFE.load(deps.storage)?.is_continuous();
 
     // add all new members and update total
     for add in to_add.into_iter() {
         let add_addr = deps.api.addr_validate(&add.addr)?;
 
-        let mut diff = 0;
+        let points = add.points;
+        let mut old_points = 0;
         members().update(deps.storage, &add_addr, height, |old| -> StdResult<_> {
             diffs.push(MemberDiff::new(
                 add.addr,
                 old.as_ref().map(|mi| mi.points),
                 Some(add.points),
             ));
-            let old = old.unwrap_or_default();
-            total -= old.points;
+            old_points = old.unwrap_or_default().points;
+            total -= old_points;
             total += add.points;
-            diff = add.points as i128 - old.points as i128;
             Ok(MemberInfo::new(add.points))
         })?;
-        apply_points_correction(deps.branch(), &add_addr, ppw, diff)?;
+        let diff = points as i128 - old_points as i128;
+        apply_points_correction(deps.branch(), &add_addr, &ppw, diff)?;
+        // Grants can't exceed the points left after an overwrite
+        scale_grants(deps.storage, &add_addr, old_points, points)?;
+        if continuous && (restart_decay || !LAST_DECAY.has(deps.storage, &add_addr)) {
+            LAST_DECAY.save(deps.storage, &add_addr, &time)?;
+        }
     }
 
     for remove in to_remove.into_iter() {
@@ -577,7 +1096,9 @@ pub fn update_members<Q: CustomQuery>(
             diffs.push(MemberDiff::new(remove, Some(points), None));
             total -= points;
             members().remove(deps.storage, &remove_addr, height)?;
-            apply_points_correction(deps.branch(), &remove_addr, ppw, -(points as i128))?;
+            apply_points_correction(deps.branch(), &remove_addr, &ppw, -(points as i128))?;
+            LAST_DECAY.remove(deps.storage, &remove_addr);
+            remove_grants(deps.storage, &remove_addr)?;
         }
     }
 
@@ -585,102 +1106,413 @@ pub fn update_members<Q: CustomQuery>(
     Ok(MemberChangedHookMsg { diffs })
 }
 
-/// Applies points correction for given address.
-/// `shares_per_point` is current value from `SHARES_PER_POINT` - not loaded in function, to
-/// avoid multiple queries on bulk updates.
+/// All rewards distributions, native denoms first and cw20 tokens after them
+pub fn all_distributions(storage: &dyn Storage) -> StdResult<Vec<(Denom, Distribution)>> {
+    let native = DISTRIBUTION
+        .range(storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(denom, distribution)| (Denom::Native(denom), distribution)));
+    let cw20 = CW20_DISTRIBUTION
+        .range(storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(token, distribution)| (Denom::Cw20(token), distribution)));
+    native.chain(cw20).collect()
+}
+
+fn save_distribution(
+    storage: &mut dyn Storage,
+    denom: &Denom,
+    distribution: &Distribution,
+) -> StdResult<()> {
+    match denom {
+        Denom::Native(denom) => DISTRIBUTION.save(storage, denom, distribution),
+        Denom::Cw20(token) => CW20_DISTRIBUTION.save(storage, token, distribution),
+    }
+}
+
+fn load_adjustment(
+    storage: &dyn Storage,
+    owner: &Addr,
+    denom: &Denom,
+) -> StdResult<WithdrawAdjustment> {
+    let adjustment = match denom {
+        Denom::Native(denom) => WITHDRAW_ADJUSTMENT.may_load(storage, (owner, denom))?,
+        Denom::Cw20(token) => CW20_WITHDRAW_ADJUSTMENT.may_load(storage, (owner, token))?,
+    };
+    Ok(adjustment.unwrap_or_else(|| match denom {
+        Denom::Native(denom) => WithdrawAdjustment::new(denom),
+        Denom::Cw20(token) => WithdrawAdjustment::new(token.as_str()),
+    }))
+}
+
+fn save_adjustment(
+    storage: &mut dyn Storage,
+    owner: &Addr,
+    denom: &Denom,
+    adjustment: &WithdrawAdjustment,
+) -> StdResult<()> {
+    match denom {
+        Denom::Native(denom) => WITHDRAW_ADJUSTMENT.save(storage, (owner, denom), adjustment),
+        Denom::Cw20(token) => CW20_WITHDRAW_ADJUSTMENT.save(storage, (owner, token), adjustment),
+    }
+}
+
+/// Current `shares_per_point` of every distributed token
+pub fn shares_per_point(storage: &dyn Storage) -> StdResult<Vec<(Denom, u128)>> {
+    Ok(all_distributions(storage)?
+        .into_iter()
+        .map(|(denom, distribution)| (denom, distribution.shares_per_point.u128()))
+        .collect())
+}
+
+/// Applies points correction for given address.
+/// `shares_per_point` are current values from `DISTRIBUTION` for every token - not loaded in
+/// function, to avoid multiple queries on bulk updates.
 /// `diff` is the points change
 pub fn apply_points_correction<Q: CustomQuery>(
     deps: DepsMut<Q>,
     addr: &Addr,
-    shares_per_point: u128,
+    shares_per_point: &[(Denom, u128)],
     diff: i128,
 ) -> StdResult<()> {
-    WITHDRAW_ADJUSTMENT.update(deps.storage, addr, |old| -> StdResult<_> {
-        let mut old = old.unwrap_or_else(|| {
-            // This should never happen, but better this than panic
-            WithdrawAdjustment {
-                shares_correction: 0.into(),
-                withdrawn_rewards: Uint128::zero(),
-                delegated: addr.clone(),
-            }
-        });
-        let shares_correction: i128 = old.shares_correction.into();
-        old.shares_correction = (shares_correction - shares_per_point as i128 * diff).into();
-        Ok(old)
-    })?;
+    for (denom, shares_per_point) in shares_per_point {
+        if *shares_per_point == 0 {
+            continue;
+        }
+        let mut adjustment = load_adjustment(deps.storage, addr, denom)?;
+        let shares_correction: i128 = adjustment.shares_correction.into();
+        adjustment.shares_correction =
+            (shares_correction - *shares_per_point as i128 * diff).into();
+        save_adjustment(deps.storage, addr, denom, &adjustment)?;
+    }
     Ok(())
 }
 
+/// Applies the pending continuous decay to the member, returning the change to be reported to
+/// hooks. Does nothing unless the halflife is in `DecayMode::Continuous`.
+pub fn apply_decay<Q: CustomQuery>(
+    deps: DepsMut<Q>,
+    env: &Env,
+    addr: &Addr,
+) -> Result<Option<MemberDiff>, ContractError> {
+    let halflife = HALFLIFE.load(deps.storage)?;
+    if !halflife.is_continuous() {
+        return Ok(None);
+    }
+    let old = match members().may_load(deps.storage, addr)? {
+        Some(old) => old,
+        None => return Ok(None),
+    };
+    let since = LAST_DECAY
+        .may_load(deps.storage, addr)?
+        .unwrap_or(halflife.last_applied);
+    let (points, reached) = halflife.decayed(old.points, since, env.block.time);
+    LAST_DECAY.save(deps.storage, addr, &reached)?;
+    if points == old.points {
+        return Ok(None);
+    }
+
+    let reduction = old.points - points;
+    members().replace(
+        deps.storage,
+        addr,
+        Some(&MemberInfo::new(points)),
+        Some(&old),
+        env.block.height,
+    )?;
+    scale_grants(deps.storage, addr, old.points, points)?;
+    let ppw = shares_per_point(deps.storage)?;
+    TOTAL.update(deps.storage, |total| -> StdResult<_> {
+        Ok(total - reduction)
+    })?;
+    apply_points_correction(deps, addr, &ppw, -(reduction as i128))?;
+
+    Ok(Some(MemberDiff::new(addr, Some(old.points), Some(points))))
+}
+
+/// Points of the member with the pending continuous decay accounted, without storing it
+fn decayed_points<Q: CustomQuery>(
+    deps: Deps<Q>,
+    halflife: &Halflife,
+    now: Timestamp,
+    addr: &Addr,
+    points: u64,
+) -> StdResult<u64> {
+    if !halflife.is_continuous() {
+        return Ok(points);
+    }
+    let since = LAST_DECAY
+        .may_load(deps.storage, addr)?
+        .unwrap_or(halflife.last_applied);
+    Ok(halflife.decayed(points, since, now).0)
+}
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn sudo(deps: DepsMut<TgradeQuery>, env: Env, msg: SudoMsg) -> Result<Response, ContractError> {
     match msg {
         SudoMsg::UpdateMember(member) => sudo_add_member(deps, env, member),
         SudoMsg::PrivilegeChange(PrivilegeChangeMsg::Promoted {}) => privilege_promote(deps),
         SudoMsg::EndBlock {} => end_block(deps, env),
+        SudoMsg::Export {} => export(deps.as_ref()),
+        SudoMsg::Import(state) => import(deps, env, *state),
         _ => Err(ContractError::UnknownSudoMsg {}),
     }
 }
 
 fn privilege_promote<Q: CustomQuery>(deps: DepsMut<Q>) -> Result<Response, ContractError> {
+    let mut res = Response::new();
     if HALFLIFE.load(deps.storage)?.halflife.is_some() {
         let msgs = request_privileges(&[Privilege::EndBlocker]);
-        Ok(Response::new().add_submessages(msgs))
-    } else {
-        Ok(Response::new())
+        res = res.add_submessages(msgs);
     }
-}
+    let msgs = request_privileges(&[Privilege::StateExporterImporter]);
+    res = res.add_submessages(msgs);
 
-fn points_reduction(points: u64) -> u64 {
-    points - (points / 2)
+    Ok(res)
 }
 
-fn end_block<Q: CustomQuery>(mut deps: DepsMut<Q>, env: Env) -> Result<Response, ContractError> {
+/// Maximum number of expired grants removed in a single block
+const EXPIRED_GRANTS_BATCH: usize = 100;
+
+/// Removes what is left of expired grants from their members. Grants lose points together with
+/// their members (by halflife or slashing), so only the remainder is removed, never the points
+/// the member earned otherwise.
+fn expire_grants<Q: CustomQuery>(
+    mut deps: DepsMut<Q>,
+    env: &Env,
+) -> Result<Response, ContractError> {
     let mut resp = Response::new();
 
-    // If duration of half life added to timestamp of last applied
-    // if lesser then current timestamp, do nothing
-    if !HALFLIFE.load(deps.storage)?.should_apply(env.block.time) {
+    let end = Bound::inclusive((env.block.time.seconds(), u64::MAX));
+    let expired = GRANTS
+        .range(deps.storage, None, Some(end), Order::Ascending)
+        .take(EXPIRED_GRANTS_BATCH)
+        .collect::<StdResult<Vec<_>>>()?;
+    if expired.is_empty() {
         return Ok(resp);
     }
 
-    let ppw: u128 = DISTRIBUTION.load(deps.storage)?.shares_per_point.into();
+    let ppw = shares_per_point(deps.storage)?;
 
     let mut reduction = 0;
+    let mut diffs: Vec<MemberDiff> = vec![];
+    for (key, grant) in &expired {
+        // Pending decay scales the grant down together with the member points
+        diffs.extend(apply_decay(deps.branch(), env, &grant.addr)?);
+        let grant = GRANTS.load(deps.storage, *key)?;
+        GRANTS.remove(deps.storage, *key);
+        MEMBER_GRANTS.remove(deps.storage, (&grant.addr, key.1));
+
+        let old = match members().may_load(deps.storage, &grant.addr)? {
+            Some(old) => old,
+            None => continue,
+        };
+        let diff = grant.points.min(old.points);
+        if diff == 0 {
+            continue;
+        }
+        diffs.push(MemberDiff::new(
+            &grant.addr,
+            Some(old.points),
+            Some(old.points - diff),
+        ));
+        reduction += diff;
+        members().replace(
+            deps.storage,
+            &grant.addr,
+            Some(&MemberInfo::new(old.points - diff)),
+            Some(&old),
+            env.block.height,
+        )?;
+        apply_points_correction(deps.branch(), &grant.addr, &ppw, -(diff as i128))?;
+    }
+    if !diffs.is_empty() {
+        let diff = MemberChangedHookMsg { diffs };
+        // call all registered hooks
+        resp.messages = HOOKS.prepare_hooks(deps.storage, |h| {
+            diff.clone().into_cosmos_msg(h).map(SubMsg::new)
+        })?;
+    }
 
-    let members_to_update: Vec<_> = members()
-        .range(deps.storage, None, None, Order::Ascending)
-        .filter_map(|item| {
-            (move || -> StdResult<Option<_>> {
-                let (
-                    addr,
-                    MemberInfo {
-                        points,
-                        start_height,
-                    },
-                ) = item?;
-                if points <= 1 {
-                    return Ok(None);
-                }
-                Ok(Some(Member {
-                    addr: addr.into(),
-                    points,
-                    start_height,
-                }))
-            })()
-            .transpose()
-        })
-        .collect::<StdResult<_>>()?;
+    TOTAL.update(deps.storage, |total| -> StdResult<_> {
+        Ok(total - reduction)
+    })?;
+
+    let evt = Event::new("expired_grants")
+        .add_attribute("grants", expired.len().to_string())
+        .add_attribute("reduction", reduction.to_string());
+    Ok(resp.add_event(evt))
+}
+
+/// Maximum number of members processed by automatic withdrawal in a single block
+const AUTO_WITHDRAW_BATCH: usize = 50;
+
+/// Sends rewards of the next batch of members registered for automatic withdrawal. Once all
+/// members are processed, the next block starts over. Members without points left are dropped
+/// from the registry after their rewards are sent. Sending is allowed to fail - the rewards are
+/// then restored and the member is dropped as well, see `auto_withdraw_reply`.
+fn auto_withdraw<Q: CustomQuery>(mut deps: DepsMut<Q>) -> Result<Response, ContractError> {
+    let mut resp = Response::new();
+    PENDING_WITHDRAWALS.clear(deps.storage);
+    let mut reply_id = 0;
+
+    let cursor = AUTO_WITHDRAW_CURSOR.may_load(deps.storage)?;
+    let start = cursor.as_ref().map(Bound::exclusive);
+    let batch = AUTO_WITHDRAWALS
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(AUTO_WITHDRAW_BATCH)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    match batch.last() {
+        Some((last, _)) if batch.len() == AUTO_WITHDRAW_BATCH => {
+            AUTO_WITHDRAW_CURSOR.save(deps.storage, last)?
+        }
+        _ => AUTO_WITHDRAW_CURSOR.remove(deps.storage),
+    }
+
+    for (owner, settings) in batch {
+        let (attrs, transfers) = withdraw(
+            deps.branch(),
+            &owner,
+            &settings.receiver,
+            settings.min_amount,
+            None,
+        )?;
+        let points = members()
+            .may_load(deps.storage, &owner)?
+            .map_or(0, |mi| mi.points);
+        if points == 0 {
+            AUTO_WITHDRAWALS.remove(deps.storage, &owner);
+        }
+        if transfers.is_empty() {
+            continue;
+        }
+
+        for (msg, rewards) in transfers {
+            reply_id += 1;
+            let pending = PendingWithdrawal {
+                owner: owner.clone(),
+                rewards,
+            };
+            PENDING_WITHDRAWALS.save(deps.storage, reply_id, &pending)?;
+            resp = resp.add_submessage(SubMsg::reply_on_error(msg.msg, reply_id));
+        }
+        let evt = Event::new("auto_withdraw")
+            .add_attribute("owner", owner)
+            .add_attribute("receiver", settings.receiver)
+            .add_attributes(attrs);
+        resp = resp.add_event(evt);
+    }
 
+    Ok(resp)
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn reply(deps: DepsMut<TgradeQuery>, _env: Env, msg: Reply) -> Result<Response, ContractError> {
+    auto_withdraw_reply(deps, msg)
+}
+
+/// Restores rewards of the automatic withdrawal which failed to be sent, and drops its owner from
+/// the registry, so the failing transfer is not retried every block.
+fn auto_withdraw_reply<Q: CustomQuery>(
+    deps: DepsMut<Q>,
+    msg: Reply,
+) -> Result<Response, ContractError> {
+    let pending = PENDING_WITHDRAWALS
+        .may_load(deps.storage, msg.id)?
+        .ok_or(ContractError::UnrecognisedReply(msg.id))?;
+    PENDING_WITHDRAWALS.remove(deps.storage, msg.id);
+
+    let mut evt = Event::new("auto_withdraw_failed").add_attribute("owner", pending.owner.as_str());
+    if let SubMsgResult::Err(err) = msg.result {
+        evt = evt.add_attribute("error", err);
+    }
+    for (denom, amount) in pending.rewards {
+        let mut adjustment = load_adjustment(deps.storage, &pending.owner, &denom)?;
+        adjustment.withdrawn_rewards -= amount;
+        save_adjustment(deps.storage, &pending.owner, &denom, &adjustment)?;
+
+        let mut distribution = match &denom {
+            Denom::Native(denom) => DISTRIBUTION.load(deps.storage, denom)?,
+            Denom::Cw20(token) => CW20_DISTRIBUTION.load(deps.storage, token)?,
+        };
+        distribution.withdrawable_total += amount;
+        save_distribution(deps.storage, &denom, &distribution)?;
+
+        evt = evt
+            .add_attribute("reward", &distribution.denom)
+            .add_attribute("amount", amount.to_string());
+    }
+    AUTO_WITHDRAWALS.remove(deps.storage, &pending.owner);
+
+    Ok(Response::new().add_event(evt))
+}
+
+fn end_block<Q: CustomQuery>(mut deps: DepsMut<Q>, env: Env) -> Result<Response, ContractError> {
+    let mut resp = expire_grants(deps.branch(), &env)?;
+    let withdrawals = auto_withdraw(deps.branch())?;
+    resp = resp
+        .add_submessages(withdrawals.messages)
+        .add_events(withdrawals.events);
+
+    // Continuously decaying points are reduced lazily, when members are touched
+    let halflife = HALFLIFE.load(deps.storage)?;
+    if halflife.mode == DecayMode::Continuous {
+        return Ok(resp);
+    }
+
+    let mut job = match HALFLIFE_JOB.may_load(deps.storage)? {
+        Some(job) => job,
+        // If duration of half life added to timestamp of last applied
+        // if lesser then current timestamp, do nothing
+        None if halflife.should_apply(env.block.time) => {
+            // The next period is counted from the start of this one, no matter how many blocks it
+            // takes to process
+            HALFLIFE.save(
+                deps.storage,
+                &Halflife {
+                    last_applied: env.block.time,
+                    ..halflife.clone()
+                },
+            )?;
+            HalflifeJob {
+                started_at: env.block.height,
+                ..HalflifeJob::default()
+            }
+        }
+        None => return Ok(resp),
+    };
+
+    let ppw = shares_per_point(deps.storage)?;
+
+    let start = job.start_after.as_ref().map(Bound::exclusive);
+    let batch_size = halflife.batch_size as usize;
+    let mut batch = members()
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(batch_size + 1)
+        .collect::<StdResult<Vec<_>>>()?;
+    let finished = batch.len() <= batch_size;
+    batch.truncate(batch_size);
+
+    let mut reduction = 0;
     let mut diffs: Vec<MemberDiff> = vec![];
-    for member in members_to_update {
-        let diff = points_reduction(member.points);
+    for (addr, member) in batch {
+        job.processed += 1;
+        job.start_after = Some(addr.clone());
+
+        // Only the points held when the halflife started are reduced, the ones added later are
+        // fresh
+        let at_start = members()
+            .may_load_at_height(deps.storage, &addr, job.started_at + 1)?
+            .map_or(0, |mi| mi.points);
+        let diff = halflife.reduction_of(at_start).min(member.points);
+        if diff == 0 {
+            continue;
+        }
         diffs.push(MemberDiff::new(
-            member.addr.clone(),
+            &addr,
             Some(member.points),
             Some(member.points - diff),
         ));
         reduction += diff;
-        let addr = Addr::unchecked(member.addr);
         members().replace(
             deps.storage,
             &addr,
@@ -688,30 +1520,31 @@ fn end_block<Q: CustomQuery>(mut deps: DepsMut<Q>, env: Env) -> Result<Response,
             Some(&MemberInfo::new(member.points)),
             env.block.height,
         )?;
-        apply_points_correction(deps.branch(), &addr, ppw, -(diff as i128))?;
+        scale_grants(deps.storage, &addr, member.points, member.points - diff)?;
+        apply_points_correction(deps.branch(), &addr, &ppw, -(diff as i128))?;
+    }
+    if !diffs.is_empty() {
+        let diff = MemberChangedHookMsg { diffs };
+        // call all registered hooks
+        resp = resp.add_submessages(HOOKS.prepare_hooks(deps.storage, |h| {
+            diff.clone().into_cosmos_msg(h).map(SubMsg::new)
+        })?);
     }
-    let diff = MemberChangedHookMsg { diffs };
-    // call all registered hooks
-    resp.messages = HOOKS.prepare_hooks(deps.storage, |h| {
-        diff.clone().into_cosmos_msg(h).map(SubMsg::new)
-    })?;
-
-    // We need to update half life's last applied timestamp to current one
-    HALFLIFE.update(deps.storage, |hf| -> StdResult<_> {
-        Ok(Halflife {
-            halflife: hf.halflife,
-            last_applied: env.block.time,
-        })
-    })?;
 
     let mut total = TOTAL.load(deps.storage)?;
     total -= reduction;
     TOTAL.save(deps.storage, &total)?;
+    job.reduction += reduction;
 
-    let evt = Event::new("halflife")
-        .add_attribute("height", env.block.height.to_string())
-        .add_attribute("reduction", reduction.to_string());
-    let resp = resp.add_event(evt);
+    if finished {
+        HALFLIFE_JOB.remove(deps.storage);
+        let evt = Event::new("halflife")
+            .add_attribute("height", env.block.height.to_string())
+            .add_attribute("reduction", job.reduction.to_string());
+        resp = resp.add_event(evt);
+    } else {
+        HALFLIFE_JOB.save(deps.storage, &job)?;
+    }
 
     Ok(resp)
 }
@@ -723,10 +1556,12 @@ pub fn query(deps: Deps<TgradeQuery>, env: Env, msg: QueryMsg) -> StdResult<Bina
         Member {
             addr,
             at_height: height,
-        } => to_binary(&query_member(deps, addr, height)?),
-        ListMembers { start_after, limit } => to_binary(&list_members(deps, start_after, limit)?),
+        } => to_binary(&query_member(deps, &env, addr, height)?),
+        ListMembers { start_after, limit } => {
+            to_binary(&list_members(deps, &env, start_after, limit)?)
+        }
         ListMembersByPoints { start_after, limit } => {
-            to_binary(&list_members_by_points(deps, start_after, limit)?)
+            to_binary(&list_members_by_points(deps, &env, start_after, limit)?)
         }
         TotalPoints {} => to_binary(&query_total_points(deps)?),
         Admin {} => to_binary(&ADMIN.query_admin(deps)?),
@@ -743,34 +1578,69 @@ pub fn query(deps: Deps<TgradeQuery>, env: Env, msg: QueryMsg) -> StdResult<Bina
         UndistributedRewards {} => to_binary(&query_undistributed_rewards(deps, env)?),
         Delegated { owner } => to_binary(&query_delegated(deps, owner)?),
         Halflife {} => to_binary(&query_halflife(deps)?),
+        HalflifeProgress {} => to_binary(&query_halflife_progress(deps)?),
         IsSlasher { addr } => {
             let addr = deps.api.addr_validate(&addr)?;
             to_binary(&SLASHERS.is_slasher(deps.storage, &addr)?)
         }
         ListSlashers {} => to_binary(&SLASHERS.list_slashers(deps.storage)?),
-        DistributionData {} => to_binary(&DISTRIBUTION.may_load(deps.storage)?),
-        WithdrawAdjustmentData { addr } => {
-            let addr = deps.api.addr_validate(&addr)?;
-            to_binary(&WITHDRAW_ADJUSTMENT.may_load(deps.storage, &addr)?)
+        DistributionData {} => to_binary(&query_distribution_data(deps)?),
+        WithdrawAdjustmentData { addr } => to_binary(&query_withdraw_adjustment_data(deps, addr)?),
+        AutoWithdraw { addr } => to_binary(&query_auto_withdraw(deps, addr)?),
+        ListDistributions { start_after, limit } => {
+            to_binary(&list_distributions(deps, start_after, limit)?)
+        }
+        MemberRewardHistory {
+            addr,
+            start_after,
+            limit,
+        } => to_binary(&query_member_reward_history(
+            deps,
+            addr,
+            start_after,
+            limit,
+        )?),
+        Issuer { addr } => to_binary(&query_issuer(deps, &env, addr)?),
+        ListIssuers { start_after, limit } => {
+            to_binary(&list_issuers(deps, &env, start_after, limit)?)
         }
+        IssuanceLog {
+            issuer,
+            start_after,
+            limit,
+        } => to_binary(&list_issuances(deps, issuer, start_after, limit)?),
     }
 }
 
+/// Stored total of points. The pending continuous decay is not accounted, as it would require
+/// iterating over all members.
 fn query_total_points<Q: CustomQuery>(deps: Deps<Q>) -> StdResult<TotalPointsResponse> {
     let points = TOTAL.load(deps.storage)?;
     Ok(TotalPointsResponse { points })
 }
 
+/// Current points are reported with the pending continuous decay applied, historical ones as
+/// they were stored.
 fn query_member<Q: CustomQuery>(
     deps: Deps<Q>,
+    env: &Env,
     addr: String,
     height: Option<u64>,
 ) -> StdResult<MemberResponse> {
     let addr = deps.api.addr_validate(&addr)?;
     let mi = match height {
-        Some(h) => members().may_load_at_height(deps.storage, &addr, h),
-        None => members().may_load(deps.storage, &addr),
-    }?;
+        Some(h) => members().may_load_at_height(deps.storage, &addr, h)?,
+        None => {
+            let halflife = HALFLIFE.load(deps.storage)?;
+            members()
+                .may_load(deps.storage, &addr)?
+                .map(|mi| -> StdResult<_> {
+                    let points = decayed_points(deps, &halflife, env.block.time, &addr, mi.points)?;
+                    Ok(MemberInfo { points, ..mi })
+                })
+                .transpose()?
+        }
+    };
     Ok(mi.into())
 }
 
@@ -778,45 +1648,51 @@ pub fn query_withdrawable_rewards<Q: CustomQuery>(
     deps: Deps<Q>,
     owner: String,
 ) -> StdResult<RewardsResponse> {
-    // Not checking address, as if it is invalid it is guaranteed not to appear in maps, so
-    // `withdrawable_rewards` would return error itself.
+    // Not checking address, as if it is invalid it is guaranteed not to appear in maps
     let owner = Addr::unchecked(owner);
-    let distribution = DISTRIBUTION.load(deps.storage)?;
-    let adjustment = if let Some(adj) = WITHDRAW_ADJUSTMENT.may_load(deps.storage, &owner)? {
-        adj
-    } else {
-        return Ok(RewardsResponse {
-            rewards: coin(0, distribution.denom),
-        });
-    };
-
-    let rewards = withdrawable_rewards(deps, &owner, &distribution, &adjustment)?;
-    Ok(RewardsResponse { rewards })
+    let rewards = all_distributions(deps.storage)?
+        .into_iter()
+        .map(|(denom, distribution)| {
+            let adjustment = load_adjustment(deps.storage, &owner, &denom)?;
+            let amount = withdrawable_amount(deps, &owner, &distribution, &adjustment)?;
+            Ok((denom, amount))
+        })
+        .collect::<StdResult<_>>()?;
+    Ok(RewardsResponse::new(rewards))
 }
 
 pub fn query_undistributed_rewards<Q: CustomQuery>(
     deps: Deps<Q>,
     env: Env,
 ) -> StdResult<RewardsResponse> {
-    let distribution = DISTRIBUTION.load(deps.storage)?;
-    let balance = deps
-        .querier
-        .query_balance(env.contract.address, distribution.denom.clone())?
-        .amount;
+    let rewards: Vec<Coin> = DISTRIBUTION
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| {
+            let (denom, distribution) = item?;
+            let balance = deps
+                .querier
+                .query_balance(env.contract.address.clone(), denom.clone())?
+                .amount;
+            Ok(coin(
+                (balance - distribution.withdrawable_total).into(),
+                denom,
+            ))
+        })
+        .collect::<StdResult<_>>()?;
 
+    // cw20 tokens are distributed as soon as they are received
     Ok(RewardsResponse {
-        rewards: coin(
-            (balance - distribution.withdrawable_total).into(),
-            &distribution.denom,
-        ),
+        rewards,
+        cw20_rewards: vec![],
     })
 }
 
 pub fn query_distributed_rewards<Q: CustomQuery>(deps: Deps<Q>) -> StdResult<RewardsResponse> {
-    let distribution = DISTRIBUTION.load(deps.storage)?;
-    Ok(RewardsResponse {
-        rewards: coin(distribution.distributed_total.into(), &distribution.denom),
-    })
+    let rewards = all_distributions(deps.storage)?
+        .into_iter()
+        .map(|(denom, distribution)| (denom, distribution.distributed_total))
+        .collect();
+    Ok(RewardsResponse::new(rewards))
 }
 
 pub fn query_delegated<Q: CustomQuery>(
@@ -825,17 +1701,56 @@ pub fn query_delegated<Q: CustomQuery>(
 ) -> StdResult<DelegatedResponse> {
     let owner = deps.api.addr_validate(&owner)?;
 
-    let delegated = WITHDRAW_ADJUSTMENT
-        .may_load(deps.storage, &owner)?
-        .map_or(owner, |data| data.delegated);
+    let delegated = DELEGATED.may_load(deps.storage, &owner)?.unwrap_or(owner);
 
     Ok(DelegatedResponse { delegated })
 }
 
+fn query_distribution_data<Q: CustomQuery>(deps: Deps<Q>) -> StdResult<DistributionDataResponse> {
+    let distributions = DISTRIBUTION
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(_, distribution)| distribution))
+        .collect::<StdResult<_>>()?;
+    let cw20_distributions = CW20_DISTRIBUTION
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(_, distribution)| distribution))
+        .collect::<StdResult<_>>()?;
+    Ok(DistributionDataResponse {
+        distributions,
+        cw20_distributions,
+    })
+}
+
+fn query_withdraw_adjustment_data<Q: CustomQuery>(
+    deps: Deps<Q>,
+    addr: String,
+) -> StdResult<WithdrawAdjustmentDataResponse> {
+    let addr = deps.api.addr_validate(&addr)?;
+    let adjustments = WITHDRAW_ADJUSTMENT
+        .prefix(&addr)
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(_, adjustment)| adjustment))
+        .collect::<StdResult<_>>()?;
+    let cw20_adjustments = CW20_WITHDRAW_ADJUSTMENT
+        .prefix(&addr)
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(_, adjustment)| adjustment))
+        .collect::<StdResult<_>>()?;
+    let delegated = DELEGATED.may_load(deps.storage, &addr)?.unwrap_or(addr);
+    Ok(WithdrawAdjustmentDataResponse {
+        delegated,
+        adjustments,
+        cw20_adjustments,
+    })
+}
+
 fn query_halflife<Q: CustomQuery>(deps: Deps<Q>) -> StdResult<HalflifeResponse> {
     let Halflife {
         halflife,
         last_applied: last_halflife,
+        reduction,
+        mode,
+        batch_size: _,
     } = HALFLIFE.load(deps.storage)?;
 
     Ok(HalflifeResponse {
@@ -846,17 +1761,201 @@ fn query_halflife<Q: CustomQuery>(deps: Deps<Q>) -> StdResult<HalflifeResponse>
                 last_halflife,
                 halflife: d,
                 next_halflife,
+                reduction,
+                mode,
             }
         }),
     })
 }
 
+fn query_halflife_progress<Q: CustomQuery>(deps: Deps<Q>) -> StdResult<HalflifeProgressResponse> {
+    let job = HALFLIFE_JOB.may_load(deps.storage)?;
+    Ok(HalflifeProgressResponse {
+        pending: job.is_some(),
+        start_after: job
+            .as_ref()
+            .and_then(|job| job.start_after.as_ref())
+            .map(Addr::to_string),
+        processed: job.as_ref().map(|job| job.processed).unwrap_or_default(),
+        reduction: job.map(|job| job.reduction).unwrap_or_default(),
+    })
+}
+
 // settings for pagination
 const MAX_LIMIT: u32 = 100;
 const DEFAULT_LIMIT: u32 = 30;
 
+fn query_auto_withdraw<Q: CustomQuery>(
+    deps: Deps<Q>,
+    addr: String,
+) -> StdResult<AutoWithdrawResponse> {
+    let addr = deps.api.addr_validate(&addr)?;
+    let auto_withdraw = AUTO_WITHDRAWALS.may_load(deps.storage, &addr)?;
+    Ok(AutoWithdrawResponse { auto_withdraw })
+}
+
+fn list_distributions<Q: CustomQuery>(
+    deps: Deps<Q>,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<DistributionsResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+
+    let distributions = DISTRIBUTION_HISTORY
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(_, record)| record))
+        .collect::<StdResult<_>>()?;
+
+    Ok(DistributionsResponse { distributions })
+}
+
+/// Derives rewards accrued by the member from the distribution history, from the shares per point
+/// of every distribution and the member points at the beginning of the distribution block.
+/// Amounts are rounded down, so they may differ slightly from what was actually assigned for
+/// withdrawal. At most `limit` distributions are scanned.
+fn query_member_reward_history<Q: CustomQuery>(
+    deps: Deps<Q>,
+    addr: String,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<MemberRewardHistoryResponse> {
+    let addr = deps.api.addr_validate(&addr)?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+
+    let records = DISTRIBUTION_HISTORY
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .collect::<StdResult<Vec<_>>>()?;
+    let last_scanned = records.last().map(|(id, _)| *id);
+
+    let mut rewards = vec![];
+    for (_, record) in records {
+        let points = members()
+            .may_load_at_height(deps.storage, &addr, record.height)?
+            .map(|mi| mi.points)
+            .unwrap_or_default();
+        if points == 0 {
+            continue;
+        }
+        let total_points = record.total_points;
+        // Older records without shares per point only know the total points
+        let share = |amount: Uint128, shares: Option<&Uint128>| match shares {
+            Some(shares) => Uint128::new((shares.u128() * points as u128) >> SHARES_SHIFT),
+            None => amount.multiply_ratio(points, total_points),
+        };
+        let shares_per_point = record.shares_per_point;
+        let cw20_shares_per_point = record.cw20_shares_per_point;
+        rewards.push(MemberReward {
+            distribution: record.id,
+            height: record.height,
+            time: record.time,
+            points,
+            total_points,
+            rewards: record
+                .rewards
+                .into_iter()
+                .enumerate()
+                .map(|(i, c)| {
+                    let amount = share(c.amount, shares_per_point.get(i));
+                    coin(amount.u128(), c.denom)
+                })
+                .collect(),
+            cw20_rewards: record
+                .cw20_rewards
+                .into_iter()
+                .enumerate()
+                .map(|(i, c)| Cw20Coin {
+                    amount: share(c.amount, cw20_shares_per_point.get(i)),
+                    address: c.address,
+                })
+                .collect(),
+        });
+    }
+
+    Ok(MemberRewardHistoryResponse {
+        rewards,
+        last_scanned,
+    })
+}
+
+fn issuer_response(
+    storage: &dyn Storage,
+    env: &Env,
+    addr: Addr,
+    issuer: Issuer,
+) -> StdResult<IssuerResponse> {
+    let issued = ISSUED
+        .may_load(storage, &addr)?
+        .unwrap_or_default()
+        .in_period(issuer.period, env.block.time);
+    Ok(IssuerResponse {
+        remaining: issuer.cap.saturating_sub(issued),
+        addr,
+        issuer,
+        issued,
+    })
+}
+
+fn query_issuer<Q: CustomQuery>(
+    deps: Deps<Q>,
+    env: &Env,
+    addr: String,
+) -> StdResult<IssuerResponse> {
+    let addr = deps.api.addr_validate(&addr)?;
+    let issuer = ISSUERS.load(deps.storage, &addr)?;
+    issuer_response(deps.storage, env, addr, issuer)
+}
+
+fn list_issuers<Q: CustomQuery>(
+    deps: Deps<Q>,
+    env: &Env,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<IssuersResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let addr = maybe_addr(deps.api, start_after)?;
+    let start = addr.as_ref().map(Bound::exclusive);
+
+    let issuers = ISSUERS
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            let (addr, issuer) = item?;
+            issuer_response(deps.storage, env, addr, issuer)
+        })
+        .collect::<StdResult<_>>()?;
+
+    Ok(IssuersResponse { issuers })
+}
+
+fn list_issuances<Q: CustomQuery>(
+    deps: Deps<Q>,
+    issuer: String,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<IssuancesResponse> {
+    let issuer = deps.api.addr_validate(&issuer)?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+
+    let issuances = issuances()
+        .idx
+        .issuer
+        .prefix(issuer)
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(_, issuance)| issuance))
+        .collect::<StdResult<_>>()?;
+
+    Ok(IssuancesResponse { issuances })
+}
+
 fn list_members<Q: CustomQuery>(
     deps: Deps<Q>,
+    env: &Env,
     start_after: Option<String>,
     limit: Option<u32>,
 ) -> StdResult<MemberListResponse> {
@@ -864,6 +1963,7 @@ fn list_members<Q: CustomQuery>(
     let addr = maybe_addr(deps.api, start_after)?;
     let start = addr.as_ref().map(Bound::exclusive);
 
+    let halflife = HALFLIFE.load(deps.storage)?;
     let members: StdResult<Vec<_>> = members()
         .range(deps.storage, start, None, Order::Ascending)
         .take(limit)
@@ -875,6 +1975,7 @@ fn list_members<Q: CustomQuery>(
                     start_height,
                 },
             ) = item?;
+            let points = decayed_points(deps, &halflife, env.block.time, &addr, points)?;
             Ok(Member {
                 addr: addr.into(),
                 points,
@@ -886,8 +1987,11 @@ fn list_members<Q: CustomQuery>(
     Ok(MemberListResponse { members: members? })
 }
 
+/// Members are ordered by their stored points, the pending continuous decay is not accounted
+/// for in the ordering.
 fn list_members_by_points<Q: CustomQuery>(
     deps: Deps<Q>,
+    env: &Env,
     start_after: Option<Member>,
     limit: Option<u32>,
 ) -> StdResult<MemberListResponse> {
@@ -899,6 +2003,7 @@ fn list_members_by_points<Q: CustomQuery>(
                 .map(|addr| Bound::exclusive((m.points, addr)))
         })
         .transpose()?;
+    let halflife = HALFLIFE.load(deps.storage)?;
     let members: StdResult<Vec<_>> = members()
         .idx
         .points
@@ -912,6 +2017,7 @@ fn list_members_by_points<Q: CustomQuery>(
                     start_height,
                 },
             ) = item?;
+            let points = decayed_points(deps, &halflife, env.block.time, &addr, points)?;
             Ok(Member {
                 addr: addr.into(),
                 points,
@@ -931,7 +2037,8 @@ pub fn migrate(
 ) -> Result<Response, ContractError> {
     let stored_version = ensure_from_older_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
 
-    migrate_config(deps.branch(), msg)?;
+    migrate_config(deps.branch(), &env, msg)?;
+    migrate_distribution(deps.branch())?;
 
     let mut resp = Response::new();
 
@@ -957,6 +2064,7 @@ mod tests {
 
     use cosmwasm_std::testing::{mock_env, mock_info};
     use cosmwasm_std::{from_slice, Api, OwnedDeps, Querier, StdError, Storage};
+    use cw20::Cw20Coin;
     use cw_controllers::AdminError;
     use cw_storage_plus::Map;
     use tg4::{member_key, TOTAL_KEY};
@@ -995,10 +2103,32 @@ mod tests {
             preauths_hooks: 1,
             preauths_slashing: 0,
             halflife: Some(Duration::new(HALFLIFE)),
+            halflife_reduction: None,
+            decay_mode: None,
+            halflife_batch: None,
             denom: "usdc".to_owned(),
         };
         let info = mock_info("creator", &[]);
-        instantiate(deps, mock_env(), info, msg).unwrap();
+        let mut deps = deps;
+        instantiate(deps.branch(), mock_env(), info, msg).unwrap();
+        // cw20 rewards in tests are sent by the "token" contract
+        execute_add_reward_token(deps, mock_info(INIT_ADMIN, &[]), "token".to_owned()).unwrap();
+    }
+
+    /// Sends `amount` of the "token" cw20 rewards to the contract, as if transferred by "partner"
+    fn receive_tokens(deps: DepsMut<TgradeQuery>, env: Env, amount: u128, msg: Binary) -> Response {
+        let wrapper = Cw20ReceiveMsg {
+            sender: "partner".to_owned(),
+            amount: Uint128::new(amount),
+            msg,
+        };
+        execute(
+            deps,
+            env,
+            mock_info("token", &[]),
+            ExecuteMsg::Receive(wrapper),
+        )
+        .unwrap()
     }
 
     #[test]
@@ -1017,17 +2147,8 @@ mod tests {
         assert_eq!(1, preauths);
 
         let raw = query(deps.as_ref(), mock_env(), QueryMsg::DistributionData {}).unwrap();
-        let res: Distribution = from_slice(&raw).unwrap();
-        assert_eq!(
-            res,
-            Distribution {
-                denom: "usdc".to_owned(),
-                shares_per_point: Uint128::zero(),
-                shares_leftover: 0,
-                distributed_total: Uint128::zero(),
-                withdrawable_total: Uint128::zero(),
-            }
-        );
+        let res: DistributionDataResponse = from_slice(&raw).unwrap();
+        assert_eq!(res.distributions, vec![Distribution::new("usdc")]);
 
         let raw = query(
             deps.as_ref(),
@@ -1037,13 +2158,13 @@ mod tests {
             },
         )
         .unwrap();
-        let res: WithdrawAdjustment = from_slice(&raw).unwrap();
+        let res: WithdrawAdjustmentDataResponse = from_slice(&raw).unwrap();
         assert_eq!(
             res,
-            WithdrawAdjustment {
-                shares_correction: Int128::zero(),
-                withdrawn_rewards: Uint128::zero(),
-                delegated: Addr::unchecked("user1"),
+            WithdrawAdjustmentDataResponse {
+                delegated: Addr::unchecked(USER1),
+                adjustments: vec![],
+                cw20_adjustments: vec![],
             }
         );
     }
@@ -1053,19 +2174,21 @@ mod tests {
         let mut deps = mock_deps_tgrade();
         do_instantiate(deps.as_mut());
 
-        let member1 = query_member(deps.as_ref(), USER1.into(), None).unwrap();
+        let member1 = query_member(deps.as_ref(), &mock_env(), USER1.into(), None).unwrap();
         assert_eq!(member1.points, Some(11));
 
-        let member2 = query_member(deps.as_ref(), USER2.into(), None).unwrap();
+        let member2 = query_member(deps.as_ref(), &mock_env(), USER2.into(), None).unwrap();
         assert_eq!(member2.points, Some(6));
 
-        let member3 = query_member(deps.as_ref(), USER3.into(), None).unwrap();
+        let member3 = query_member(deps.as_ref(), &mock_env(), USER3.into(), None).unwrap();
         assert_eq!(member3.points, None);
 
-        let members = list_members(deps.as_ref(), None, None).unwrap();
+        let members = list_members(deps.as_ref(), &mock_env(), None, None).unwrap();
         assert_eq!(members.members.len(), 2);
         // assert the set is proper
-        let members = list_members(deps.as_ref(), None, None).unwrap().members;
+        let members = list_members(deps.as_ref(), &mock_env(), None, None)
+            .unwrap()
+            .members;
         assert_eq!(members.len(), 2);
         // Assert the set is proper
         assert_eq!(
@@ -1085,7 +2208,9 @@ mod tests {
         );
 
         // Test pagination / limits
-        let members = list_members(deps.as_ref(), None, Some(1)).unwrap().members;
+        let members = list_members(deps.as_ref(), &mock_env(), None, Some(1))
+            .unwrap()
+            .members;
         assert_eq!(members.len(), 1);
         // Assert the set is proper
         assert_eq!(
@@ -1099,7 +2224,7 @@ mod tests {
 
         // Next page
         let start_after = Some(members[0].addr.clone());
-        let members = list_members(deps.as_ref(), start_after, Some(1))
+        let members = list_members(deps.as_ref(), &mock_env(), start_after, Some(1))
             .unwrap()
             .members;
         assert_eq!(members.len(), 1);
@@ -1115,7 +2240,7 @@ mod tests {
 
         // Assert there's no more
         let start_after = Some(members[0].addr.clone());
-        let members = list_members(deps.as_ref(), start_after, Some(1))
+        let members = list_members(deps.as_ref(), &mock_env(), start_after, Some(1))
             .unwrap()
             .members;
         assert_eq!(members.len(), 0);
@@ -1126,7 +2251,7 @@ mod tests {
         let mut deps = mock_deps_tgrade();
         do_instantiate(deps.as_mut());
 
-        let members = list_members_by_points(deps.as_ref(), None, None)
+        let members = list_members_by_points(deps.as_ref(), &mock_env(), None, None)
             .unwrap()
             .members;
         assert_eq!(members.len(), 2);
@@ -1148,7 +2273,7 @@ mod tests {
         );
 
         // Test pagination / limits
-        let members = list_members_by_points(deps.as_ref(), None, Some(1))
+        let members = list_members_by_points(deps.as_ref(), &mock_env(), None, Some(1))
             .unwrap()
             .members;
         assert_eq!(members.len(), 1);
@@ -1164,7 +2289,7 @@ mod tests {
 
         // Next page
         let start_after = Some(members[0].clone());
-        let members = list_members_by_points(deps.as_ref(), start_after, None)
+        let members = list_members_by_points(deps.as_ref(), &mock_env(), start_after, None)
             .unwrap()
             .members;
         assert_eq!(members.len(), 1);
@@ -1180,7 +2305,7 @@ mod tests {
 
         // Assert there's no more
         let start_after = Some(members[0].clone());
-        let members = list_members_by_points(deps.as_ref(), start_after, Some(1))
+        let members = list_members_by_points(deps.as_ref(), &mock_env(), start_after, Some(1))
             .unwrap()
             .members;
         assert_eq!(members.len(), 0);
@@ -1195,6 +2320,8 @@ mod tests {
             last_halflife,
             halflife,
             next_halflife,
+            reduction,
+            mode,
         } = query_halflife(deps.as_ref())
             .unwrap()
             .halflife_info
@@ -1210,6 +2337,10 @@ mod tests {
         // Next halflife event.
         let expected_next_halflife = last_halflife.plus_seconds(halflife.seconds());
         assert_eq!(expected_next_halflife, next_halflife);
+
+        // Halving by default.
+        assert_eq!(reduction, Decimal::percent(50));
+        assert_eq!(mode, DecayMode::Periodic);
     }
 
     #[test]
@@ -1232,6 +2363,9 @@ mod tests {
             preauths_hooks: 1,
             preauths_slashing: 0,
             halflife: None,
+            halflife_reduction: None,
+            decay_mode: None,
+            halflife_batch: None,
             denom: "usdc".to_owned(),
         };
         let info = mock_info("creator", &[]);
@@ -1247,7 +2381,9 @@ mod tests {
         do_instantiate(deps.as_mut());
 
         // make sure we get 2 members as expected, no error
-        let members = list_members(deps.as_ref(), None, None).unwrap().members;
+        let members = list_members(deps.as_ref(), &mock_env(), None, None)
+            .unwrap()
+            .members;
         assert_eq!(members.len(), 2);
 
         // we write some garbage non-utf8 key in the same key space as members, with some tricks
@@ -1257,7 +2393,7 @@ mod tests {
             .unwrap();
 
         // this should now error when trying to parse the invalid data (in the same keyspace)
-        let err = list_members(deps.as_ref(), None, None).unwrap_err();
+        let err = list_members(deps.as_ref(), &mock_env(), None, None).unwrap_err();
         assert!(matches!(err, StdError::InvalidUtf8 { .. }));
     }
 
@@ -1269,13 +2405,13 @@ mod tests {
         user3_points: Option<u64>,
         height: Option<u64>,
     ) {
-        let member1 = query_member(deps.as_ref(), USER1.into(), height).unwrap();
+        let member1 = query_member(deps.as_ref(), &mock_env(), USER1.into(), height).unwrap();
         assert_eq!(member1.points, user1_points);
 
-        let member2 = query_member(deps.as_ref(), USER2.into(), height).unwrap();
+        let member2 = query_member(deps.as_ref(), &mock_env(), USER2.into(), height).unwrap();
         assert_eq!(member2.points, user2_points);
 
-        let member3 = query_member(deps.as_ref(), USER3.into(), height).unwrap();
+        let member3 = query_member(deps.as_ref(), &mock_env(), USER3.into(), height).unwrap();
         assert_eq!(member3.points, user3_points);
 
         // this is only valid if we are not doing a historical query
@@ -1286,7 +2422,7 @@ mod tests {
             let count = points.iter().filter(|x| x.is_some()).count();
 
             // TODO: more detailed compare?
-            let members = list_members(deps.as_ref(), None, None).unwrap();
+            let members = list_members(deps.as_ref(), &mock_env(), None, None).unwrap();
             assert_eq!(count, members.members.len());
 
             let total = query_total_points(deps.as_ref()).unwrap();
@@ -1650,8 +2786,8 @@ mod tests {
 
         // end block at half life
         env.block.time = env.block.time.plus_seconds(HALFLIFE);
-        let expected_reduction_user1 = points_reduction(USER1_POINTS);
-        let expected_reduction_user2 = points_reduction(USER2_POINTS);
+        let expected_reduction_user1 = USER1_POINTS - USER1_POINTS / 2;
+        let expected_reduction_user2 = USER2_POINTS - USER2_POINTS / 2;
         let expected_reduction = expected_reduction_user1 + expected_reduction_user2;
         let evt = Event::new("halflife")
             .add_attribute("height", env.block.height.to_string())
@@ -1734,13 +2870,561 @@ mod tests {
                 .add_message(msg.into_cosmos_msg(contract1).unwrap());
         }
         assert_eq!(
-            migrate(deps.as_mut(), env, MigrateMsg { halflife: None }),
+            migrate(
+                deps.as_mut(),
+                env,
+                MigrateMsg {
+                    halflife: None,
+                    halflife_reduction: None,
+                    decay_mode: None,
+                    halflife_batch: None,
+                }
+            ),
             Ok(resp)
         );
     }
 
+    #[test]
+    fn migrate_single_denom_distribution() {
+        use crate::migration::{
+            LegacyWithdrawAdjustment, LEGACY_DISTRIBUTION, LEGACY_WITHDRAW_ADJUSTMENT,
+        };
+
+        let mut deps = mock_deps_tgrade();
+        do_instantiate(deps.as_mut());
+
+        // storage layout from before multiple denoms were supported
+        DISTRIBUTION.remove(&mut deps.storage, "usdc");
+        let distribution = Distribution {
+            shares_per_point: Uint128::new(100),
+            distributed_total: Uint128::new(1000),
+            ..Distribution::new("usdc")
+        };
+        LEGACY_DISTRIBUTION
+            .save(&mut deps.storage, &distribution)
+            .unwrap();
+        let user1 = Addr::unchecked(USER1);
+        let legacy = LegacyWithdrawAdjustment {
+            shares_correction: Int128::from(-50),
+            withdrawn_rewards: Uint128::new(7),
+            delegated: Addr::unchecked(USER3),
+        };
+        LEGACY_WITHDRAW_ADJUSTMENT
+            .save(&mut deps.storage, &user1, &legacy)
+            .unwrap();
+
+        let msg = MigrateMsg {
+            halflife: None,
+            halflife_reduction: None,
+            decay_mode: None,
+            halflife_batch: None,
+        };
+        migrate(deps.as_mut(), mock_env(), msg).unwrap();
+
+        assert_eq!(
+            query_distribution_data(deps.as_ref())
+                .unwrap()
+                .distributions,
+            vec![distribution]
+        );
+        assert_eq!(
+            query_withdraw_adjustment_data(deps.as_ref(), USER1.to_owned()).unwrap(),
+            WithdrawAdjustmentDataResponse {
+                delegated: Addr::unchecked(USER3),
+                adjustments: vec![WithdrawAdjustment {
+                    denom: "usdc".to_owned(),
+                    shares_correction: Int128::from(-50),
+                    withdrawn_rewards: Uint128::new(7),
+                }],
+                cw20_adjustments: vec![],
+            }
+        );
+        assert!(LEGACY_DISTRIBUTION
+            .may_load(&deps.storage)
+            .unwrap()
+            .is_none());
+        assert!(LEGACY_WITHDRAW_ADJUSTMENT.is_empty(&deps.storage));
+    }
+
+    mod cw20_rewards {
+        use super::*;
+        use cosmwasm_std::coins;
+        use cosmwasm_std::testing::MOCK_CONTRACT_ADDR;
+
+        fn cw20_rewards(deps: Deps<TgradeQuery>, owner: &str) -> Vec<Cw20Coin> {
+            query_withdrawable_rewards(deps, owner.to_owned())
+                .unwrap()
+                .cw20_rewards
+        }
+
+        #[test]
+        fn received_tokens_are_distributed() {
+            let mut deps = mock_deps_tgrade();
+            do_instantiate(deps.as_mut());
+
+            let res = receive_tokens(deps.as_mut(), mock_env(), 170, Binary::default());
+            assert_eq!(res.attributes[1], ("sender", "partner"));
+            assert_eq!(
+                cw20_rewards(deps.as_ref(), USER1),
+                vec![Cw20Coin {
+                    address: "token".to_owned(),
+                    amount: Uint128::new(110),
+                }]
+            );
+            assert_eq!(
+                cw20_rewards(deps.as_ref(), USER2),
+                vec![Cw20Coin {
+                    address: "token".to_owned(),
+                    amount: Uint128::new(60),
+                }]
+            );
+            // native rewards are not affected
+            let rewards = query_withdrawable_rewards(deps.as_ref(), USER1.to_owned()).unwrap();
+            assert_eq!(rewards.rewards, vec![coin(0, "usdc")]);
+
+            // declared sender can be overwritten
+            let msg = to_binary(&ReceiveMsg::DistributeRewards {
+                sender: Some("dao".to_owned()),
+            })
+            .unwrap();
+            let res = receive_tokens(deps.as_mut(), mock_env(), 17, msg);
+            assert_eq!(res.attributes[1], ("sender", "dao"));
+            assert_eq!(
+                query_distributed_rewards(deps.as_ref())
+                    .unwrap()
+                    .cw20_rewards,
+                vec![Cw20Coin {
+                    address: "token".to_owned(),
+                    amount: Uint128::new(187),
+                }]
+            );
+        }
+
+        #[test]
+        fn withdrawal_transfers_tokens() {
+            let mut deps = mock_deps_tgrade();
+            do_instantiate(deps.as_mut());
+            receive_tokens(deps.as_mut(), mock_env(), 170, Binary::default());
+
+            let res = execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info(USER1, &[]),
+                ExecuteMsg::WithdrawRewards {
+                    owner: None,
+                    receiver: Some(USER3.to_owned()),
+                    denom: None,
+                },
+            )
+            .unwrap();
+            assert_eq!(
+                res.messages,
+                vec![SubMsg::new(WasmMsg::Execute {
+                    contract_addr: "token".to_owned(),
+                    msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                        recipient: USER3.to_owned(),
+                        amount: Uint128::new(110),
+                    })
+                    .unwrap(),
+                    funds: vec![],
+                })]
+            );
+            assert_eq!(
+                cw20_rewards(deps.as_ref(), USER1),
+                vec![Cw20Coin {
+                    address: "token".to_owned(),
+                    amount: Uint128::zero(),
+                }]
+            );
+
+            // points change after the distribution doesn't affect it
+            execute_add_points(
+                deps.as_mut(),
+                mock_env(),
+                mock_info(INIT_ADMIN, &[]),
+                USER2.to_owned(),
+                100,
+                None,
+            )
+            .unwrap();
+            assert_eq!(
+                cw20_rewards(deps.as_ref(), USER2),
+                vec![Cw20Coin {
+                    address: "token".to_owned(),
+                    amount: Uint128::new(60),
+                }]
+            );
+        }
+
+        #[test]
+        fn only_reward_tokens_accepted() {
+            let mut deps = mock_deps_tgrade();
+            do_instantiate(deps.as_mut());
+
+            let wrapper = Cw20ReceiveMsg {
+                sender: "partner".to_owned(),
+                amount: Uint128::new(170),
+                msg: Binary::default(),
+            };
+            let err = execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info("fake", &[]),
+                ExecuteMsg::Receive(wrapper.clone()),
+            )
+            .unwrap_err();
+            assert_eq!(ContractError::UnknownRewardToken("fake".to_owned()), err);
+
+            // only the admin can add reward tokens
+            let msg = ExecuteMsg::AddRewardToken {
+                token: "fake".to_owned(),
+            };
+            let err = execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info(USER1, &[]),
+                msg.clone(),
+            )
+            .unwrap_err();
+            assert_eq!(ContractError::Admin(AdminError::NotAdmin {}), err);
+
+            execute(deps.as_mut(), mock_env(), mock_info(INIT_ADMIN, &[]), msg).unwrap();
+            execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info("fake", &[]),
+                ExecuteMsg::Receive(wrapper),
+            )
+            .unwrap();
+            assert_eq!(cw20_rewards(deps.as_ref(), USER1).len(), 2);
+        }
+
+        #[test]
+        fn withdrawal_of_single_token() {
+            let mut deps = mock_deps_tgrade();
+            do_instantiate(deps.as_mut());
+            deps.querier
+                .update_balance(MOCK_CONTRACT_ADDR, vec![coin(170, "usdc")]);
+            execute_distribute_rewards(deps.as_mut(), mock_env(), mock_info("dao", &[]), None)
+                .unwrap();
+            receive_tokens(deps.as_mut(), mock_env(), 170, Binary::default());
+
+            // native rewards are withdrawn without touching the token
+            let res = execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info(USER1, &[]),
+                ExecuteMsg::WithdrawRewards {
+                    owner: None,
+                    receiver: None,
+                    denom: Some(Denom::Native("usdc".to_owned())),
+                },
+            )
+            .unwrap();
+            assert_eq!(
+                res.messages,
+                vec![SubMsg::new(BankMsg::Send {
+                    to_address: USER1.to_owned(),
+                    amount: coins(110, "usdc"),
+                })]
+            );
+            assert_eq!(
+                cw20_rewards(deps.as_ref(), USER1),
+                vec![Cw20Coin {
+                    address: "token".to_owned(),
+                    amount: Uint128::new(110),
+                }]
+            );
+        }
+    }
+
+    mod decay {
+        use super::*;
+
+        fn set_decay(deps: DepsMut<TgradeQuery>, reduction: Decimal, mode: DecayMode) {
+            let msg = MigrateMsg {
+                halflife: None,
+                halflife_reduction: Some(reduction),
+                decay_mode: Some(mode),
+                halflife_batch: None,
+            };
+            migrate(deps, mock_env(), msg).unwrap();
+        }
+
+        #[test]
+        fn configurable_reduction() {
+            let mut deps = mock_deps_tgrade();
+            do_instantiate(deps.as_mut());
+            set_decay(deps.as_mut(), Decimal::percent(10), DecayMode::Periodic);
+
+            let info = query_halflife(deps.as_ref())
+                .unwrap()
+                .halflife_info
+                .unwrap();
+            assert_eq!(info.reduction, Decimal::percent(10));
+            assert_eq!(info.mode, DecayMode::Periodic);
+
+            let mut env = mock_env();
+            env.block.time = env.block.time.plus_seconds(HALFLIFE);
+            end_block(deps.as_mut(), env).unwrap();
+            // 11 - ceil(1.1), 6 - ceil(0.6)
+            assert_users(&deps, Some(9), Some(5), None, None);
+        }
+
+        #[test]
+        fn halflife_in_batches() {
+            let mut deps = mock_deps_tgrade();
+            do_instantiate(deps.as_mut());
+            let msg = MigrateMsg {
+                halflife: None,
+                halflife_reduction: None,
+                decay_mode: None,
+                halflife_batch: Some(1),
+            };
+            migrate(deps.as_mut(), mock_env(), msg).unwrap();
+            let hook = String::from("hook1");
+            execute_add_hook(deps.as_mut(), mock_info(INIT_ADMIN, &[]), hook.clone()).unwrap();
+
+            let mut env = mock_env();
+            env.block.time = env.block.time.plus_seconds(HALFLIFE);
+            env.block.height += 1;
+
+            // first batch reduces only the first member
+            let res = end_block(deps.as_mut(), env.clone()).unwrap();
+            let msg = MemberChangedHookMsg::one(MemberDiff::new(USER1, Some(11), Some(5)));
+            assert_eq!(
+                res.messages,
+                vec![SubMsg::new(msg.into_cosmos_msg(hook.clone()).unwrap())]
+            );
+            assert!(res.events.is_empty());
+            assert_users(&deps, Some(5), Some(USER2_POINTS), None, None);
+            assert_eq!(
+                query_halflife_progress(deps.as_ref()).unwrap(),
+                HalflifeProgressResponse {
+                    pending: true,
+                    start_after: Some(USER1.to_owned()),
+                    processed: 1,
+                    reduction: 6,
+                }
+            );
+            // next period is counted from the start of this one
+            let info = query_halflife(deps.as_ref())
+                .unwrap()
+                .halflife_info
+                .unwrap();
+            assert_eq!(info.last_halflife, env.block.time);
+
+            // next block finishes the job
+            env.block.height += 1;
+            env.block.time = env.block.time.plus_seconds(5);
+            let res = end_block(deps.as_mut(), env.clone()).unwrap();
+            let msg = MemberChangedHookMsg::one(MemberDiff::new(USER2, Some(6), Some(3)));
+            assert_eq!(
+                res.messages,
+                vec![SubMsg::new(msg.into_cosmos_msg(hook).unwrap())]
+            );
+            let evt = Event::new("halflife")
+                .add_attribute("height", env.block.height.to_string())
+                .add_attribute("reduction", "9");
+            assert_eq!(res.events, vec![evt]);
+            assert_users(&deps, Some(5), Some(3), None, None);
+            assert!(!query_halflife_progress(deps.as_ref()).unwrap().pending);
+
+            // and nothing happens until the next period
+            env.block.height += 1;
+            assert_eq!(end_block(deps.as_mut(), env), Ok(Response::new()));
+        }
+
+        #[test]
+        fn pending_halflife_reduces_only_points_held_at_start() {
+            let mut deps = mock_deps_tgrade();
+            let msg = InstantiateMsg {
+                admin: Some(INIT_ADMIN.into()),
+                members: vec![
+                    Member {
+                        addr: USER1.into(),
+                        points: USER1_POINTS,
+                        start_height: None,
+                    },
+                    Member {
+                        addr: USER2.into(),
+                        points: USER2_POINTS,
+                        start_height: None,
+                    },
+                ],
+                preauths_hooks: 0,
+                preauths_slashing: 0,
+                halflife: Some(Duration::new(HALFLIFE)),
+                halflife_reduction: None,
+                decay_mode: None,
+                halflife_batch: Some(1),
+                denom: "usdc".to_owned(),
+            };
+            instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+            let mut env = mock_env();
+            env.block.time = env.block.time.plus_seconds(HALFLIFE);
+            env.block.height += 1;
+            end_block(deps.as_mut(), env.clone()).unwrap();
+            assert_users(&deps, Some(5), Some(USER2_POINTS), None, None);
+
+            // points awarded while the halflife is pending are not reduced by it, the ones held
+            // before still are
+            env.block.height += 1;
+            execute_add_points(
+                deps.as_mut(),
+                env.clone(),
+                mock_info(INIT_ADMIN, &[]),
+                USER2.into(),
+                4,
+                None,
+            )
+            .unwrap();
+            let res = end_block(deps.as_mut(), env.clone()).unwrap();
+            assert!(res.messages.is_empty());
+            let evt = Event::new("halflife")
+                .add_attribute("height", env.block.height.to_string())
+                .add_attribute("reduction", "9");
+            assert_eq!(res.events, vec![evt]);
+            assert_users(&deps, Some(5), Some(7), None, None);
+        }
+
+        #[test]
+        fn instantiate_validates_halflife_batch() {
+            let mut deps = mock_deps_tgrade();
+            let msg = InstantiateMsg {
+                admin: None,
+                members: vec![],
+                preauths_hooks: 0,
+                preauths_slashing: 0,
+                halflife: Some(Duration::new(HALFLIFE)),
+                halflife_reduction: None,
+                decay_mode: None,
+                halflife_batch: Some(0),
+                denom: "usdc".to_owned(),
+            };
+            let err =
+                instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap_err();
+            assert_eq!(ContractError::InvalidHalflifeBatch {}, err);
+        }
+
+        #[test]
+        fn invalid_reduction() {
+            let mut deps = mock_deps_tgrade();
+            do_instantiate(deps.as_mut());
+
+            for reduction in [Decimal::zero(), Decimal::percent(101)] {
+                let msg = MigrateMsg {
+                    halflife: None,
+                    halflife_reduction: Some(reduction),
+                    decay_mode: None,
+                    halflife_batch: None,
+                };
+                let err = migrate(deps.as_mut(), mock_env(), msg).unwrap_err();
+                assert_eq!(err, ContractError::InvalidReduction(reduction));
+            }
+        }
+
+        #[test]
+        fn continuous_decay_is_lazy() {
+            let mut deps = mock_deps_tgrade();
+            do_instantiate(deps.as_mut());
+            set_decay(deps.as_mut(), Decimal::percent(50), DecayMode::Continuous);
+
+            let admin_info = mock_info(INIT_ADMIN, &[]);
+            let hook = String::from("hook1");
+            execute_add_hook(deps.as_mut(), admin_info.clone(), hook.clone()).unwrap();
+
+            let mut env = mock_env();
+            env.block.time = env.block.time.plus_seconds(HALFLIFE);
+            env.block.height += 1;
+
+            // end block doesn't touch anything
+            assert_eq!(end_block(deps.as_mut(), env.clone()), Ok(Response::new()));
+            assert_eq!(query_total_points(deps.as_ref()).unwrap().points, 17);
+
+            // but reads account for the elapsed time
+            let member = query_member(deps.as_ref(), &env, USER1.into(), None).unwrap();
+            assert_eq!(member.points, Some(5));
+            let members = list_members(deps.as_ref(), &env, None, None).unwrap();
+            assert_eq!(
+                members.members.iter().map(|m| m.points).collect::<Vec<_>>(),
+                vec![5, 3]
+            );
+
+            // touching the member settles the decay and reports it to hooks
+            let res = execute_add_points(
+                deps.as_mut(),
+                env.clone(),
+                admin_info,
+                USER1.into(),
+                10,
+                None,
+            )
+            .unwrap();
+            let msg = MemberChangedHookMsg::new(vec![
+                MemberDiff::new(USER1, Some(11), Some(5)),
+                MemberDiff::new(USER1, Some(5), Some(15)),
+            ]);
+            assert_eq!(
+                res.messages,
+                vec![SubMsg::new(msg.into_cosmos_msg(hook).unwrap())]
+            );
+            assert_eq!(query_total_points(deps.as_ref()).unwrap().points, 21);
+
+            // fresh points start decaying from now on
+            let member = query_member(deps.as_ref(), &env, USER1.into(), None).unwrap();
+            assert_eq!(member.points, Some(15));
+            env.block.time = env.block.time.plus_seconds(HALFLIFE / 2);
+            let member = query_member(deps.as_ref(), &env, USER1.into(), None).unwrap();
+            assert_eq!(member.points, Some(11));
+        }
+
+        #[test]
+        fn frequent_additions_do_not_escape_decay() {
+            let mut deps = mock_deps_tgrade();
+            do_instantiate(deps.as_mut());
+            set_decay(deps.as_mut(), Decimal::percent(10), DecayMode::Continuous);
+
+            let admin_info = mock_info(INIT_ADMIN, &[]);
+            let mut env = mock_env();
+            execute_add_points(
+                deps.as_mut(),
+                env.clone(),
+                admin_info.clone(),
+                USER3.into(),
+                5,
+                None,
+            )
+            .unwrap();
+
+            // every addition within the period is too early to remove a whole point
+            for _ in 0..4 {
+                env.block.time = env.block.time.plus_seconds(HALFLIFE / 5);
+                env.block.height += 1;
+                execute_add_points(
+                    deps.as_mut(),
+                    env.clone(),
+                    admin_info.clone(),
+                    USER3.into(),
+                    1,
+                    None,
+                )
+                .unwrap();
+            }
+            let member = query_member(deps.as_ref(), &env, USER3.into(), None).unwrap();
+            assert_eq!(member.points, Some(9));
+
+            // but the period keeps running since the first addition
+            env.block.time = env.block.time.plus_seconds(HALFLIFE / 5);
+            let member = query_member(deps.as_ref(), &env, USER3.into(), None).unwrap();
+            assert_eq!(member.points, Some(8));
+        }
+    }
+
     mod points {
         use super::*;
+        use cosmwasm_std::{OverflowError, OverflowOperation};
 
         #[test]
         fn add_to_existing_member() {
@@ -1751,7 +3435,7 @@ mod tests {
             let info = mock_info(INIT_ADMIN, &[]);
 
             // Originally USER1 has 11 points of points
-            execute_add_points(deps.as_mut(), env, info, "user1".to_string(), 10).unwrap();
+            execute_add_points(deps.as_mut(), env, info, "user1".to_string(), 10, None).unwrap();
             assert_users(&deps, Some(21), Some(6), None, None);
         }
 
@@ -1764,10 +3448,1026 @@ mod tests {
             let info = mock_info(INIT_ADMIN, &[]);
 
             let new_user = "user111".to_owned();
-            execute_add_points(deps.as_mut(), env, info, new_user.clone(), 10).unwrap();
-            let new_member = query_member(deps.as_ref(), new_user, None).unwrap();
+            execute_add_points(deps.as_mut(), env, info, new_user.clone(), 10, None).unwrap();
+            let new_member = query_member(deps.as_ref(), &mock_env(), new_user, None).unwrap();
             assert_eq!(new_member.points, Some(10));
         }
+
+        #[test]
+        fn add_batch() {
+            let mut deps = mock_deps_tgrade();
+            do_instantiate(deps.as_mut());
+            let admin_info = mock_info(INIT_ADMIN, &[]);
+            let hook = String::from("hook1");
+            execute_add_hook(deps.as_mut(), admin_info.clone(), hook.clone()).unwrap();
+
+            let points = vec![
+                (USER1.to_owned(), 5),
+                (USER3.to_owned(), 4),
+                (USER1.to_owned(), 2),
+            ];
+
+            let err = execute_add_points_batch(
+                deps.as_mut(),
+                mock_env(),
+                mock_info(USER1, &[]),
+                points.clone(),
+            )
+            .unwrap_err();
+            assert_eq!(
+                err,
+                ContractError::Unauthorized("Sender is neither admin nor issuer".to_owned())
+            );
+
+            let res =
+                execute_add_points_batch(deps.as_mut(), mock_env(), admin_info, points).unwrap();
+            assert_users(&deps, Some(18), Some(USER2_POINTS), Some(4), None);
+
+            // duplicates are merged, and all changes go in a single hook message
+            let msg = MemberChangedHookMsg::new(vec![
+                MemberDiff::new(USER1, Some(11), Some(18)),
+                MemberDiff::new(USER3, None, Some(4)),
+            ]);
+            assert_eq!(
+                res.messages,
+                vec![SubMsg::new(msg.into_cosmos_msg(hook).unwrap())]
+            );
+        }
+
+        #[test]
+        fn add_points_batch_overflow() {
+            let mut deps = mock_deps_tgrade();
+            do_instantiate(deps.as_mut());
+            let admin_info = mock_info(INIT_ADMIN, &[]);
+
+            let overflow = |a: u64, b: u64| {
+                ContractError::Std(StdError::overflow(OverflowError::new(
+                    OverflowOperation::Add,
+                    a,
+                    b,
+                )))
+            };
+
+            // repeated address
+            let points = vec![(USER3.to_owned(), u64::MAX), (USER3.to_owned(), 1)];
+            let err =
+                execute_add_points_batch(deps.as_mut(), mock_env(), admin_info.clone(), points)
+                    .unwrap_err();
+            assert_eq!(err, overflow(u64::MAX, 1));
+
+            // total of the batch
+            let points = vec![(USER1.to_owned(), u64::MAX), (USER3.to_owned(), 1)];
+            let err =
+                execute_add_points_batch(deps.as_mut(), mock_env(), admin_info.clone(), points)
+                    .unwrap_err();
+            assert_eq!(err, overflow(u64::MAX, 1));
+
+            // points of an existing member
+            let points = vec![(USER1.to_owned(), u64::MAX)];
+            let err = execute_add_points_batch(deps.as_mut(), mock_env(), admin_info, points)
+                .unwrap_err();
+            assert_eq!(err, overflow(USER1_POINTS, u64::MAX));
+            assert_users(&deps, Some(USER1_POINTS), Some(USER2_POINTS), None, None);
+        }
+    }
+
+    mod export_import {
+        use super::*;
+        use crate::state::EngagementState;
+        use cosmwasm_std::testing::MOCK_CONTRACT_ADDR;
+        use tg_bindings_test::TgradeDeps;
+
+        fn export_state(deps: DepsMut<TgradeQuery>) -> EngagementState {
+            let res = sudo(deps, mock_env(), SudoMsg::Export {}).unwrap();
+            from_slice(&res.data.unwrap()).unwrap()
+        }
+
+        fn setup_state(deps: &mut TgradeDeps) {
+            do_instantiate(deps.as_mut());
+            let admin_info = mock_info(INIT_ADMIN, &[]);
+
+            execute_add_hook(deps.as_mut(), admin_info.clone(), "hook".to_owned()).unwrap();
+            SLASHERS
+                .add_slasher(&mut deps.storage, Addr::unchecked("slasher"))
+                .unwrap();
+            execute_set_issuer(
+                deps.as_mut(),
+                admin_info,
+                "issuer".to_owned(),
+                100,
+                Duration::new(100),
+                None,
+            )
+            .unwrap();
+            let env = mock_env();
+            execute_add_points(
+                deps.as_mut(),
+                env.clone(),
+                mock_info("issuer", &[]),
+                USER3.to_owned(),
+                17,
+                Some(env.block.time.plus_seconds(100)),
+            )
+            .unwrap();
+
+            deps.querier
+                .update_balance(MOCK_CONTRACT_ADDR, vec![coin(340, "usdc")]);
+            execute_distribute_rewards(deps.as_mut(), mock_env(), mock_info("dao", &[]), None)
+                .unwrap();
+            receive_tokens(deps.as_mut(), mock_env(), 68, Binary::default());
+
+            execute_slash(
+                deps.as_mut(),
+                mock_env(),
+                mock_info("slasher", &[]),
+                USER1.to_owned(),
+                Decimal::percent(50),
+            )
+            .unwrap();
+            execute_delegate_withdrawal(deps.as_mut(), mock_info(USER2, &[]), "wallet".to_owned())
+                .unwrap();
+            execute_auto_withdraw(
+                deps.as_mut(),
+                mock_info(USER3, &[]),
+                None,
+                Uint128::new(1000),
+            )
+            .unwrap();
+        }
+
+        #[test]
+        fn export_contains_all_state() {
+            let mut deps = mock_deps_tgrade();
+            setup_state(&mut deps);
+
+            let state = export_state(deps.as_mut());
+            assert_eq!(state.admin, Some(Addr::unchecked(INIT_ADMIN)));
+            assert_eq!(
+                state.halflife,
+                crate::state::HALFLIFE.load(&deps.storage).unwrap()
+            );
+            assert_eq!(state.members.len(), 3);
+            assert_eq!(state.distributions.len(), 1);
+            assert_eq!(state.cw20_distributions.len(), 1);
+            // USER1 has adjustments from slashing, USER2 has delegation, USER3 from joining later
+            assert_eq!(state.accounts.len(), 3);
+            assert_eq!(state.accounts[1].delegated, Some(Addr::unchecked("wallet")));
+            assert_eq!(state.hooks, vec!["hook".to_owned()]);
+            assert_eq!(state.preauths_hooks, 1);
+            assert_eq!(state.preauths_slashing, 0);
+            assert_eq!(state.slashers, vec!["slasher".to_owned()]);
+            assert_eq!(state.grants.len(), 1);
+            assert_eq!(state.issuers.len(), 1);
+            assert_eq!(state.issuances.len(), 1);
+            assert_eq!(state.distribution_history.len(), 2);
+        }
+
+        #[test]
+        fn import_works() {
+            let mut deps = mock_deps_tgrade();
+            setup_state(&mut deps);
+            let state = export_state(deps.as_mut());
+
+            let mut imported = mock_deps_tgrade();
+            sudo(
+                imported.as_mut(),
+                mock_env(),
+                SudoMsg::Import(Box::new(state.clone())),
+            )
+            .unwrap();
+
+            assert_eq!(export_state(imported.as_mut()), state);
+            assert_users(&imported, Some(6), Some(USER2_POINTS), Some(17), None);
+            for user in [USER1, USER2, USER3] {
+                assert_eq!(
+                    query_withdrawable_rewards(imported.as_ref(), user.to_owned()).unwrap(),
+                    query_withdrawable_rewards(deps.as_ref(), user.to_owned()).unwrap()
+                );
+            }
+
+            // distributions continue to work on imported state
+            let mut env = mock_env();
+            env.block.height += 1;
+            receive_tokens(imported.as_mut(), env, 29, Binary::default());
+            let rewards = query_withdrawable_rewards(imported.as_ref(), USER3.to_owned()).unwrap();
+            assert_eq!(rewards.cw20_rewards[0].amount, Uint128::new(34 + 17));
+            let history = list_distributions(imported.as_ref(), Some(2), None).unwrap();
+            assert_eq!(history.distributions[0].id, 3);
+
+            // member points history is reset, only later distributions are reported for members
+            let history =
+                query_member_reward_history(imported.as_ref(), USER3.to_owned(), None, None)
+                    .unwrap();
+            let ids: Vec<_> = history.rewards.iter().map(|r| r.distribution).collect();
+            assert_eq!(ids, vec![3]);
+        }
+
+        #[test]
+        fn import_deletes_existing_entries() {
+            let mut deps = mock_deps_tgrade();
+            do_instantiate(deps.as_mut());
+            let state = export_state(deps.as_mut());
+
+            let mut imported = mock_deps_tgrade();
+            setup_state(&mut imported);
+            sudo(
+                imported.as_mut(),
+                mock_env(),
+                SudoMsg::Import(Box::new(state.clone())),
+            )
+            .unwrap();
+
+            assert_eq!(export_state(imported.as_mut()), state);
+            assert_users(
+                &imported,
+                Some(USER1_POINTS),
+                Some(USER2_POINTS),
+                None,
+                None,
+            );
+            assert!(state.accounts.is_empty());
+            assert!(GRANTS.is_empty(&imported.storage));
+        }
+    }
+
+    mod auto_withdraw {
+        use super::*;
+
+        fn transfer(recipient: &str, amount: u128, reply_id: u64) -> SubMsg {
+            let msg = WasmMsg::Execute {
+                contract_addr: "token".to_owned(),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: recipient.to_owned(),
+                    amount: Uint128::new(amount),
+                })
+                .unwrap(),
+                funds: vec![],
+            };
+            SubMsg::reply_on_error(msg, reply_id)
+        }
+
+        fn token_rewards(deps: Deps<TgradeQuery>, owner: &str) -> u128 {
+            query_withdrawable_rewards(deps, owner.to_owned())
+                .unwrap()
+                .cw20_rewards[0]
+                .amount
+                .u128()
+        }
+
+        #[test]
+        fn rewards_are_swept_above_threshold() {
+            let mut deps = mock_deps_tgrade();
+            do_instantiate(deps.as_mut());
+
+            execute_auto_withdraw(
+                deps.as_mut(),
+                mock_info(USER1, &[]),
+                Some("wallet".to_owned()),
+                Uint128::new(100),
+            )
+            .unwrap();
+            execute_auto_withdraw(
+                deps.as_mut(),
+                mock_info(USER2, &[]),
+                None,
+                Uint128::new(100),
+            )
+            .unwrap();
+            assert_eq!(
+                query_auto_withdraw(deps.as_ref(), USER2.to_owned())
+                    .unwrap()
+                    .auto_withdraw,
+                Some(AutoWithdrawal {
+                    receiver: Addr::unchecked(USER2),
+                    min_amount: Uint128::new(100),
+                })
+            );
+
+            // USER1 gets 110, USER2 gets 60
+            receive_tokens(deps.as_mut(), mock_env(), 170, Binary::default());
+            let res = end_block(deps.as_mut(), mock_env()).unwrap();
+            assert_eq!(res.messages, vec![transfer("wallet", 110, 1)]);
+            assert_eq!(res.events.len(), 1);
+            assert_eq!(res.events[0].ty, "auto_withdraw");
+
+            let rewards = query_withdrawable_rewards(deps.as_ref(), USER1.to_owned()).unwrap();
+            assert_eq!(rewards.cw20_rewards[0].amount, Uint128::zero());
+            let rewards = query_withdrawable_rewards(deps.as_ref(), USER2.to_owned()).unwrap();
+            assert_eq!(rewards.cw20_rewards[0].amount, Uint128::new(60));
+
+            // nothing more to sweep
+            assert_eq!(end_block(deps.as_mut(), mock_env()), Ok(Response::new()));
+
+            // USER2 reaches the threshold, USER1 is not registered anymore
+            execute_cancel_auto_withdraw(deps.as_mut(), mock_info(USER1, &[])).unwrap();
+            receive_tokens(deps.as_mut(), mock_env(), 170, Binary::default());
+            let res = end_block(deps.as_mut(), mock_env()).unwrap();
+            assert_eq!(res.messages, vec![transfer(USER2, 120, 1)]);
+            let rewards = query_withdrawable_rewards(deps.as_ref(), USER1.to_owned()).unwrap();
+            assert_eq!(rewards.cw20_rewards[0].amount, Uint128::new(110));
+        }
+
+        #[test]
+        fn only_members_register() {
+            let mut deps = mock_deps_tgrade();
+            do_instantiate(deps.as_mut());
+
+            let err =
+                execute_auto_withdraw(deps.as_mut(), mock_info(USER3, &[]), None, Uint128::zero())
+                    .unwrap_err();
+            assert_eq!(err, ContractError::NotMember {});
+        }
+
+        #[test]
+        fn members_without_points_are_dropped() {
+            let mut deps = mock_deps_tgrade();
+            do_instantiate(deps.as_mut());
+
+            execute_auto_withdraw(deps.as_mut(), mock_info(USER1, &[]), None, Uint128::zero())
+                .unwrap();
+            receive_tokens(deps.as_mut(), mock_env(), 170, Binary::default());
+            execute_update_members(
+                deps.as_mut(),
+                mock_env(),
+                mock_info(INIT_ADMIN, &[]),
+                vec![],
+                vec![USER1.to_owned()],
+            )
+            .unwrap();
+
+            // rewards earned before are still sent, but the registration is gone
+            let res = end_block(deps.as_mut(), mock_env()).unwrap();
+            assert_eq!(res.messages, vec![transfer(USER1, 110, 1)]);
+            let settings = query_auto_withdraw(deps.as_ref(), USER1.to_owned()).unwrap();
+            assert_eq!(settings.auto_withdraw, None);
+        }
+
+        #[test]
+        fn failed_withdrawal_is_restored() {
+            let mut deps = mock_deps_tgrade();
+            do_instantiate(deps.as_mut());
+
+            execute_auto_withdraw(deps.as_mut(), mock_info(USER1, &[]), None, Uint128::zero())
+                .unwrap();
+            execute_auto_withdraw(deps.as_mut(), mock_info(USER2, &[]), None, Uint128::zero())
+                .unwrap();
+            receive_tokens(deps.as_mut(), mock_env(), 170, Binary::default());
+            let res = end_block(deps.as_mut(), mock_env()).unwrap();
+            assert_eq!(
+                res.messages,
+                vec![transfer(USER1, 110, 1), transfer(USER2, 60, 2)]
+            );
+            assert_eq!(token_rewards(deps.as_ref(), USER1), 0);
+
+            let failure = Reply {
+                id: 1,
+                result: SubMsgResult::Err("transfer failed".to_owned()),
+            };
+            let res = reply(deps.as_mut(), mock_env(), failure.clone()).unwrap();
+            assert_eq!(res.events[0].ty, "auto_withdraw_failed");
+
+            // rewards are back, and the member is not retried
+            assert_eq!(token_rewards(deps.as_ref(), USER1), 110);
+            assert_eq!(token_rewards(deps.as_ref(), USER2), 0);
+            let settings = query_auto_withdraw(deps.as_ref(), USER1.to_owned()).unwrap();
+            assert_eq!(settings.auto_withdraw, None);
+            let distribution = CW20_DISTRIBUTION
+                .load(&deps.storage, &Addr::unchecked("token"))
+                .unwrap();
+            assert_eq!(distribution.withdrawable_total, Uint128::new(110));
+
+            // the same failure is not restored twice
+            let err = reply(deps.as_mut(), mock_env(), failure).unwrap_err();
+            assert_eq!(err, ContractError::UnrecognisedReply(1));
+
+            receive_tokens(deps.as_mut(), mock_env(), 170, Binary::default());
+            let res = end_block(deps.as_mut(), mock_env()).unwrap();
+            assert_eq!(res.messages, vec![transfer(USER2, 60, 1)]);
+        }
+    }
+
+    mod history {
+        use super::*;
+        use cosmwasm_std::testing::MOCK_CONTRACT_ADDR;
+
+        #[test]
+        fn distributions_are_recorded() {
+            let mut deps = mock_deps_tgrade();
+            do_instantiate(deps.as_mut());
+
+            let mut env = mock_env();
+            env.block.height += 1;
+            deps.querier
+                .update_balance(MOCK_CONTRACT_ADDR, vec![coin(170, "usdc")]);
+            execute_distribute_rewards(
+                deps.as_mut(),
+                env.clone(),
+                mock_info("anyone", &[]),
+                Some("dao".to_owned()),
+            )
+            .unwrap();
+            // points added after distribution in the same block
+            execute_add_points(
+                deps.as_mut(),
+                env.clone(),
+                mock_info(INIT_ADMIN, &[]),
+                USER3.to_owned(),
+                17,
+                None,
+            )
+            .unwrap();
+
+            env.block.height += 1;
+            receive_tokens(deps.as_mut(), env.clone(), 340, Binary::default());
+
+            let distributions = list_distributions(deps.as_ref(), None, None)
+                .unwrap()
+                .distributions;
+            assert_eq!(
+                distributions,
+                vec![
+                    DistributionRecord {
+                        id: 1,
+                        height: env.block.height - 1,
+                        time: env.block.time,
+                        sender: Addr::unchecked("dao"),
+                        total_points: 17,
+                        rewards: vec![coin(170, "usdc")],
+                        cw20_rewards: vec![],
+                        shares_per_point: vec![Uint128::new(10 << SHARES_SHIFT)],
+                        cw20_shares_per_point: vec![],
+                    },
+                    DistributionRecord {
+                        id: 2,
+                        height: env.block.height,
+                        time: env.block.time,
+                        sender: Addr::unchecked("partner"),
+                        total_points: 34,
+                        rewards: vec![],
+                        cw20_rewards: vec![Cw20Coin {
+                            address: "token".to_owned(),
+                            amount: Uint128::new(340),
+                        }],
+                        shares_per_point: vec![],
+                        cw20_shares_per_point: vec![Uint128::new(10 << SHARES_SHIFT)],
+                    },
+                ]
+            );
+
+            let distributions = list_distributions(deps.as_ref(), Some(1), None)
+                .unwrap()
+                .distributions;
+            assert_eq!(distributions.len(), 1);
+            assert_eq!(distributions[0].id, 2);
+        }
+
+        #[test]
+        fn member_rewards_are_derived() {
+            let mut deps = mock_deps_tgrade();
+            do_instantiate(deps.as_mut());
+
+            let mut env = mock_env();
+            env.block.height += 1;
+            deps.querier
+                .update_balance(MOCK_CONTRACT_ADDR, vec![coin(170, "usdc")]);
+            execute_distribute_rewards(deps.as_mut(), env.clone(), mock_info("anyone", &[]), None)
+                .unwrap();
+            execute_add_points(
+                deps.as_mut(),
+                env.clone(),
+                mock_info(INIT_ADMIN, &[]),
+                USER3.to_owned(),
+                17,
+                None,
+            )
+            .unwrap();
+
+            env.block.height += 1;
+            receive_tokens(deps.as_mut(), env, 340, Binary::default());
+
+            let history = |addr: &str, start_after| {
+                query_member_reward_history(deps.as_ref(), addr.to_owned(), start_after, None)
+                    .unwrap()
+                    .rewards
+                    .into_iter()
+                    .map(|reward| {
+                        (
+                            reward.distribution,
+                            reward.points,
+                            reward.rewards,
+                            reward.cw20_rewards,
+                        )
+                    })
+                    .collect::<Vec<_>>()
+            };
+            let tokens = |amount| {
+                vec![Cw20Coin {
+                    address: "token".to_owned(),
+                    amount: Uint128::new(amount),
+                }]
+            };
+
+            assert_eq!(
+                history(USER1, None),
+                vec![
+                    (1, USER1_POINTS, vec![coin(110, "usdc")], vec![]),
+                    (2, USER1_POINTS, vec![], tokens(110)),
+                ]
+            );
+            assert_eq!(
+                history(USER1, Some(1)),
+                vec![(2, USER1_POINTS, vec![], tokens(110))]
+            );
+            // not a member at the first distribution
+            assert_eq!(history(USER3, None), vec![(2, 17, vec![], tokens(170))]);
+            // matches what was actually assigned
+            let rewards = query_withdrawable_rewards(deps.as_ref(), USER3.to_owned()).unwrap();
+            assert_eq!(rewards.cw20_rewards, tokens(170));
+        }
+
+        #[test]
+        fn member_history_scans_limited_distributions() {
+            let mut deps = mock_deps_tgrade();
+            do_instantiate(deps.as_mut());
+
+            let mut env = mock_env();
+            for _ in 0..3 {
+                env.block.height += 1;
+                receive_tokens(deps.as_mut(), env.clone(), 170, Binary::default());
+            }
+            execute_add_points(
+                deps.as_mut(),
+                env.clone(),
+                mock_info(INIT_ADMIN, &[]),
+                USER3.to_owned(),
+                17,
+                None,
+            )
+            .unwrap();
+            env.block.height += 1;
+            receive_tokens(deps.as_mut(), env, 340, Binary::default());
+
+            // distributions without the member still count towards the limit
+            let page = query_member_reward_history(deps.as_ref(), USER3.to_owned(), None, Some(2))
+                .unwrap();
+            assert_eq!(page.rewards, vec![]);
+            assert_eq!(page.last_scanned, Some(2));
+
+            let page =
+                query_member_reward_history(deps.as_ref(), USER3.to_owned(), Some(2), Some(2))
+                    .unwrap();
+            assert_eq!(page.rewards.len(), 1);
+            assert_eq!(page.rewards[0].distribution, 4);
+            assert_eq!(page.last_scanned, Some(4));
+
+            let page =
+                query_member_reward_history(deps.as_ref(), USER3.to_owned(), Some(4), Some(2))
+                    .unwrap();
+            assert_eq!(page.rewards, vec![]);
+            assert_eq!(page.last_scanned, None);
+        }
+    }
+
+    mod grants {
+        use super::*;
+
+        fn token_rewards(deps: Deps<TgradeQuery>, owner: &str) -> u128 {
+            query_withdrawable_rewards(deps, owner.to_owned())
+                .unwrap()
+                .cw20_rewards[0]
+                .amount
+                .u128()
+        }
+
+        #[test]
+        fn expiration_must_be_in_future() {
+            let mut deps = mock_deps_tgrade();
+            do_instantiate(deps.as_mut());
+
+            let env = mock_env();
+            let err = execute_add_points(
+                deps.as_mut(),
+                env.clone(),
+                mock_info(INIT_ADMIN, &[]),
+                USER1.to_owned(),
+                5,
+                Some(env.block.time),
+            )
+            .unwrap_err();
+            assert_eq!(err, ContractError::InvalidExpiration {});
+        }
+
+        #[test]
+        fn expired_points_are_removed() {
+            let mut deps = mock_deps_tgrade();
+            do_instantiate(deps.as_mut());
+            let hook = String::from("hook1");
+            execute_add_hook(deps.as_mut(), mock_info(INIT_ADMIN, &[]), hook.clone()).unwrap();
+
+            let mut env = mock_env();
+            let expires_at = env.block.time.plus_seconds(100);
+            execute_add_points(
+                deps.as_mut(),
+                env.clone(),
+                mock_info(INIT_ADMIN, &[]),
+                USER1.to_owned(),
+                6,
+                Some(expires_at),
+            )
+            .unwrap();
+            assert_users(&deps, Some(17), Some(USER2_POINTS), None, None);
+            receive_tokens(deps.as_mut(), env.clone(), 230, Binary::default());
+
+            // not expired yet
+            env.block.time = env.block.time.plus_seconds(99);
+            assert_eq!(end_block(deps.as_mut(), env.clone()), Ok(Response::new()));
+
+            env.block.time = expires_at;
+            let res = end_block(deps.as_mut(), env.clone()).unwrap();
+            assert_users(&deps, Some(USER1_POINTS), Some(USER2_POINTS), None, None);
+            let msg = MemberChangedHookMsg::one(MemberDiff::new(USER1, Some(17), Some(11)));
+            assert_eq!(
+                res.messages,
+                vec![SubMsg::new(msg.into_cosmos_msg(hook).unwrap())]
+            );
+            assert!(GRANTS.is_empty(&deps.storage));
+
+            // rewards distributed before expiration are kept, later ones follow the new points
+            receive_tokens(deps.as_mut(), env, 170, Binary::default());
+            assert_eq!(token_rewards(deps.as_ref(), USER1), 170 + 110);
+            assert_eq!(token_rewards(deps.as_ref(), USER2), 60 + 60);
+        }
+
+        #[test]
+        fn only_remaining_grant_is_removed_after_slashing() {
+            let mut deps = mock_deps_tgrade();
+            do_instantiate(deps.as_mut());
+            SLASHERS
+                .add_slasher(&mut deps.storage, Addr::unchecked(INIT_ADMIN))
+                .unwrap();
+
+            let mut env = mock_env();
+            execute_add_points(
+                deps.as_mut(),
+                env.clone(),
+                mock_info(INIT_ADMIN, &[]),
+                USER1.to_owned(),
+                6,
+                Some(env.block.time.plus_seconds(100)),
+            )
+            .unwrap();
+            execute_slash(
+                deps.as_mut(),
+                env.clone(),
+                mock_info(INIT_ADMIN, &[]),
+                USER1.to_owned(),
+                Decimal::percent(50),
+            )
+            .unwrap();
+            assert_users(&deps, Some(9), Some(USER2_POINTS), None, None);
+
+            // 17 points slashed to 9 leave 6 * 9 / 17 = 3 points of the grant
+            env.block.time = env.block.time.plus_seconds(100);
+            end_block(deps.as_mut(), env).unwrap();
+            assert_users(&deps, Some(6), Some(USER2_POINTS), None, None);
+            assert!(MEMBER_GRANTS.is_empty(&deps.storage));
+        }
+
+        #[test]
+        fn only_remaining_grant_is_removed_after_halflife() {
+            let mut deps = mock_deps_tgrade();
+            do_instantiate(deps.as_mut());
+
+            let mut env = mock_env();
+            let expires_at = env.block.time.plus_seconds(HALFLIFE + 100);
+            execute_add_points(
+                deps.as_mut(),
+                env.clone(),
+                mock_info(INIT_ADMIN, &[]),
+                USER1.to_owned(),
+                6,
+                Some(expires_at),
+            )
+            .unwrap();
+
+            env.block.time = env.block.time.plus_seconds(HALFLIFE);
+            end_block(deps.as_mut(), env.clone()).unwrap();
+            assert_users(&deps, Some(8), Some(3), None, None);
+
+            // 17 points halved to 8 leave 6 * 8 / 17 = 2 points of the grant
+            env.block.time = expires_at;
+            end_block(deps.as_mut(), env).unwrap();
+            assert_users(&deps, Some(6), Some(3), None, None);
+        }
+
+        #[test]
+        fn overwritten_members_keep_scaled_grants() {
+            let mut deps = mock_deps_tgrade();
+            do_instantiate(deps.as_mut());
+
+            let mut env = mock_env();
+            let expires_at = env.block.time.plus_seconds(100);
+            execute_add_points(
+                deps.as_mut(),
+                env.clone(),
+                mock_info(INIT_ADMIN, &[]),
+                USER1.to_owned(),
+                6,
+                Some(expires_at),
+            )
+            .unwrap();
+            let add = vec![Member {
+                addr: USER1.into(),
+                points: 10,
+                start_height: None,
+            }];
+            execute_update_members(
+                deps.as_mut(),
+                env.clone(),
+                mock_info(INIT_ADMIN, &[]),
+                add,
+                vec![],
+            )
+            .unwrap();
+
+            // 17 points overwritten with 10 leave 6 * 10 / 17 = 3 points of the grant
+            env.block.time = expires_at;
+            end_block(deps.as_mut(), env).unwrap();
+            assert_users(&deps, Some(7), Some(USER2_POINTS), None, None);
+        }
+
+        #[test]
+        fn removed_members_drop_grants() {
+            let mut deps = mock_deps_tgrade();
+            do_instantiate(deps.as_mut());
+
+            let mut env = mock_env();
+            let expires_at = env.block.time.plus_seconds(100);
+            execute_add_points(
+                deps.as_mut(),
+                env.clone(),
+                mock_info(INIT_ADMIN, &[]),
+                USER1.to_owned(),
+                6,
+                Some(expires_at),
+            )
+            .unwrap();
+            execute_update_members(
+                deps.as_mut(),
+                env.clone(),
+                mock_info(INIT_ADMIN, &[]),
+                vec![],
+                vec![USER1.into()],
+            )
+            .unwrap();
+            assert!(GRANTS.is_empty(&deps.storage));
+            assert!(MEMBER_GRANTS.is_empty(&deps.storage));
+
+            // points of a re-added member are not taken by the old grant
+            sudo_add_member(
+                deps.as_mut(),
+                env.clone(),
+                Member {
+                    addr: USER1.into(),
+                    points: 4,
+                    start_height: None,
+                },
+            )
+            .unwrap();
+            env.block.time = expires_at;
+            end_block(deps.as_mut(), env).unwrap();
+            assert_users(&deps, Some(4), Some(USER2_POINTS), None, None);
+        }
+
+        #[test]
+        fn only_remaining_grant_is_removed_after_continuous_decay() {
+            let mut deps = mock_deps_tgrade();
+            do_instantiate(deps.as_mut());
+            let msg = MigrateMsg {
+                halflife: None,
+                halflife_reduction: None,
+                decay_mode: Some(DecayMode::Continuous),
+                halflife_batch: None,
+            };
+            migrate(deps.as_mut(), mock_env(), msg).unwrap();
+
+            let mut env = mock_env();
+            let expires_at = env.block.time.plus_seconds(HALFLIFE);
+            execute_add_points(
+                deps.as_mut(),
+                env.clone(),
+                mock_info(INIT_ADMIN, &[]),
+                USER1.to_owned(),
+                USER1_POINTS,
+                Some(expires_at),
+            )
+            .unwrap();
+
+            // 22 points decay to 11 on expiration, leaving 11 * 11 / 22 = 5 points of the grant
+            env.block.time = expires_at;
+            end_block(deps.as_mut(), env.clone()).unwrap();
+            let member = query_member(deps.as_ref(), &env, USER1.into(), None).unwrap();
+            assert_eq!(member.points, Some(6));
+            assert_eq!(query_total_points(deps.as_ref()).unwrap().points, 12);
+            assert!(MEMBER_GRANTS.is_empty(&deps.storage));
+        }
+    }
+
+    mod issuers {
+        use super::*;
+
+        const ISSUER: &str = "issuer";
+
+        fn set_issuer(deps: DepsMut<TgradeQuery>, cap: u64, allowlist: Option<Vec<String>>) {
+            execute_set_issuer(
+                deps,
+                mock_info(INIT_ADMIN, &[]),
+                ISSUER.to_owned(),
+                cap,
+                Duration::new(100),
+                allowlist,
+            )
+            .unwrap();
+        }
+
+        fn allowance(deps: Deps<TgradeQuery>, env: &Env) -> IssuerResponse {
+            query_issuer(deps, env, ISSUER.to_owned()).unwrap()
+        }
+
+        #[test]
+        fn only_admin_sets_issuers() {
+            let mut deps = mock_deps_tgrade();
+            do_instantiate(deps.as_mut());
+
+            let err = execute_set_issuer(
+                deps.as_mut(),
+                mock_info(USER1, &[]),
+                ISSUER.to_owned(),
+                10,
+                Duration::new(100),
+                None,
+            )
+            .unwrap_err();
+            assert_eq!(err, AdminError::NotAdmin {}.into());
+
+            let err = execute_set_issuer(
+                deps.as_mut(),
+                mock_info(INIT_ADMIN, &[]),
+                ISSUER.to_owned(),
+                10,
+                Duration::new(0),
+                None,
+            )
+            .unwrap_err();
+            assert_eq!(err, ContractError::InvalidIssuerPeriod {});
+
+            set_issuer(deps.as_mut(), 10, None);
+            let issuers = list_issuers(deps.as_ref(), &mock_env(), None, None)
+                .unwrap()
+                .issuers;
+            assert_eq!(issuers.len(), 1);
+            assert_eq!(issuers[0].addr, ISSUER);
+            assert_eq!(issuers[0].remaining, 10);
+
+            execute_remove_issuer(deps.as_mut(), mock_info(INIT_ADMIN, &[]), ISSUER.to_owned())
+                .unwrap();
+            let err = execute_add_points(
+                deps.as_mut(),
+                mock_env(),
+                mock_info(ISSUER, &[]),
+                USER1.to_owned(),
+                1,
+                None,
+            )
+            .unwrap_err();
+            assert_eq!(
+                err,
+                ContractError::Unauthorized("Sender is neither admin nor issuer".to_owned())
+            );
+        }
+
+        #[test]
+        fn cap_applies_to_rolling_period() {
+            let mut deps = mock_deps_tgrade();
+            do_instantiate(deps.as_mut());
+            set_issuer(deps.as_mut(), 10, None);
+
+            let mut env = mock_env();
+            execute_add_points(
+                deps.as_mut(),
+                env.clone(),
+                mock_info(ISSUER, &[]),
+                USER1.to_owned(),
+                6,
+                None,
+            )
+            .unwrap();
+
+            env.block.time = env.block.time.plus_seconds(50);
+            let err = execute_add_points_batch(
+                deps.as_mut(),
+                env.clone(),
+                mock_info(ISSUER, &[]),
+                vec![(USER2.to_owned(), 3), (USER3.to_owned(), 2)],
+            )
+            .unwrap_err();
+            assert_eq!(err, ContractError::IssuerCapExceeded { remaining: 4 });
+            assert_users(
+                &deps,
+                Some(USER1_POINTS + 6),
+                Some(USER2_POINTS),
+                None,
+                None,
+            );
+
+            execute_add_points_batch(
+                deps.as_mut(),
+                env.clone(),
+                mock_info(ISSUER, &[]),
+                vec![(USER2.to_owned(), 2), (USER3.to_owned(), 2)],
+            )
+            .unwrap();
+            let res = allowance(deps.as_ref(), &env);
+            assert_eq!((res.issued, res.remaining), (10, 0));
+
+            // first issuance leaves the period, rounded up to a bucket of 100 / 24 seconds
+            env.block.time = env.block.time.plus_seconds(50);
+            assert_eq!(allowance(deps.as_ref(), &env).remaining, 0);
+            env.block.time = env.block.time.plus_seconds(4);
+            let res = allowance(deps.as_ref(), &env);
+            assert_eq!((res.issued, res.remaining), (4, 6));
+
+            // admin is not limited
+            execute_add_points(
+                deps.as_mut(),
+                env.clone(),
+                mock_info(INIT_ADMIN, &[]),
+                USER1.to_owned(),
+                100,
+                None,
+            )
+            .unwrap();
+            assert_eq!(allowance(deps.as_ref(), &env).remaining, 6);
+
+            let log = list_issuances(deps.as_ref(), ISSUER.to_owned(), None, None)
+                .unwrap()
+                .issuances;
+            let log: Vec<_> = log
+                .into_iter()
+                .map(|issuance| (issuance.id, issuance.recipients, issuance.points))
+                .collect();
+            // a single entry per call
+            assert_eq!(
+                log,
+                vec![
+                    (1, vec![(Addr::unchecked(USER1), 6)], 6),
+                    (
+                        2,
+                        vec![(Addr::unchecked(USER2), 2), (Addr::unchecked(USER3), 2)],
+                        4
+                    ),
+                ]
+            );
+
+            let log = list_issuances(deps.as_ref(), ISSUER.to_owned(), Some(1), Some(1))
+                .unwrap()
+                .issuances;
+            assert_eq!(log.len(), 1);
+            assert_eq!(log[0].id, 2);
+        }
+
+        #[test]
+        fn allowlist_restricts_recipients() {
+            let mut deps = mock_deps_tgrade();
+            do_instantiate(deps.as_mut());
+            set_issuer(deps.as_mut(), 10, Some(vec![USER1.to_owned()]));
+
+            let err = execute_add_points_batch(
+                deps.as_mut(),
+                mock_env(),
+                mock_info(ISSUER, &[]),
+                vec![(USER1.to_owned(), 1), (USER2.to_owned(), 1)],
+            )
+            .unwrap_err();
+            assert_eq!(err, ContractError::RecipientNotAllowed(USER2.to_owned()));
+
+            execute_add_points(
+                deps.as_mut(),
+                mock_env(),
+                mock_info(ISSUER, &[]),
+                USER1.to_owned(),
+                1,
+                None,
+            )
+            .unwrap();
+            assert_users(
+                &deps,
+                Some(USER1_POINTS + 1),
+                Some(USER2_POINTS),
+                None,
+                None,
+            );
+        }
     }
 
     #[test]
@@ -1,4 +1,4 @@
-use cosmwasm_std::StdError;
+use cosmwasm_std::{Decimal, StdError};
 use thiserror::Error;
 
 use cw_controllers::AdminError;
@@ -27,6 +27,33 @@ pub enum ContractError {
     #[error("Unknown sudo message")]
     UnknownSudoMsg {},
 
+    #[error("cw20 token {0} is not a reward token")]
+    UnknownRewardToken(String),
+
     #[error("No members to distribute tokens to")]
     NoMembersToDistributeTo {},
+
+    #[error("Halflife reduction must be greater than 0 and at most 1, got {0}")]
+    InvalidReduction(Decimal),
+
+    #[error("Halflife batch size must be greater than 0")]
+    InvalidHalflifeBatch {},
+
+    #[error("Grant expiration must be in the future")]
+    InvalidExpiration {},
+
+    #[error("Issuer period must be greater than 0")]
+    InvalidIssuerPeriod {},
+
+    #[error("Issuer cap exceeded, only {remaining} points can be awarded now")]
+    IssuerCapExceeded { remaining: u64 },
+
+    #[error("Issuer is not allowed to award points to {0}")]
+    RecipientNotAllowed(String),
+
+    #[error("Only members can register for automatic withdrawal")]
+    NotMember {},
+
+    #[error("Unrecognised reply id: {0}")]
+    UnrecognisedReply(u64),
 }
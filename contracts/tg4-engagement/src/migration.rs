@@ -1,30 +1,104 @@
-use cosmwasm_std::{Deps, DepsMut, Order, StdResult};
+use cosmwasm_std::{Addr, Deps, DepsMut, Env, Order, StdResult, Uint128};
+use cw_storage_plus::{Item, Map};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
 
 use tg4::{MemberChangedHookMsg, MemberDiff, MemberInfo};
 use tg_bindings::TgradeQuery;
 use tg_utils::members;
 
+use crate::contract::validate_reduction;
 use crate::error::ContractError;
+use crate::i128::Int128;
 use crate::msg::MigrateMsg;
-use crate::state::{Halflife, HALFLIFE};
+use crate::state::{
+    DecayMode, Distribution, WithdrawAdjustment, DELEGATED, DISTRIBUTION, HALFLIFE, HALFLIFE_JOB,
+    LAST_DECAY, WITHDRAW_ADJUSTMENT,
+};
 
 pub(crate) fn migrate_config(
     deps: DepsMut<TgradeQuery>,
+    env: &Env,
     msg: MigrateMsg,
 ) -> Result<(), ContractError> {
+    let mut halflife = HALFLIFE.load(deps.storage)?;
     if let Some(duration) = msg.halflife {
         // Update half life's duration
         // Zero duration means no / remove half life
-        HALFLIFE.update(deps.storage, |hf| -> StdResult<_> {
-            Ok(Halflife {
-                halflife: if duration.seconds() > 0 {
-                    Some(duration)
-                } else {
-                    None
-                },
-                last_applied: hf.last_applied,
-            })
-        })?;
+        halflife.halflife = if duration.seconds() > 0 {
+            Some(duration)
+        } else {
+            None
+        };
+    }
+    if let Some(reduction) = msg.halflife_reduction {
+        halflife.reduction = validate_reduction(reduction)?;
+    }
+    if let Some(mode) = msg.decay_mode {
+        if mode != halflife.mode {
+            // Lazily tracked decay times are meaningless in the other mode. Switching to continuous
+            // decays everyone since the last periodic halflife, switching back starts a new period.
+            let addrs = LAST_DECAY
+                .keys(deps.storage, None, None, Order::Ascending)
+                .collect::<StdResult<Vec<_>>>()?;
+            for addr in addrs {
+                LAST_DECAY.remove(deps.storage, &addr);
+            }
+            if mode == DecayMode::Periodic {
+                halflife.last_applied = env.block.time;
+            }
+            halflife.mode = mode;
+        }
+    }
+    if let Some(batch) = msg.halflife_batch {
+        if batch == 0 {
+            return Err(ContractError::InvalidHalflifeBatch {});
+        }
+        halflife.batch_size = batch;
+    }
+    if halflife.halflife.is_none() || halflife.mode == DecayMode::Continuous {
+        // Pending periodic halflife would never be finished
+        HALFLIFE_JOB.remove(deps.storage);
+    }
+    HALFLIFE.save(deps.storage, &halflife)?;
+    Ok(())
+}
+
+/// Withdraw adjustment as stored before rewards in multiple denoms were supported
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
+pub(crate) struct LegacyWithdrawAdjustment {
+    pub shares_correction: Int128,
+    pub withdrawn_rewards: Uint128,
+    pub delegated: Addr,
+}
+
+pub(crate) const LEGACY_DISTRIBUTION: Item<Distribution> = Item::new("distribution");
+pub(crate) const LEGACY_WITHDRAW_ADJUSTMENT: Map<&Addr, LegacyWithdrawAdjustment> =
+    Map::new("withdraw_adjustment");
+
+/// Moves single denom distribution data to the per denom storage
+pub(crate) fn migrate_distribution(deps: DepsMut<TgradeQuery>) -> Result<(), ContractError> {
+    let distribution = match LEGACY_DISTRIBUTION.may_load(deps.storage)? {
+        Some(distribution) => distribution,
+        None => return Ok(()),
+    };
+    LEGACY_DISTRIBUTION.remove(deps.storage);
+    DISTRIBUTION.save(deps.storage, &distribution.denom, &distribution)?;
+
+    let adjustments = LEGACY_WITHDRAW_ADJUSTMENT
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    for (addr, legacy) in adjustments {
+        LEGACY_WITHDRAW_ADJUSTMENT.remove(deps.storage, &addr);
+        if legacy.delegated != addr {
+            DELEGATED.save(deps.storage, &addr, &legacy.delegated)?;
+        }
+        let adjustment = WithdrawAdjustment {
+            denom: distribution.denom.clone(),
+            shares_correction: legacy.shares_correction,
+            withdrawn_rewards: legacy.withdrawn_rewards,
+        };
+        WITHDRAW_ADJUSTMENT.save(deps.storage, (&addr, &distribution.denom), &adjustment)?;
     }
     Ok(())
 }
@@ -1,4 +1,5 @@
-use cosmwasm_std::{Addr, Coin, Decimal, Timestamp};
+use cosmwasm_std::{Addr, Coin, Decimal, Timestamp, Uint128};
+use cw20::{Cw20Coin, Cw20ReceiveMsg, Denom};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
@@ -6,6 +7,11 @@ use tg4::Member;
 use tg_bindings::{Evidence, PrivilegeChangeMsg};
 use tg_utils::Duration;
 
+use crate::state::{
+    AutoWithdrawal, DecayMode, Distribution, DistributionRecord, EngagementState, Issuance, Issuer,
+    WithdrawAdjustment,
+};
+
 #[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
 #[serde(rename_all = "snake_case")]
 pub struct InstantiateMsg {
@@ -18,11 +24,22 @@ pub struct InstantiateMsg {
     #[serde(default)]
     pub preauths_slashing: u64,
     pub halflife: Option<Duration>,
-    /// Denom of tokens which may be distributed by this contract.
+    /// Portion of points removed every halflife period. `0.5` if not set.
+    #[serde(default)]
+    pub halflife_reduction: Option<Decimal>,
+    /// How the halflife is applied, `DecayMode::Periodic` if not set.
+    #[serde(default)]
+    pub decay_mode: Option<DecayMode>,
+    /// Maximum number of members reduced in a single block by the periodic halflife, 100 if not
+    /// set.
+    #[serde(default)]
+    pub halflife_batch: Option<u32>,
+    /// Denom of tokens which is expected to be distributed by this contract. Rewards in other
+    /// native denoms are distributed once the admin adds them with `AddRewardDenom`.
     pub denom: String,
 }
 
-#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
 #[serde(rename_all = "snake_case")]
 pub enum ExecuteMsg {
     /// Change the admin
@@ -33,21 +50,50 @@ pub enum ExecuteMsg {
         remove: Vec<String>,
         add: Vec<Member>,
     },
-    /// Add points to member's address
-    AddPoints { addr: String, points: u64 },
+    /// Add points to member's address. Must be called by the admin or an issuer.
+    /// If `expires_at` is set, the points are removed from the member once it passes.
+    AddPoints {
+        addr: String,
+        points: u64,
+        #[serde(default)]
+        expires_at: Option<Timestamp>,
+    },
+    /// Add points to many members at once, as pairs of address and points. Points for repeated
+    /// addresses are summed up. All the changes are sent to hooks in a single message.
+    /// Must be called by the admin or an issuer.
+    AddPointsBatch { points: Vec<(String, u64)> },
+    /// Registers an address allowed to award points, or updates an existing one. It can award at
+    /// most `cap` points within any `period`, and only to `allowlist` addresses if set.
+    /// Must be called by Admin
+    SetIssuer {
+        addr: String,
+        cap: u64,
+        period: Duration,
+        allowlist: Option<Vec<String>>,
+    },
+    /// Removes an issuer. Must be called by Admin
+    RemoveIssuer { addr: String },
+    /// Adds a native denom to be distributed by `DistributeRewards`, besides the `denom` set on
+    /// instantiation. Reward denoms cannot be removed. Must be called by Admin
+    AddRewardDenom { denom: String },
+    /// Adds a cw20 token to be accepted and distributed by `Receive`. cw20 tokens not added
+    /// this way are rejected. Reward tokens cannot be removed. Must be called by Admin
+    AddRewardToken { token: String },
     /// Add a new hook to be informed of all membership changes. Must be called by Admin
     AddHook { addr: String },
     /// Remove a hook. Must be called by Admin
     RemoveHook { addr: String },
     /// Distributes rewards sent with this message, and all rewards transferred since last call of this
     /// to members, proportionally to their points. Rewards are not immediately send to members, but
-    /// assigned to them for later withdrawal (see: `ExecuteMsg::WithdrawFunds`)
+    /// assigned to them for later withdrawal (see: `ExecuteMsg::WithdrawFunds`). Every reward denom
+    /// held by the contract is distributed, other tokens are ignored.
     DistributeRewards {
         /// Original source of rewards, informational. If present overwrites "sender" field on
         /// propagated event.
         sender: Option<String>,
     },
-    /// Withdraws rewards which were previously distributed and assigned to sender.
+    /// Withdraws rewards which were previously distributed and assigned to sender, in all denoms
+    /// at once unless `denom` is set.
     WithdrawRewards {
         /// Account from which assigned rewards would be withdrawn; `sender` by default. `sender` has
         /// to be eligible for withdrawal from `owner` address to perform this call (`owner` has to
@@ -55,6 +101,10 @@ pub enum ExecuteMsg {
         owner: Option<String>,
         /// Address where to transfer funds. If not present, funds would be sent to `sender`.
         receiver: Option<String>,
+        /// Withdraws only the rewards in this denom or cw20 token, so a token failing to transfer
+        /// doesn't block the others.
+        #[serde(default)]
+        denom: Option<Denom>,
     },
     /// Sets given address as allowed for senders funds withdrawal. Funds still can be withdrawn by
     /// sender himself, but this additional account is allowed to perform it as well. There can be only
@@ -64,12 +114,37 @@ pub enum ExecuteMsg {
         /// to own address.
         delegated: String,
     },
+    /// Registers sender for automatic withdrawal. Rewards assigned to sender are sent to
+    /// `receiver` (sender by default) at the end of some future block, every token separately
+    /// once its amount reaches `min_amount`. Registering again replaces previous settings.
+    /// Only members can register; members without points, or whose rewards failed to be sent,
+    /// are dropped.
+    AutoWithdraw {
+        receiver: Option<String>,
+        min_amount: Uint128,
+    },
+    /// Stops automatic withdrawal for sender
+    CancelAutoWithdraw {},
     /// Adds slasher for contract if there are enough `slasher_preauths` left
     AddSlasher { addr: String },
     /// Removes slasher for contract
     RemoveSlasher { addr: String },
     /// Slash engagement points from address
     Slash { addr: String, portion: Decimal },
+    /// cw20 tokens sent to this contract are distributed to members, as `DistributeRewards` does
+    /// for native tokens. Only tokens added with `AddRewardToken` are accepted. The optional
+    /// message is `ReceiveMsg`.
+    Receive(Cw20ReceiveMsg),
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum ReceiveMsg {
+    DistributeRewards {
+        /// Original source of rewards, informational. If present overwrites "sender" field on
+        /// propagated event.
+        sender: Option<String>,
+    },
 }
 
 #[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
@@ -77,7 +152,9 @@ pub enum ExecuteMsg {
 pub enum QueryMsg {
     /// Return AdminResponse
     Admin {},
-    /// Return TotalPointsResponse
+    /// Return TotalPointsResponse. In the continuous decay mode it is the total as of the last
+    /// touch of every member, so it can be higher than the sum of decayed points reported by
+    /// the member queries.
     TotalPoints {},
     /// Returns MemberListResponse
     ListMembers {
@@ -98,8 +175,8 @@ pub enum QueryMsg {
     Hooks {},
     /// Return the current number of preauths. Returns PreauthResponse.
     Preauths {},
-    /// Return how many rewards are assigned for withdrawal from the given address. Returns
-    /// `RewardsResponse`.
+    /// Return how many rewards are assigned for withdrawal from the given address, per denom.
+    /// Returns `RewardsResponse`.
     WithdrawableRewards { owner: String },
     /// Return how many rewards were distributed in total by this contract. Returns
     /// `RewardsResponse`.
@@ -110,19 +187,52 @@ pub enum QueryMsg {
     /// Return address allowed for withdrawal of the funds assigned to owner. Returns `DelegateResponse`
     Delegated { owner: String },
     /// Returns information about the half-life, including the duration in seconds, the last
-    /// and the next occurrence.
+    /// and the next occurrence, and the effective decay rate.
     Halflife {},
+    /// Returns progress of the periodic halflife being applied over several blocks.
+    /// Returns `HalflifeProgressResponse`.
+    HalflifeProgress {},
     /// Returns information (bool) about whether the given address is an active slasher
     IsSlasher { addr: String },
     /// Returns all active slashers as a vector of addresses
     ListSlashers {},
-    /// Returns rewards distribution data
+    /// Returns rewards distribution data for every denom. Returns `DistributionDataResponse`.
     DistributionData {},
-    /// Returns withdraw adjustment data
+    /// Returns withdraw adjustment data for every denom. Returns `WithdrawAdjustmentDataResponse`.
     WithdrawAdjustmentData { addr: String },
+    /// Returns automatic withdrawal settings of the address. Returns `AutoWithdrawResponse`.
+    AutoWithdraw { addr: String },
+    /// Returns past rewards distributions, oldest first. Returns `DistributionsResponse`.
+    ListDistributions {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    /// Returns rewards accrued by the member in past distributions, oldest first. At most `limit`
+    /// distributions are scanned, and the ones in which the member had no points are skipped.
+    /// Distributions from before the state import are skipped as well, as member points history
+    /// is not imported. Returns `MemberRewardHistoryResponse`.
+    MemberRewardHistory {
+        addr: String,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    /// Returns the issuer configuration and how many points it can still award in the current
+    /// period. Returns `IssuerResponse`.
+    Issuer { addr: String },
+    /// Returns `IssuersResponse`
+    ListIssuers {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Returns points awarded by the issuer, oldest first. Returns `IssuancesResponse`.
+    IssuanceLog {
+        issuer: String,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
 }
 
-#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
 #[serde(rename_all = "snake_case")]
 pub enum SudoMsg {
     /// This will be delivered every block if the contract is currently registered for Begin Block
@@ -143,6 +253,12 @@ pub enum SudoMsg {
     /// Use case: for post-genesis validators, we want to set some initial engagement points.
     /// Note: If the member already exists, its points will be reset to the points sent here.
     UpdateMember(Member),
+    /// This will export contract state. Requires `StateExporterImporter` privilege.
+    Export {},
+    /// This will import contract state. Requires `StateExporterImporter` privilege.
+    /// Member points history is not a part of the state - imported members count as added at
+    /// the import height.
+    Import(Box<EngagementState>),
 }
 
 #[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
@@ -150,9 +266,32 @@ pub struct PreauthResponse {
     pub preauths: u64,
 }
 
-#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
 pub struct RewardsResponse {
-    pub rewards: Coin,
+    /// Rewards per denom, ordered by denom
+    pub rewards: Vec<Coin>,
+    /// Rewards per cw20 token, ordered by token address
+    #[serde(default)]
+    pub cw20_rewards: Vec<Cw20Coin>,
+}
+
+impl RewardsResponse {
+    pub fn new(rewards: Vec<(Denom, Uint128)>) -> Self {
+        let mut resp = RewardsResponse {
+            rewards: vec![],
+            cw20_rewards: vec![],
+        };
+        for (denom, amount) in rewards {
+            match denom {
+                Denom::Native(denom) => resp.rewards.push(Coin { denom, amount }),
+                Denom::Cw20(address) => resp.cw20_rewards.push(Cw20Coin {
+                    address: address.into(),
+                    amount,
+                }),
+            }
+        }
+        resp
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
@@ -160,6 +299,20 @@ pub struct DelegatedResponse {
     pub delegated: Addr,
 }
 
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
+pub struct DistributionDataResponse {
+    pub distributions: Vec<Distribution>,
+    pub cw20_distributions: Vec<Distribution>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
+pub struct WithdrawAdjustmentDataResponse {
+    pub delegated: Addr,
+    /// Only denoms with any adjustment are listed
+    pub adjustments: Vec<WithdrawAdjustment>,
+    pub cw20_adjustments: Vec<WithdrawAdjustment>,
+}
+
 #[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
 pub struct HalflifeResponse {
     // `None` means the halflife functionality is disabled for this instance.
@@ -170,7 +323,77 @@ pub struct HalflifeResponse {
 pub struct HalflifeInfo {
     pub last_halflife: Timestamp,
     pub halflife: Duration,
+    /// In continuous mode members are decayed lazily and this is only informational.
     pub next_halflife: Timestamp,
+    /// Portion of points removed every `halflife`
+    pub reduction: Decimal,
+    pub mode: DecayMode,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
+pub struct HalflifeProgressResponse {
+    /// Whether a halflife is currently being applied
+    pub pending: bool,
+    /// Last member reduced by the pending halflife
+    pub start_after: Option<String>,
+    /// Number of members processed by the pending halflife
+    pub processed: u64,
+    /// Points removed by the pending halflife so far
+    pub reduction: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
+pub struct AutoWithdrawResponse {
+    /// `None` if the address is not registered for automatic withdrawal
+    pub auto_withdraw: Option<AutoWithdrawal>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct DistributionsResponse {
+    pub distributions: Vec<DistributionRecord>,
+}
+
+/// Rewards accrued by a member in a single distribution
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct MemberReward {
+    /// Id of the distribution
+    pub distribution: u64,
+    pub height: u64,
+    pub time: Timestamp,
+    /// Points of the member at the beginning of the distribution block
+    pub points: u64,
+    /// Total points the rewards were distributed to
+    pub total_points: u64,
+    pub rewards: Vec<Coin>,
+    pub cw20_rewards: Vec<Cw20Coin>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct MemberRewardHistoryResponse {
+    pub rewards: Vec<MemberReward>,
+    /// Id of the last distribution scanned, to be passed as `start_after` for the next page.
+    /// `None` if there were no more distributions.
+    pub last_scanned: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
+pub struct IssuerResponse {
+    pub addr: Addr,
+    pub issuer: Issuer,
+    /// Points awarded within the last `period`
+    pub issued: u64,
+    /// Points which can still be awarded now
+    pub remaining: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
+pub struct IssuersResponse {
+    pub issuers: Vec<IssuerResponse>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
+pub struct IssuancesResponse {
+    pub issuances: Vec<Issuance>,
 }
 
 #[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
@@ -187,6 +410,13 @@ pub struct ListSlashersResponse {
 #[serde(rename_all = "snake_case")]
 pub struct MigrateMsg {
     pub halflife: Option<Duration>,
+    #[serde(default)]
+    pub halflife_reduction: Option<Decimal>,
+    #[serde(default)]
+    pub decay_mode: Option<DecayMode>,
+    /// Maximum number of members reduced per block by the periodic halflife
+    #[serde(default)]
+    pub halflife_batch: Option<u32>,
 }
 
 #[cfg(test)]
@@ -2,6 +2,7 @@ mod suite;
 
 use crate::error::ContractError;
 use cosmwasm_std::{coin, coins, Decimal, Event};
+use cw_controllers::AdminError;
 use suite::{expected_members, SuiteBuilder};
 use tg4::Member;
 use tg_utils::{Duration, PreauthError};
@@ -566,6 +567,110 @@ mod funds_distribution {
         let resp = suite.withdrawable_rewards("unknown").unwrap();
         assert_eq!(resp, coin(0, "usdc"))
     }
+
+    #[test]
+    fn multiple_denoms_distributed() {
+        let members = vec![
+            "member1".to_owned(),
+            "member2".to_owned(),
+            "member3".to_owned(),
+        ];
+
+        let mut suite = SuiteBuilder::new()
+            .with_member(&members[0], 1)
+            .with_member(&members[1], 3)
+            .with_funds(&members[2], 400)
+            .with_denom_funds(&members[2], coin(140, "uatom"))
+            .build();
+
+        let denom = suite.denom.clone();
+        let owner = suite.owner.clone();
+        suite.add_reward_denom(owner.as_str(), "uatom").unwrap();
+
+        suite
+            .distribute_funds(&members[2], None, &[coin(80, "uatom"), coin(400, &denom)])
+            .unwrap();
+
+        assert_eq!(
+            suite.all_withdrawable_rewards(&members[0]).unwrap(),
+            vec![coin(20, "uatom"), coin(100, &denom)]
+        );
+        assert_eq!(
+            suite.all_withdrawable_rewards(&members[1]).unwrap(),
+            vec![coin(60, "uatom"), coin(300, &denom)]
+        );
+
+        // points change affects only future distributions, in every denom
+        suite
+            .modify_members(owner.as_str(), &[(&members[0], 3)], &[])
+            .unwrap();
+        suite
+            .distribute_funds(&members[2], None, &coins(60, "uatom"))
+            .unwrap();
+        assert_eq!(
+            suite.all_withdrawable_rewards(&members[0]).unwrap(),
+            vec![coin(50, "uatom"), coin(100, &denom)]
+        );
+        assert_eq!(
+            suite.all_distributed_funds().unwrap(),
+            vec![coin(140, "uatom"), coin(400, &denom)]
+        );
+
+        // all denoms are withdrawn at once
+        suite.withdraw_funds(&members[0], None, None).unwrap();
+        assert_eq!(suite.token_balance_in(&members[0], "uatom").unwrap(), 50);
+        assert_eq!(suite.token_balance(&members[0]).unwrap(), 100);
+        assert_eq!(
+            suite.all_withdrawable_rewards(&members[0]).unwrap(),
+            vec![coin(0, "uatom"), coin(0, &denom)]
+        );
+        assert_eq!(
+            suite.all_undistributed_funds().unwrap(),
+            vec![coin(0, "uatom"), coin(0, &denom)]
+        );
+    }
+
+    #[test]
+    fn only_reward_denoms_distributed() {
+        let members = vec!["member1".to_owned(), "member2".to_owned()];
+
+        let mut suite = SuiteBuilder::new()
+            .with_member(&members[0], 1)
+            .with_funds(&members[1], 400)
+            .with_denom_funds(&members[1], coin(140, "uatom"))
+            .build();
+
+        let denom = suite.denom.clone();
+        let owner = suite.owner.clone();
+
+        // tokens in other denoms stay in the contract
+        suite
+            .distribute_funds(&members[1], None, &[coin(80, "uatom"), coin(400, &denom)])
+            .unwrap();
+        assert_eq!(
+            suite.all_withdrawable_rewards(&members[0]).unwrap(),
+            vec![coin(400, &denom)]
+        );
+        assert_eq!(
+            suite.all_undistributed_funds().unwrap(),
+            vec![coin(0, &denom)]
+        );
+
+        // only the admin can add reward denoms
+        let err = suite.add_reward_denom(&members[0], "uatom").unwrap_err();
+        assert_eq!(
+            ContractError::Admin(AdminError::NotAdmin {}),
+            err.downcast().unwrap()
+        );
+
+        // once added, tokens received before are distributed as well
+        suite.add_reward_denom(owner.as_str(), "uatom").unwrap();
+        suite.distribute_funds(&members[1], None, &[]).unwrap();
+        assert_eq!(
+            suite.all_withdrawable_rewards(&members[0]).unwrap(),
+            vec![coin(80, "uatom"), coin(400, &denom)]
+        );
+    }
 }
 
 mod slashing {
@@ -941,6 +1046,9 @@ mod migration {
                 &admin,
                 &MigrateMsg {
                     halflife: Some(Duration::new(200)),
+                    halflife_reduction: None,
+                    decay_mode: None,
+                    halflife_batch: None,
                 },
             )
             .unwrap();
@@ -964,6 +1072,9 @@ mod migration {
                 &admin,
                 &MigrateMsg {
                     halflife: Some(Duration::new(0)),
+                    halflife_reduction: None,
+                    decay_mode: None,
+                    halflife_batch: None,
                 },
             )
             .unwrap();
@@ -1,7 +1,7 @@
 use crate::error::ContractError;
 use crate::msg::*;
 use anyhow::Result as AnyResult;
-use cosmwasm_std::{Addr, Coin, CosmosMsg, Decimal, StdResult};
+use cosmwasm_std::{coin, Addr, Coin, CosmosMsg, Decimal, StdResult};
 use cw_multi_test::{AppResponse, Contract, ContractWrapper, CosmosRouter, Executor};
 use derivative::Derivative;
 use tg4::{Member, MemberListResponse};
@@ -16,7 +16,8 @@ fn contract_engagement() -> Box<dyn Contract<TgradeMsg, TgradeQuery>> {
         crate::contract::query,
     )
     .with_migrate(crate::contract::migrate)
-    .with_sudo(crate::contract::sudo);
+    .with_sudo(crate::contract::sudo)
+    .with_reply(crate::contract::reply);
 
     Box::new(contract)
 }
@@ -37,6 +38,7 @@ pub fn expected_members(members: Vec<(&str, u64)>) -> Vec<Member> {
 pub struct SuiteBuilder {
     members: Vec<Member>,
     funds: Vec<(Addr, u128)>,
+    denom_funds: Vec<(Addr, Coin)>,
     halflife: Option<Duration>,
     #[derivative(Default(value = "\"usdc\".to_owned()"))]
     denom: String,
@@ -59,6 +61,12 @@ impl SuiteBuilder {
         self
     }
 
+    /// Sets initial amount of tokens in other than the main denom on address
+    pub fn with_denom_funds(mut self, addr: &str, funds: Coin) -> Self {
+        self.denom_funds.push((Addr::unchecked(addr), funds));
+        self
+    }
+
     pub fn with_halflife(mut self, halflife: Duration) -> Self {
         self.halflife = Some(halflife);
         self
@@ -77,6 +85,7 @@ impl SuiteBuilder {
     #[track_caller]
     pub fn build(self) -> Suite {
         let funds = self.funds;
+        let denom_funds = self.denom_funds;
 
         let owner = Addr::unchecked("owner");
 
@@ -89,15 +98,19 @@ impl SuiteBuilder {
         let denom = self.denom;
 
         app.init_modules(|router, api, storage| -> AnyResult<()> {
-            for (addr, amount) in funds {
+            let funds = funds
+                .into_iter()
+                .map(|(addr, amount)| (addr, coin(amount, &denom)))
+                .chain(denom_funds);
+            for (addr, funds) in funds {
                 router.execute(
                     api,
                     storage,
                     &block_info,
                     owner.clone(),
                     CosmosMsg::Custom(TgradeMsg::MintTokens {
-                        denom: denom.clone(),
-                        amount: amount.into(),
+                        denom: funds.denom,
+                        amount: funds.amount,
                         recipient: addr.to_string(),
                     }),
                 )?;
@@ -118,6 +131,9 @@ impl SuiteBuilder {
                     preauths_hooks: 0,
                     preauths_slashing: self.preauths_slashing,
                     halflife: self.halflife,
+                    halflife_reduction: None,
+                    decay_mode: None,
+                    halflife_batch: None,
                     denom: denom.clone(),
                 },
                 &[],
@@ -190,6 +206,7 @@ impl Suite {
             &ExecuteMsg::WithdrawRewards {
                 owner: owner.into().map(str::to_owned),
                 receiver: receiver.into().map(str::to_owned),
+                denom: None,
             },
             &[],
         )
@@ -235,6 +252,17 @@ impl Suite {
         )
     }
 
+    pub fn add_reward_denom(&mut self, executor: &str, denom: &str) -> AnyResult<AppResponse> {
+        self.app.execute_contract(
+            Addr::unchecked(executor),
+            self.contract.clone(),
+            &ExecuteMsg::AddRewardDenom {
+                denom: denom.to_owned(),
+            },
+            &[],
+        )
+    }
+
     pub fn add_slasher(&mut self, executor: &str, addr: &str) -> AnyResult<AppResponse> {
         self.app.execute_contract(
             Addr::unchecked(executor),
@@ -292,7 +320,19 @@ impl Suite {
         Ok(slashers_list)
     }
 
+    /// Rewards in the main denom of the suite
+    fn main_denom_rewards(&self, rewards: Vec<Coin>) -> Coin {
+        rewards
+            .into_iter()
+            .find(|c| c.denom == self.denom)
+            .unwrap_or_else(|| coin(0, &self.denom))
+    }
+
     pub fn withdrawable_rewards(&self, owner: &str) -> Result<Coin, ContractError> {
+        Ok(self.main_denom_rewards(self.all_withdrawable_rewards(owner)?))
+    }
+
+    pub fn all_withdrawable_rewards(&self, owner: &str) -> Result<Vec<Coin>, ContractError> {
         let resp: RewardsResponse = self.app.wrap().query_wasm_smart(
             self.contract.clone(),
             &QueryMsg::WithdrawableRewards {
@@ -303,6 +343,10 @@ impl Suite {
     }
 
     pub fn distributed_funds(&self) -> Result<Coin, ContractError> {
+        Ok(self.main_denom_rewards(self.all_distributed_funds()?))
+    }
+
+    pub fn all_distributed_funds(&self) -> Result<Vec<Coin>, ContractError> {
         let resp: RewardsResponse = self
             .app
             .wrap()
@@ -311,6 +355,10 @@ impl Suite {
     }
 
     pub fn undistributed_funds(&self) -> Result<Coin, ContractError> {
+        Ok(self.main_denom_rewards(self.all_undistributed_funds()?))
+    }
+
+    pub fn all_undistributed_funds(&self) -> Result<Vec<Coin>, ContractError> {
         let resp: RewardsResponse = self
             .app
             .wrap()
@@ -330,10 +378,14 @@ impl Suite {
 
     /// Shortcut for querying distributeable token balance of contract
     pub fn token_balance(&self, owner: &str) -> StdResult<u128> {
+        self.token_balance_in(owner, &self.denom)
+    }
+
+    pub fn token_balance_in(&self, owner: &str, denom: &str) -> StdResult<u128> {
         let amount = self
             .app
             .wrap()
-            .query_balance(Addr::unchecked(owner), &self.denom)?
+            .query_balance(Addr::unchecked(owner), denom)?
             .amount;
         Ok(amount.into())
     }
@@ -1,10 +1,19 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
+use crate::error::ContractError;
 use crate::i128::Int128;
-use cosmwasm_std::{Addr, Timestamp, Uint128};
-use cw_storage_plus::{Item, Map};
-use tg_utils::Duration;
+use cosmwasm_std::Order::Ascending;
+use cosmwasm_std::{
+    to_binary, Addr, Coin, Decimal, Deps, DepsMut, Env, Response, StdError, StdResult, Storage,
+    Timestamp, Uint128,
+};
+use cw2::{get_contract_version, set_contract_version, ContractVersion};
+use cw20::{Cw20Coin, Denom};
+use cw_storage_plus::{Index, IndexList, IndexedMap, Item, Map, MultiIndex};
+use tg4::{Member, MemberInfo};
+use tg_bindings::{TgradeMsg, TgradeQuery};
+use tg_utils::{members, Duration, ADMIN, HOOKS, PREAUTH_HOOKS, TOTAL};
 pub use tg_utils::{PREAUTH_SLASHING, SLASHERS};
 
 #[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
@@ -13,6 +22,39 @@ pub struct Halflife {
     pub halflife: Option<Duration>,
 
     pub last_applied: Timestamp,
+
+    /// Portion of points removed every halflife period, `0.5` unless configured otherwise
+    #[serde(default = "default_reduction")]
+    pub reduction: Decimal,
+
+    /// How the decay is applied
+    #[serde(default)]
+    pub mode: DecayMode,
+
+    /// Maximum number of members reduced in a single block by the periodic halflife
+    #[serde(default = "default_batch_size")]
+    pub batch_size: u32,
+}
+
+fn default_reduction() -> Decimal {
+    Decimal::percent(50)
+}
+
+pub const DEFAULT_HALFLIFE_BATCH: u32 = 100;
+
+fn default_batch_size() -> u32 {
+    DEFAULT_HALFLIFE_BATCH
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, JsonSchema, Debug, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum DecayMode {
+    /// All members are reduced at once in `end_block`, every time the halflife passes
+    #[default]
+    Periodic,
+    /// Points decay with elapsed time, and are reduced lazily whenever the member is touched.
+    /// Between full periods the reduction is interpolated linearly.
+    Continuous,
 }
 
 impl Halflife {
@@ -23,6 +65,53 @@ impl Halflife {
             false
         }
     }
+
+    pub fn is_continuous(&self) -> bool {
+        self.halflife.is_some() && self.mode == DecayMode::Continuous
+    }
+
+    /// How many points are removed from `points` by a single full halflife period.
+    /// Members with a single point left are never reduced.
+    pub fn reduction_of(&self, points: u64) -> u64 {
+        if points <= 1 {
+            return 0;
+        }
+        let kept = Uint128::from(points) * (Decimal::one() - self.reduction);
+        points - kept.u128() as u64
+    }
+
+    /// Points left from `points` after decaying continuously from `since` until `now`. Returns
+    /// them together with the timestamp the decay is accounted up to - the fractional part of the
+    /// period is only consumed if it removed at least one point, so frequent touches cannot
+    /// escape the decay by rounding.
+    pub fn decayed(&self, points: u64, since: Timestamp, now: Timestamp) -> (u64, Timestamp) {
+        let period = match self.halflife {
+            Some(halflife) if halflife.seconds() > 0 => halflife.seconds(),
+            _ => return (points, now),
+        };
+        if now <= since {
+            return (points, since);
+        }
+
+        let elapsed = now.seconds() - since.seconds();
+        let mut points = points;
+        for _ in 0..elapsed / period {
+            let reduction = self.reduction_of(points);
+            if reduction == 0 {
+                return (points, now);
+            }
+            points -= reduction;
+        }
+
+        let reached = since.plus_seconds(elapsed / period * period);
+        let partial = (self.reduction_of(points) as u128 * (elapsed % period) as u128
+            / period as u128) as u64;
+        if partial == 0 {
+            (points, reached)
+        } else {
+            (points - partial, now)
+        }
+    }
 }
 
 /// How much points is the worth of single token in rewards distribution.
@@ -37,6 +126,26 @@ pub const SHARES_SHIFT: u8 = 32;
 
 pub const HALFLIFE: Item<Halflife> = Item::new("halflife");
 
+/// Periodic halflife being applied over several blocks
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug, Default)]
+pub struct HalflifeJob {
+    /// Last member processed, `None` if the halflife has just started
+    pub start_after: Option<Addr>,
+    /// Number of members processed so far
+    pub processed: u64,
+    /// Points removed so far
+    pub reduction: u64,
+    /// Height of the block the halflife started in
+    #[serde(default)]
+    pub started_at: u64,
+}
+
+pub const HALFLIFE_JOB: Item<HalflifeJob> = Item::new("halflife_job");
+
+/// Time up to which the continuous decay was applied to the member. Members missing here are
+/// decayed since `Halflife::last_applied`.
+pub const LAST_DECAY: Map<&Addr, Timestamp> = Map::new("last_decay");
+
 #[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
 pub struct Distribution {
     /// Tokens can be distributed by this denom.
@@ -51,20 +160,577 @@ pub struct Distribution {
     pub withdrawable_total: Uint128,
 }
 
+impl Distribution {
+    pub fn new(denom: impl Into<String>) -> Self {
+        Distribution {
+            denom: denom.into(),
+            shares_per_point: Uint128::zero(),
+            shares_leftover: 0,
+            distributed_total: Uint128::zero(),
+            withdrawable_total: Uint128::zero(),
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
 pub struct WithdrawAdjustment {
+    /// Denom the adjustment applies to
+    pub denom: String,
     /// How much points should be added/removed from calculated funds while withdrawal.
     pub shares_correction: Int128,
     /// How much funds addresses already withdrawn.
     pub withdrawn_rewards: Uint128,
-    /// User delegated for funds withdrawal
-    pub delegated: Addr,
 }
 
-/// Rewards distribution data
-pub const DISTRIBUTION: Item<Distribution> = Item::new("distribution");
-/// Information how to exactly adjust rewards while withdrawal
-pub const WITHDRAW_ADJUSTMENT: Map<&Addr, WithdrawAdjustment> = Map::new("withdraw_adjustment");
+impl WithdrawAdjustment {
+    pub fn new(denom: impl Into<String>) -> Self {
+        WithdrawAdjustment {
+            denom: denom.into(),
+            shares_correction: Int128::zero(),
+            withdrawn_rewards: Uint128::zero(),
+        }
+    }
+}
+
+/// Rewards distribution data, per denom
+pub const DISTRIBUTION: Map<&str, Distribution> = Map::new("distributions");
+/// Information how to exactly adjust rewards while withdrawal, per address and denom. Missing
+/// entries mean no adjustment.
+pub const WITHDRAW_ADJUSTMENT: Map<(&Addr, &str), WithdrawAdjustment> =
+    Map::new("withdraw_adjustments");
+/// Rewards distribution data, per cw20 token contract. `Distribution::denom` is the token address.
+pub const CW20_DISTRIBUTION: Map<&Addr, Distribution> = Map::new("cw20_distributions");
+/// Information how to exactly adjust cw20 rewards while withdrawal, per address and token
+pub const CW20_WITHDRAW_ADJUSTMENT: Map<(&Addr, &Addr), WithdrawAdjustment> =
+    Map::new("cw20_withdraw_adjustments");
+/// Address delegated for funds withdrawal, the owner himself if missing
+pub const DELEGATED: Map<&Addr, Addr> = Map::new("delegated");
+
+/// Settings of automatic rewards withdrawal
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
+pub struct AutoWithdrawal {
+    /// Address rewards are sent to
+    pub receiver: Addr,
+    /// Rewards in any token are sent only once they reach this amount
+    pub min_amount: Uint128,
+}
+
+/// Members registered for automatic withdrawal
+pub const AUTO_WITHDRAWALS: Map<&Addr, AutoWithdrawal> = Map::new("auto_withdrawals");
+
+/// Last member processed by automatic withdrawal, the next block continues after it
+pub const AUTO_WITHDRAW_CURSOR: Item<Addr> = Item::new("auto_withdraw_cursor");
+
+/// Rewards sent by a single automatic withdrawal message, restored to the owner if it fails
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct PendingWithdrawal {
+    pub owner: Addr,
+    pub rewards: Vec<(Denom, Uint128)>,
+}
+
+/// Automatic withdrawals sent in the current block, by reply id. Messages are executed within the
+/// same block, so the entries are obsolete by the next one.
+pub const PENDING_WITHDRAWALS: Map<u64, PendingWithdrawal> = Map::new("pending_withdrawals");
+
+/// Single rewards distribution, kept for the history
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct DistributionRecord {
+    pub id: u64,
+    pub height: u64,
+    pub time: Timestamp,
+    /// Declared sender of the rewards
+    pub sender: Addr,
+    /// Total points the rewards were distributed to
+    pub total_points: u64,
+    /// Distributed native tokens, ordered by denom
+    pub rewards: Vec<Coin>,
+    /// Distributed cw20 tokens
+    pub cw20_rewards: Vec<Cw20Coin>,
+    /// Shares assigned to a single point by the distribution, one for every entry of `rewards`
+    #[serde(default)]
+    pub shares_per_point: Vec<Uint128>,
+    /// Shares assigned to a single point by the distribution, one for every entry of
+    /// `cw20_rewards`
+    #[serde(default)]
+    pub cw20_shares_per_point: Vec<Uint128>,
+}
+
+/// History of rewards distributions, by id
+pub const DISTRIBUTION_HISTORY: Map<u64, DistributionRecord> = Map::new("distribution_history");
+
+/// Number of rewards distributions so far, used to assign their ids
+pub const DISTRIBUTION_COUNT: Item<u64> = Item::new("distribution_count");
+
+/// Points granted until some time, removed from the member once it passes
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
+pub struct Grant {
+    pub addr: Addr,
+    /// What is left of the granted points - reduced in the same proportion as the member's
+    /// points whenever they decay or are slashed
+    pub points: u64,
+}
+
+/// Expiring grants, by expiration time (in seconds) and id
+pub const GRANTS: Map<(u64, u64), Grant> = Map::new("grants");
+
+/// Expiration time (in seconds) of expiring grants, by member and grant id
+pub const MEMBER_GRANTS: Map<(&Addr, u64), u64> = Map::new("member_grants");
+
+/// Reduces what is left of all grants of the member in the proportion its points were reduced
+/// from `old` to `new`. Rounding down makes sure grants never exceed the member's points.
+pub fn scale_grants(storage: &mut dyn Storage, addr: &Addr, old: u64, new: u64) -> StdResult<()> {
+    if old == 0 || new >= old {
+        return Ok(());
+    }
+    let grants = MEMBER_GRANTS
+        .prefix(addr)
+        .range(storage, None, None, Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    for (id, expires_at) in grants {
+        GRANTS.update(storage, (expires_at, id), |grant| -> StdResult<_> {
+            let mut grant = grant.ok_or_else(|| StdError::not_found("Grant"))?;
+            grant.points = (grant.points as u128 * new as u128 / old as u128) as u64;
+            Ok(grant)
+        })?;
+    }
+    Ok(())
+}
+
+/// Removes all grants of the member
+pub fn remove_grants(storage: &mut dyn Storage, addr: &Addr) -> StdResult<()> {
+    let grants = MEMBER_GRANTS
+        .prefix(addr)
+        .range(storage, None, None, Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    for (id, expires_at) in grants {
+        GRANTS.remove(storage, (expires_at, id));
+        MEMBER_GRANTS.remove(storage, (addr, id));
+    }
+    Ok(())
+}
+
+/// Number of expiring grants created so far, used to assign their ids
+pub const GRANT_COUNT: Item<u64> = Item::new("grant_count");
+
+/// Address allowed to award points besides the admin
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
+pub struct Issuer {
+    /// Maximum number of points the issuer can award within any `period`
+    pub cap: u64,
+    /// Length of the rolling period the cap applies to
+    pub period: Duration,
+    /// If set, points can be awarded only to these addresses
+    pub allowlist: Option<Vec<Addr>>,
+}
+
+pub const ISSUERS: Map<&Addr, Issuer> = Map::new("issuers");
+
+/// Number of buckets the rolling period of an issuer is split into
+pub const ISSUED_BUCKETS: u64 = 24;
+
+/// Points awarded by an issuer, summed up in buckets of `period / ISSUED_BUCKETS` seconds, so
+/// the rolling period is tracked without walking the issuance log
+#[derive(Serialize, Deserialize, Clone, Default, PartialEq, Eq, JsonSchema, Debug)]
+pub struct IssuedPoints {
+    /// End of the bucket in seconds and points awarded within it, oldest first
+    pub buckets: Vec<(u64, u64)>,
+}
+
+impl IssuedPoints {
+    /// Points awarded within `period` ending at `now`. Whole buckets are counted, so points
+    /// awarded up to a bucket before the period still count against the cap.
+    pub fn in_period(&self, period: Duration, now: Timestamp) -> u64 {
+        let since = now.seconds().saturating_sub(period.seconds());
+        self.buckets
+            .iter()
+            .filter(|(end, _)| *end > since)
+            .map(|(_, points)| points)
+            .sum()
+    }
+
+    /// Records points awarded at `now`, dropping buckets which left the period
+    pub fn add(&mut self, period: Duration, now: Timestamp, points: u64) {
+        let since = now.seconds().saturating_sub(period.seconds());
+        self.buckets.retain(|(end, _)| *end > since);
+
+        let length = (period.seconds() / ISSUED_BUCKETS).max(1);
+        let end = now.seconds() - now.seconds() % length + length;
+        match self.buckets.last_mut() {
+            Some((last, issued)) if *last == end => *issued += points,
+            _ => self.buckets.push((end, points)),
+        }
+    }
+}
+
+/// Recent points awarded by every issuer
+pub const ISSUED: Map<&Addr, IssuedPoints> = Map::new("issued");
+
+/// Points awarded by an issuer in a single call
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
+pub struct Issuance {
+    pub id: u64,
+    pub issuer: Addr,
+    /// Awarded members with their points
+    pub recipients: Vec<(Addr, u64)>,
+    /// Sum of points awarded to all recipients
+    pub points: u64,
+    pub height: u64,
+    pub time: Timestamp,
+}
+
+pub struct IssuanceIndexes<'a> {
+    pub issuer: MultiIndex<'a, Addr, Issuance, u64>,
+}
+
+impl<'a> IndexList<Issuance> for IssuanceIndexes<'a> {
+    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<Issuance>> + '_> {
+        let v: Vec<&dyn Index<Issuance>> = vec![&self.issuer];
+        Box::new(v.into_iter())
+    }
+}
+
+/// Log of points awarded by issuers, indexed by id, and additionally by issuer.
+pub fn issuances<'a>() -> IndexedMap<'a, u64, Issuance, IssuanceIndexes<'a>> {
+    let indexes = IssuanceIndexes {
+        issuer: MultiIndex::new(
+            |_, issuance| issuance.issuer.clone(),
+            "issuances",
+            "issuances__issuer",
+        ),
+    };
+    IndexedMap::new("issuances", indexes)
+}
+
+/// Number of issuances recorded so far, used to assign their ids
+pub const ISSUANCE_COUNT: Item<u64> = Item::new("issuance_count");
+
+/// Rewards related state of a single address
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
+pub struct AccountInfo {
+    pub addr: Addr,
+    /// Adjustments of native rewards, `WithdrawAdjustment::denom` is the native denom
+    pub adjustments: Vec<WithdrawAdjustment>,
+    /// Adjustments of cw20 rewards, `WithdrawAdjustment::denom` is the token address
+    pub cw20_adjustments: Vec<WithdrawAdjustment>,
+    pub delegated: Option<Addr>,
+    pub auto_withdraw: Option<AutoWithdrawal>,
+    /// Time up to which the continuous decay was applied
+    pub last_decay: Option<Timestamp>,
+}
+
+/// Expiring grant, together with its key
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
+pub struct GrantInfo {
+    pub id: u64,
+    pub expires_at: Timestamp,
+    pub addr: Addr,
+    pub points: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
+pub struct IssuerInfo {
+    pub addr: Addr,
+    pub issuer: Issuer,
+    pub issued: IssuedPoints,
+}
+
+/// Export / Import state
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct EngagementState {
+    pub contract_version: ContractVersion,
+    pub admin: Option<Addr>,
+    pub halflife: Halflife,
+    pub halflife_job: Option<HalflifeJob>,
+    pub members: Vec<Member>,
+    /// Native rewards distributions
+    pub distributions: Vec<Distribution>,
+    /// cw20 rewards distributions, `Distribution::denom` is the token address
+    pub cw20_distributions: Vec<Distribution>,
+    pub accounts: Vec<AccountInfo>,
+    pub hooks: Vec<String>,
+    pub preauths_hooks: u64,
+    pub preauths_slashing: u64,
+    pub slashers: Vec<String>,
+    pub grants: Vec<GrantInfo>,
+    pub issuers: Vec<IssuerInfo>,
+    pub issuances: Vec<Issuance>,
+    pub distribution_history: Vec<DistributionRecord>,
+}
+
+/// Export state
+pub fn export(deps: Deps<TgradeQuery>) -> Result<Response<TgradeMsg>, ContractError> {
+    // Engagement state items
+    let mut state = EngagementState {
+        contract_version: get_contract_version(deps.storage)?,
+        admin: ADMIN.get(deps)?,
+        halflife: HALFLIFE.load(deps.storage)?,
+        halflife_job: HALFLIFE_JOB.may_load(deps.storage)?,
+        members: vec![],
+        distributions: DISTRIBUTION
+            .range(deps.storage, None, None, Ascending)
+            .map(|r| r.map(|(_, distribution)| distribution))
+            .collect::<StdResult<_>>()?,
+        cw20_distributions: CW20_DISTRIBUTION
+            .range(deps.storage, None, None, Ascending)
+            .map(|r| r.map(|(_, distribution)| distribution))
+            .collect::<StdResult<_>>()?,
+        accounts: vec![],
+        hooks: HOOKS.list_hooks(deps.storage)?,
+        preauths_hooks: PREAUTH_HOOKS.get_auth(deps.storage)?,
+        preauths_slashing: PREAUTH_SLASHING.get_auth(deps.storage)?,
+        slashers: SLASHERS.list_slashers(deps.storage)?,
+        grants: vec![],
+        issuers: vec![],
+        issuances: vec![],
+        distribution_history: vec![],
+    };
+
+    // Member items
+    state.members = members()
+        .range(deps.storage, None, None, Ascending)
+        .map(|r| {
+            let (addr, info) = r?;
+            Ok(Member {
+                addr: addr.to_string(),
+                points: info.points,
+                start_height: info.start_height,
+            })
+        })
+        .collect::<StdResult<_>>()?;
+
+    // Account items, an address may have any of them
+    let mut addrs: Vec<Addr> = WITHDRAW_ADJUSTMENT
+        .keys(deps.storage, None, None, Ascending)
+        .map(|key| key.map(|(addr, _)| addr))
+        .chain(
+            CW20_WITHDRAW_ADJUSTMENT
+                .keys(deps.storage, None, None, Ascending)
+                .map(|key| key.map(|(addr, _)| addr)),
+        )
+        .chain(DELEGATED.keys(deps.storage, None, None, Ascending))
+        .chain(AUTO_WITHDRAWALS.keys(deps.storage, None, None, Ascending))
+        .chain(LAST_DECAY.keys(deps.storage, None, None, Ascending))
+        .collect::<StdResult<_>>()?;
+    addrs.sort();
+    addrs.dedup();
+    state.accounts = addrs
+        .into_iter()
+        .map(|addr| {
+            Ok(AccountInfo {
+                adjustments: WITHDRAW_ADJUSTMENT
+                    .prefix(&addr)
+                    .range(deps.storage, None, None, Ascending)
+                    .map(|r| r.map(|(_, adjustment)| adjustment))
+                    .collect::<StdResult<_>>()?,
+                cw20_adjustments: CW20_WITHDRAW_ADJUSTMENT
+                    .prefix(&addr)
+                    .range(deps.storage, None, None, Ascending)
+                    .map(|r| r.map(|(_, adjustment)| adjustment))
+                    .collect::<StdResult<_>>()?,
+                delegated: DELEGATED.may_load(deps.storage, &addr)?,
+                auto_withdraw: AUTO_WITHDRAWALS.may_load(deps.storage, &addr)?,
+                last_decay: LAST_DECAY.may_load(deps.storage, &addr)?,
+                addr,
+            })
+        })
+        .collect::<StdResult<_>>()?;
+
+    // Grant items
+    state.grants = GRANTS
+        .range(deps.storage, None, None, Ascending)
+        .map(|r| {
+            let ((expires_at, id), grant) = r?;
+            Ok(GrantInfo {
+                id,
+                expires_at: Timestamp::from_seconds(expires_at),
+                addr: grant.addr,
+                points: grant.points,
+            })
+        })
+        .collect::<StdResult<_>>()?;
+
+    // Issuer items
+    state.issuers = ISSUERS
+        .range(deps.storage, None, None, Ascending)
+        .map(|r| {
+            let (addr, issuer) = r?;
+            let issued = ISSUED.may_load(deps.storage, &addr)?.unwrap_or_default();
+            Ok(IssuerInfo {
+                addr,
+                issuer,
+                issued,
+            })
+        })
+        .collect::<StdResult<_>>()?;
+    state.issuances = issuances()
+        .range(deps.storage, None, None, Ascending)
+        .map(|r| r.map(|(_, issuance)| issuance))
+        .collect::<StdResult<_>>()?;
+
+    // Distribution history items
+    state.distribution_history = DISTRIBUTION_HISTORY
+        .range(deps.storage, None, None, Ascending)
+        .map(|r| r.map(|(_, record)| record))
+        .collect::<StdResult<_>>()?;
+
+    Ok(Response::new().set_data(to_binary(&state)?))
+}
+
+/// Import state
+pub fn import(
+    mut deps: DepsMut<TgradeQuery>,
+    env: Env,
+    state: EngagementState,
+) -> Result<Response<TgradeMsg>, ContractError> {
+    // Engagement state items
+    set_contract_version(
+        deps.storage,
+        state.contract_version.contract,
+        state.contract_version.version,
+    )?;
+    ADMIN.set(deps.branch(), state.admin)?;
+    HALFLIFE.save(deps.storage, &state.halflife)?;
+    match state.halflife_job {
+        // member history is not imported, imported points count as the ones at the start
+        Some(job) => HALFLIFE_JOB.save(
+            deps.storage,
+            &HalflifeJob {
+                started_at: env.block.height,
+                ..job
+            },
+        )?,
+        None => HALFLIFE_JOB.remove(deps.storage),
+    }
+    PREAUTH_HOOKS.set_auth(deps.storage, state.preauths_hooks)?;
+    PREAUTH_SLASHING.set_auth(deps.storage, state.preauths_slashing)?;
+
+    // Member items
+    // Delete all existing members
+    let addrs = members()
+        .keys(deps.storage, None, None, Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    for addr in addrs.iter() {
+        members().remove(deps.storage, addr, env.block.height)?;
+    }
+    // Import members, and their total points
+    let mut total = 0;
+    for member in &state.members {
+        let info = MemberInfo {
+            points: member.points,
+            start_height: member.start_height,
+        };
+        members().save(
+            deps.storage,
+            &Addr::unchecked(&member.addr),
+            &info,
+            env.block.height,
+        )?;
+        total += member.points;
+    }
+    TOTAL.save(deps.storage, &total)?;
+
+    // Distribution items
+    DISTRIBUTION.clear(deps.storage);
+    for distribution in &state.distributions {
+        DISTRIBUTION.save(deps.storage, &distribution.denom, distribution)?;
+    }
+    CW20_DISTRIBUTION.clear(deps.storage);
+    for distribution in &state.cw20_distributions {
+        let token = Addr::unchecked(&distribution.denom);
+        CW20_DISTRIBUTION.save(deps.storage, &token, distribution)?;
+    }
+
+    // Account items
+    WITHDRAW_ADJUSTMENT.clear(deps.storage);
+    CW20_WITHDRAW_ADJUSTMENT.clear(deps.storage);
+    DELEGATED.clear(deps.storage);
+    AUTO_WITHDRAWALS.clear(deps.storage);
+    AUTO_WITHDRAW_CURSOR.remove(deps.storage);
+    PENDING_WITHDRAWALS.clear(deps.storage);
+    LAST_DECAY.clear(deps.storage);
+    for account in &state.accounts {
+        let addr = &account.addr;
+        for adjustment in &account.adjustments {
+            WITHDRAW_ADJUSTMENT.save(deps.storage, (addr, &adjustment.denom), adjustment)?;
+        }
+        for adjustment in &account.cw20_adjustments {
+            let token = Addr::unchecked(&adjustment.denom);
+            CW20_WITHDRAW_ADJUSTMENT.save(deps.storage, (addr, &token), adjustment)?;
+        }
+        if let Some(delegated) = &account.delegated {
+            DELEGATED.save(deps.storage, addr, delegated)?;
+        }
+        if let Some(auto_withdraw) = &account.auto_withdraw {
+            AUTO_WITHDRAWALS.save(deps.storage, addr, auto_withdraw)?;
+        }
+        if let Some(last_decay) = &account.last_decay {
+            LAST_DECAY.save(deps.storage, addr, last_decay)?;
+        }
+    }
+
+    // Hook items
+    for hook in HOOKS.list_hooks(deps.storage)? {
+        HOOKS.remove_hook(deps.storage, Addr::unchecked(hook))?;
+    }
+    for hook in state.hooks {
+        HOOKS.add_hook(deps.storage, Addr::unchecked(hook))?;
+    }
+
+    // Slasher items
+    SLASHERS.instantiate(deps.storage)?;
+    for slasher in state.slashers {
+        SLASHERS.add_slasher(deps.storage, Addr::unchecked(slasher))?;
+    }
+
+    // Grant items, continuing their numbering
+    GRANTS.clear(deps.storage);
+    MEMBER_GRANTS.clear(deps.storage);
+    let mut grant_count = 0;
+    for grant in state.grants {
+        let key = (grant.expires_at.seconds(), grant.id);
+        MEMBER_GRANTS.save(deps.storage, (&grant.addr, grant.id), &key.0)?;
+        let value = Grant {
+            addr: grant.addr,
+            points: grant.points,
+        };
+        GRANTS.save(deps.storage, key, &value)?;
+        grant_count = grant_count.max(grant.id);
+    }
+    GRANT_COUNT.save(deps.storage, &grant_count)?;
+
+    // Issuer items
+    ISSUERS.clear(deps.storage);
+    ISSUED.clear(deps.storage);
+    for info in &state.issuers {
+        ISSUERS.save(deps.storage, &info.addr, &info.issuer)?;
+        ISSUED.save(deps.storage, &info.addr, &info.issued)?;
+    }
+    // Delete all existing issuances
+    let ids = issuances()
+        .keys(deps.storage, None, None, Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    for id in ids {
+        issuances().remove(deps.storage, id)?;
+    }
+    // Import issuances, continuing their numbering
+    let mut issuance_count = 0;
+    for issuance in &state.issuances {
+        issuances().save(deps.storage, issuance.id, issuance)?;
+        issuance_count = issuance_count.max(issuance.id);
+    }
+    ISSUANCE_COUNT.save(deps.storage, &issuance_count)?;
+
+    // Distribution history items, continuing their numbering
+    DISTRIBUTION_HISTORY.clear(deps.storage);
+    let mut distribution_count = 0;
+    for record in &state.distribution_history {
+        DISTRIBUTION_HISTORY.save(deps.storage, record.id, record)?;
+        distribution_count = distribution_count.max(record.id);
+    }
+    DISTRIBUTION_COUNT.save(deps.storage, &distribution_count)?;
+
+    Ok(Response::default())
+}
 
 #[cfg(test)]
 mod tests {
@@ -76,18 +742,27 @@ mod tests {
         let hf = Halflife {
             halflife: None,
             last_applied: Timestamp::from_seconds(epoch),
+            reduction: default_reduction(),
+            mode: DecayMode::Periodic,
+            batch_size: DEFAULT_HALFLIFE_BATCH,
         };
         assert!(!hf.should_apply(Timestamp::from_seconds(epoch)));
 
         let hf = Halflife {
             halflife: Some(Duration::new(epoch + 1)),
             last_applied: Timestamp::from_seconds(epoch),
+            reduction: default_reduction(),
+            mode: DecayMode::Periodic,
+            batch_size: DEFAULT_HALFLIFE_BATCH,
         };
         assert!(!hf.should_apply(Timestamp::from_seconds(epoch)));
 
         let hf = Halflife {
             halflife: Some(Duration::new(epoch + 1)),
             last_applied: Timestamp::from_seconds(epoch),
+            reduction: default_reduction(),
+            mode: DecayMode::Periodic,
+            batch_size: DEFAULT_HALFLIFE_BATCH,
         };
         // because halflife + last_applied + 1 = one second after half life is expected to be met
         assert!(hf.should_apply(Timestamp::from_seconds(epoch * 2 + 1)));
@@ -95,13 +770,99 @@ mod tests {
         let hf = Halflife {
             halflife: Some(Duration::new(epoch + 1)),
             last_applied: Timestamp::from_seconds(epoch + 2),
+            reduction: default_reduction(),
+            mode: DecayMode::Periodic,
+            batch_size: DEFAULT_HALFLIFE_BATCH,
         };
         assert!(!hf.should_apply(Timestamp::from_seconds(epoch + 2)));
 
         let hf = Halflife {
             halflife: Some(Duration::new(epoch + 1)),
             last_applied: Timestamp::from_seconds(epoch + 2),
+            reduction: default_reduction(),
+            mode: DecayMode::Periodic,
+            batch_size: DEFAULT_HALFLIFE_BATCH,
         };
         assert!(hf.should_apply(Timestamp::from_seconds(epoch * 2 + 3)));
     }
+
+    #[test]
+    fn configurable_reduction() {
+        let hf = Halflife {
+            halflife: Some(Duration::new(100)),
+            last_applied: Timestamp::from_seconds(0),
+            reduction: default_reduction(),
+            mode: DecayMode::Periodic,
+            batch_size: DEFAULT_HALFLIFE_BATCH,
+        };
+        // default matches the original rounding: `points - points / 2`
+        assert_eq!(hf.reduction_of(11), 6);
+        assert_eq!(hf.reduction_of(6), 3);
+        assert_eq!(hf.reduction_of(1), 0);
+
+        let hf = Halflife {
+            reduction: Decimal::percent(10),
+            ..hf
+        };
+        assert_eq!(hf.reduction_of(1000), 100);
+        assert_eq!(hf.reduction_of(11), 2);
+        assert_eq!(hf.reduction_of(2), 1);
+    }
+
+    #[test]
+    fn issued_points_roll_by_bucket() {
+        // buckets of 10 seconds
+        let period = Duration::new(240);
+        let at = Timestamp::from_seconds;
+
+        let mut issued = IssuedPoints::default();
+        issued.add(period, at(5), 3);
+        issued.add(period, at(8), 2);
+        issued.add(period, at(125), 4);
+        assert_eq!(issued.buckets, vec![(10, 5), (130, 4)]);
+
+        // the first bucket counts until its end leaves the period
+        assert_eq!(issued.in_period(period, at(249)), 9);
+        assert_eq!(issued.in_period(period, at(250)), 4);
+
+        issued.add(period, at(255), 1);
+        assert_eq!(issued.buckets, vec![(130, 4), (260, 1)]);
+        assert_eq!(issued.in_period(period, at(370)), 1);
+    }
+
+    #[test]
+    fn continuous_decay() {
+        let hf = Halflife {
+            halflife: Some(Duration::new(100)),
+            last_applied: Timestamp::from_seconds(0),
+            reduction: Decimal::percent(10),
+            mode: DecayMode::Continuous,
+            batch_size: DEFAULT_HALFLIFE_BATCH,
+        };
+        let start = Timestamp::from_seconds(1000);
+
+        // nothing elapsed
+        assert_eq!(hf.decayed(1000, start, start), (1000, start));
+        // two full periods
+        assert_eq!(
+            hf.decayed(1000, start, start.plus_seconds(200)),
+            (810, start.plus_seconds(200))
+        );
+        // half of the third period is interpolated
+        assert_eq!(
+            hf.decayed(1000, start, start.plus_seconds(250)),
+            (770, start.plus_seconds(250))
+        );
+        // too short to remove a single point, the time is not consumed
+        assert_eq!(
+            hf.decayed(1000, start, start.plus_seconds(200).plus_nanos(1)),
+            (810, start.plus_seconds(200))
+        );
+        assert_eq!(hf.decayed(5, start, start.plus_seconds(10)), (5, start));
+        // a single point never decays
+        assert_eq!(
+            hf.decayed(1, start, start.plus_seconds(10_000)),
+            (1, start.plus_seconds(10_000))
+        );
+    }
 }
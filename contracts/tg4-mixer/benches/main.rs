@@ -9,7 +9,9 @@ use cosmwasm_vm::testing::{
 };
 use cosmwasm_vm::{capabilities_from_csv, from_slice, Instance};
 
-use tg4_mixer::msg::PoEFunctionType::{AlgebraicSigmoid, GeometricMean, Sigmoid, SigmoidSqrt};
+use tg4_mixer::msg::PoEFunctionType::{
+    AlgebraicSigmoid, GeometricMean, IntegerSigmoid, Sigmoid, SigmoidSqrt,
+};
 use tg4_mixer::msg::{MixerFunctionResponse, QueryMsg};
 
 fn mock_instance_on_tgrade(wasm: &[u8]) -> Instance<MockApi, MockStorage, MockQuerier> {
@@ -46,6 +48,7 @@ fn main() {
     let s = Decimal::from_ratio(3u128, 100000u128);
     let s_sqrt = Decimal::from_ratio(3u128, 10000u128);
 
+    let mut gas_usage = vec![];
     println!();
     for (poe_fn_name, poe_fn, result, gas) in [
         ("GeometricMean", GeometricMean {}, 22360, 5729550000i64),
@@ -55,6 +58,12 @@ fn main() {
             MAX_POINTS,
             89533650000,
         ),
+        (
+            "IntegerSigmoid",
+            IntegerSigmoid { max_points, p, s },
+            999,
+            10766550000,
+        ),
         (
             "SigmoidSqrt",
             SigmoidSqrt {
@@ -104,5 +113,13 @@ fn main() {
             "{} gas",
             poe_fn_name
         );
+        gas_usage.push((poe_fn_name, gas_used));
     }
+
+    // The integer approximation is only worth it as long as it is cheaper
+    let gas_of = |name| gas_usage.iter().find(|(n, _)| *n == name).unwrap().1;
+    assert!(
+        gas_of("IntegerSigmoid") < gas_of("Sigmoid"),
+        "IntegerSigmoid is not cheaper than Sigmoid"
+    );
 }
@@ -1,8 +1,10 @@
+use std::cmp::Reverse;
+
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    to_binary, Addr, Binary, CustomQuery, Decimal, Deps, DepsMut, Empty, Env, MessageInfo, Order,
-    StdError, StdResult,
+    to_binary, Addr, Binary, CustomQuery, Decimal, Deps, DepsMut, Env, MessageInfo, Order,
+    QuerierWrapper, StdError, StdResult,
 };
 
 use cw2::set_contract_version;
@@ -11,7 +13,7 @@ use cw_utils::{ensure_from_older_version, maybe_addr};
 
 use tg_bindings::{TgradeMsg, TgradeQuery};
 use tg_utils::{
-    validate_portion, SlashMsg, HOOKS, PREAUTH_HOOKS, PREAUTH_SLASHING, SLASHERS, TOTAL,
+    validate_portion, SlashMsg, ADMIN, HOOKS, PREAUTH_HOOKS, PREAUTH_SLASHING, SLASHERS, TOTAL,
 };
 
 use tg4::{
@@ -23,10 +25,10 @@ use crate::error::ContractError;
 use crate::functions::PoEFunction;
 use crate::member_indexes::members;
 use crate::msg::{
-    ExecuteMsg, GroupsResponse, InstantiateMsg, MixerFunctionResponse, PoEFunctionType,
-    PreauthResponse, QueryMsg,
+    ExecuteMsg, GroupsResponse, InstantiateMsg, MigrateMsg, MixerFunctionResponse, PoEFunctionType,
+    PreauthResponse, QueryMsg, RecalculationResponse, SimulateFunctionResponse, SimulatedMember,
 };
-use crate::state::{Groups, GROUPS, POE_FUNCTION_TYPE};
+use crate::state::{Groups, Recalculation, GROUPS, POE_FUNCTION_TYPE, RECALCULATION};
 
 pub type Response = cosmwasm_std::Response<TgradeMsg>;
 pub type SubMsg = cosmwasm_std::SubMsg<TgradeMsg>;
@@ -37,12 +39,14 @@ const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
-    deps: DepsMut<TgradeQuery>,
+    mut deps: DepsMut<TgradeQuery>,
     env: Env,
     _info: MessageInfo,
     msg: InstantiateMsg,
 ) -> Result<Response, ContractError> {
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+    let api = deps.api;
+    ADMIN.set(deps.branch(), maybe_addr(api, msg.admin)?)?;
     PREAUTH_HOOKS.set_auth(deps.storage, msg.preauths_hooks)?;
     PREAUTH_SLASHING.set_auth(deps.storage, msg.preauths_slashing)?;
 
@@ -51,13 +55,21 @@ pub fn instantiate(
     // Store the PoE function type / params
     POE_FUNCTION_TYPE.save(deps.storage, &msg.function_type)?;
 
-    // validate the two input groups and save
+    // validate the input groups and save
     let left = verify_tg4_input(deps.as_ref(), &msg.left_group)?;
     let right = verify_tg4_input(deps.as_ref(), &msg.right_group)?;
-    let groups = Groups { left, right };
+    let extra = msg
+        .extra_groups
+        .iter()
+        .map(|addr| verify_tg4_input(deps.as_ref(), addr))
+        .collect::<Result<_, _>>()?;
+    let groups = Groups { left, right, extra };
     GROUPS.save(deps.storage, &groups)?;
 
-    // Register this contract as a slasher in left and right contracts
+    // Instantiate PoE function
+    let poe_function = to_groups_poe_fn(&msg.function_type, &groups)?;
+
+    // Register this contract as a slasher in all input contracts
     // Slashing is not part of the TG4 spec - it's a separate interface
     // that these contracts must implement.
     let slash_msg = to_binary(&SlashMsg::AddSlasher {
@@ -65,16 +77,15 @@ pub fn instantiate(
     })?;
 
     // add hooks to listen for all changes
-    let res = Response::new()
-        .add_submessage(groups.left.add_hook(&env.contract.address)?)
-        .add_submessage(groups.right.add_hook(&env.contract.address)?)
-        .add_submessage(groups.left.encode_raw_msg(slash_msg.clone())?)
-        .add_submessage(groups.right.encode_raw_msg(slash_msg)?);
-
-    // Instantiate PoE function
-    let poe_function = msg.function_type.to_poe_fn()?;
+    let mut res = Response::new();
+    for group in groups.all() {
+        res = res.add_submessage(group.add_hook(&env.contract.address)?);
+    }
+    for group in groups.all() {
+        res = res.add_submessage(group.encode_raw_msg(slash_msg.clone())?);
+    }
 
-    // calculate initial state from current members on both sides
+    // calculate initial state from current members of all groups
     initialize_members(deps, groups, &*poe_function, env.block.height)?;
     Ok(res)
 }
@@ -90,6 +101,38 @@ fn verify_tg4_input<Q: CustomQuery>(
     Ok(contract)
 }
 
+/// Instantiates the PoE function, checking it mixes all the groups
+fn to_groups_poe_fn(
+    function_type: &PoEFunctionType,
+    groups: &Groups,
+) -> Result<Box<dyn PoEFunction>, ContractError> {
+    let poe_function = function_type.to_poe_fn()?;
+    let inputs = groups.all().count();
+    if poe_function.inputs() != inputs {
+        return Err(ContractError::InputsMismatch {
+            expected: poe_function.inputs(),
+            got: inputs,
+        });
+    }
+    Ok(poe_function)
+}
+
+/// Mixes points of `addr` in all the groups, `None` if it is missing from any of them
+fn mix_member<Q: CustomQuery>(
+    querier: &QuerierWrapper<Q>,
+    groups: &Groups,
+    poe_function: &dyn PoEFunction,
+    addr: &Addr,
+) -> Result<Option<u64>, ContractError> {
+    let inputs = groups
+        .all()
+        .map(|group| group.is_member(querier, addr))
+        .collect::<StdResult<Option<Vec<_>>>>()?;
+    inputs
+        .map(|inputs| poe_function.mix_all(&inputs))
+        .transpose()
+}
+
 const QUERY_LIMIT: Option<u32> = Some(30);
 
 fn initialize_members<Q: CustomQuery>(
@@ -99,19 +142,25 @@ fn initialize_members<Q: CustomQuery>(
     height: u64,
 ) -> Result<(), ContractError> {
     let mut total = 0u64;
-    // we query all members of left group - for each non-None value, we check the value of right group and mix it.
-    // Either as None means "not a member"
+    // we query all members of left group - for each non-None value, we check the values of other groups and mix them.
+    // Any None means "not a member"
     let mut batch = groups.left.list_members(&deps.querier, None, QUERY_LIMIT)?;
     while !batch.is_empty() {
         let last = Some(batch.last().unwrap().addr.clone());
-        // check it's points in the other group, and calculate/save the mixed points if in both
+        // check it's points in the other groups, and calculate/save the mixed points if in all
         for member in batch.into_iter() {
             let addr = deps.api.addr_validate(&member.addr)?;
-            // note that this is a *raw query* and therefore quite cheap compared to a *smart query*
-            // like calling `list_members` on the right side as well
-            let other = groups.right.is_member(&deps.querier, &addr)?;
-            if let Some(right) = other {
-                let points = poe_function.mix(member.points, right)?;
+            // note that these are *raw queries* and therefore quite cheap compared to a *smart query*
+            // like calling `list_members` on the other groups as well
+            let mut inputs = vec![member.points];
+            for group in groups.all().skip(1) {
+                match group.is_member(&deps.querier, &addr)? {
+                    Some(points) => inputs.push(points),
+                    None => break,
+                }
+            }
+            if inputs.len() == poe_function.inputs() {
+                let points = poe_function.mix_all(&inputs)?;
                 total += points;
                 members().save(
                     deps.storage,
@@ -135,6 +184,7 @@ pub fn execute(
     info: MessageInfo,
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
+    let api = deps.api;
     match msg {
         ExecuteMsg::MemberChangedHook(changes) => execute_member_changed(deps, env, info, changes),
         ExecuteMsg::AddHook { addr } => execute_add_hook(deps, info, addr),
@@ -142,6 +192,13 @@ pub fn execute(
         ExecuteMsg::AddSlasher { addr } => execute_add_slasher(deps, info, addr),
         ExecuteMsg::RemoveSlasher { addr } => execute_remove_slasher(deps, info, addr),
         ExecuteMsg::Slash { addr, portion } => execute_slash(deps, env, info, addr, portion),
+        ExecuteMsg::UpdateAdmin { admin } => ADMIN
+            .execute_update_admin(deps, info, maybe_addr(api, admin)?)
+            .map_err(Into::into),
+        ExecuteMsg::UpdateFunction { function_type } => {
+            execute_update_function(deps, info, function_type)
+        }
+        ExecuteMsg::RecalculatePoints { limit } => execute_recalculate_points(deps, env, limit),
     }
 }
 
@@ -159,29 +216,21 @@ pub fn execute_member_changed<Q: CustomQuery>(
     let groups = GROUPS.load(deps.storage)?;
 
     // authorization check
-    let diff = if info.sender == groups.left.addr() {
-        let poe_function = POE_FUNCTION_TYPE.load(deps.storage)?.to_poe_fn()?;
-        update_members(
-            deps.branch(),
-            env.block.height,
-            groups.right,
-            changes.diffs,
-            &*poe_function,
-        )
-    } else if info.sender == groups.right.addr() {
-        let poe_function = POE_FUNCTION_TYPE.load(deps.storage)?.to_poe_fn()?;
-        update_members(
-            deps.branch(),
-            env.block.height,
-            groups.left,
-            changes.diffs,
-            &*poe_function,
-        )
-    } else {
-        Err(ContractError::Unauthorized(
-            "Sender is neither left nor right side group contract".to_owned(),
-        ))
-    }?;
+    let changed = groups
+        .all()
+        .position(|group| group.addr() == info.sender)
+        .ok_or_else(|| {
+            ContractError::Unauthorized("Sender is not one of the mixed group contracts".to_owned())
+        })?;
+    let poe_function = POE_FUNCTION_TYPE.load(deps.storage)?.to_poe_fn()?;
+    let diff = update_members(
+        deps.branch(),
+        env.block.height,
+        &groups,
+        changed,
+        changes.diffs,
+        &*poe_function,
+    )?;
 
     // call all registered hooks
     res.messages = HOOKS.prepare_hooks(deps.storage, |h| {
@@ -191,10 +240,12 @@ pub fn execute_member_changed<Q: CustomQuery>(
 }
 
 // the logic from execute_update_members extracted for easier re-usability
+// `changed` is the index of the group the `changes` come from, the other groups are queried
 pub fn update_members<Q: CustomQuery>(
     deps: DepsMut<Q>,
     height: u64,
-    query_group: Tg4Contract,
+    groups: &Groups,
+    changed: usize,
     changes: Vec<MemberDiff>,
     poe_function: &dyn PoEFunction,
 ) -> Result<MemberChangedHookMsg, ContractError> {
@@ -205,10 +256,19 @@ pub fn update_members<Q: CustomQuery>(
     for change in changes {
         let member_addr = deps.api.addr_validate(&change.key)?;
         let new_points = match change.new {
-            Some(x) => match query_group.is_member(&deps.querier, &member_addr)? {
-                Some(y) => Some(poe_function.mix(x, y)?),
-                None => None,
-            },
+            Some(x) => {
+                let inputs = groups
+                    .all()
+                    .enumerate()
+                    .map(|(idx, group)| match idx == changed {
+                        true => Ok(Some(x)),
+                        false => group.is_member(&deps.querier, &member_addr),
+                    })
+                    .collect::<StdResult<Option<Vec<_>>>>()?;
+                inputs
+                    .map(|inputs| poe_function.mix_all(&inputs))
+                    .transpose()?
+            }
             None => None,
         };
         let mems = members();
@@ -245,6 +305,102 @@ pub fn update_members<Q: CustomQuery>(
     Ok(MemberChangedHookMsg { diffs })
 }
 
+pub fn execute_update_function<Q: CustomQuery>(
+    deps: DepsMut<Q>,
+    info: MessageInfo,
+    function_type: PoEFunctionType,
+) -> Result<Response, ContractError> {
+    ADMIN.assert_admin(deps.as_ref(), &info.sender)?;
+
+    let groups = GROUPS.load(deps.storage)?;
+    to_groups_poe_fn(&function_type, &groups)?;
+    POE_FUNCTION_TYPE.save(deps.storage, &function_type)?;
+
+    // members keep their points until recalculated, any pending recalculation starts over
+    RECALCULATION.save(deps.storage, &Recalculation::default())?;
+
+    let res = Response::new()
+        .add_attribute("action", "update_function")
+        .add_attribute("sender", info.sender);
+    Ok(res)
+}
+
+pub fn execute_recalculate_points<Q: CustomQuery>(
+    deps: DepsMut<Q>,
+    env: Env,
+    limit: Option<u32>,
+) -> Result<Response, ContractError> {
+    if limit == Some(0) {
+        return Err(ContractError::ZeroLimit {});
+    }
+    let mut recalculation = RECALCULATION
+        .may_load(deps.storage)?
+        .ok_or(ContractError::NoRecalculation {})?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let groups = GROUPS.load(deps.storage)?;
+    let poe_function = POE_FUNCTION_TYPE.load(deps.storage)?.to_poe_fn()?;
+
+    let batch = members()
+        .range(
+            deps.storage,
+            recalculation.start_after.as_ref().map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .take(limit)
+        .collect::<StdResult<Vec<_>>>()?;
+    let done = batch.len() < limit;
+
+    let mut total = TOTAL.load(deps.storage)?;
+    let mut diffs = vec![];
+    for (addr, old) in batch {
+        recalculation.start_after = Some(addr.clone());
+        recalculation.processed += 1;
+
+        // members are in all the groups, unless a hook is still on its way
+        let new = mix_member(&deps.querier, &groups, &*poe_function, &addr)?;
+        if new == Some(old.points) {
+            continue;
+        }
+
+        total -= old.points;
+        total += new.unwrap_or_default();
+        match new {
+            Some(points) => members().save(
+                deps.storage,
+                &addr,
+                &MemberInfo::new_with_height(points, old.start_height.unwrap_or(env.block.height)),
+                env.block.height,
+            )?,
+            None => members().remove(deps.storage, &addr, env.block.height)?,
+        }
+        diffs.push(MemberDiff::new(addr, Some(old.points), new));
+    }
+    TOTAL.save(deps.storage, &total)?;
+
+    if done {
+        RECALCULATION.remove(deps.storage);
+    } else {
+        RECALCULATION.save(deps.storage, &recalculation)?;
+    }
+
+    let mut res = Response::new()
+        .add_attribute("action", "recalculate_points")
+        .add_attribute("processed", recalculation.processed.to_string())
+        .add_attribute("finished", done.to_string());
+
+    // alert the hooks with all the changes at once
+    if !diffs.is_empty() {
+        res = res.add_submessages(HOOKS.prepare_hooks(deps.storage, |h| {
+            MemberChangedHookMsg::new(diffs.clone())
+                .into_cosmos_msg(h)
+                .map(SubMsg::new)
+        })?);
+    }
+
+    Ok(res)
+}
+
 pub fn execute_add_hook<Q: CustomQuery>(
     deps: DepsMut<Q>,
     info: MessageInfo,
@@ -353,12 +509,13 @@ pub fn execute_slash<Q: CustomQuery>(
     })?;
 
     // response
-    let res = Response::new()
+    let mut res = Response::new()
         .add_attribute("action", "slash")
         .add_attribute("addr", &addr)
-        .add_attribute("sender", info.sender)
-        .add_submessage(groups.left.encode_raw_msg(slash_msg.clone())?)
-        .add_submessage(groups.right.encode_raw_msg(slash_msg)?);
+        .add_attribute("sender", info.sender);
+    for group in groups.all() {
+        res = res.add_submessage(group.encode_raw_msg(slash_msg.clone())?);
+    }
     Ok(res)
 }
 
@@ -398,6 +555,26 @@ pub fn query(deps: Deps<TgradeQuery>, _env: Env, msg: QueryMsg) -> StdResult<Bin
             to_binary(&SLASHERS.is_slasher(deps.storage, &addr)?)
         }
         ListSlashers {} => to_binary(&SLASHERS.list_slashers(deps.storage)?),
+        Admin {} => to_binary(&ADMIN.query_admin(deps)?),
+        SimulateFunction {
+            poe_function,
+            start_after,
+            limit,
+        } => to_binary(
+            &query_simulate_function(deps, poe_function, start_after, limit)
+                .map_err(|err| StdError::generic_err(err.to_string()))?,
+        ),
+        PointsRecalculation {} => {
+            let recalculation = RECALCULATION.may_load(deps.storage)?;
+            to_binary(&RecalculationResponse {
+                pending: recalculation.is_some(),
+                start_after: recalculation
+                    .as_ref()
+                    .and_then(|r| r.start_after.as_ref())
+                    .map(Addr::to_string),
+                processed: recalculation.map(|r| r.processed).unwrap_or_default(),
+            })
+        }
     }
 }
 
@@ -409,8 +586,9 @@ fn query_total_points<Q: CustomQuery>(deps: Deps<Q>) -> StdResult<TotalPointsRes
 fn query_groups<Q: CustomQuery>(deps: Deps<Q>) -> StdResult<GroupsResponse> {
     let groups = GROUPS.load(deps.storage)?;
     Ok(GroupsResponse {
-        left: groups.left.0.into(),
-        right: groups.right.0.into(),
+        left: groups.left.0.to_string(),
+        right: groups.right.0.to_string(),
+        groups: groups.all().map(|group| group.0.to_string()).collect(),
     })
 }
 
@@ -518,13 +696,78 @@ pub fn query_mixer_function<Q: CustomQuery>(
     poe_function.mix(stake, engagement)
 }
 
+pub fn query_simulate_function<Q: CustomQuery>(
+    deps: Deps<Q>,
+    poe_function: PoEFunctionType,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> Result<SimulateFunctionResponse, ContractError> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start_after = maybe_addr(deps.api, start_after)?;
+    let groups = GROUPS.load(deps.storage)?;
+    let poe_function = to_groups_poe_fn(&poe_function, &groups)?;
+
+    let batch = members()
+        .range(
+            deps.storage,
+            start_after.as_ref().map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .take(limit)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    let mut simulated = vec![];
+    // ties are broken the same way `ListMembersByPoints` does, by the ascending start height,
+    // and then the descending address
+    let mut tie_breaks = vec![];
+    for (addr, old) in batch {
+        let new_points = mix_member(&deps.querier, &groups, &*poe_function, &addr)?;
+        tie_breaks.push((-(old.start_height.unwrap_or_default() as i64), addr.clone()));
+        simulated.push(SimulatedMember {
+            addr: addr.into(),
+            old_points: old.points,
+            new_points,
+            old_rank: 0,
+            new_rank: 0,
+        });
+    }
+
+    let mut by_old: Vec<_> = (0..simulated.len()).collect();
+    by_old.sort_by_key(|&i| Reverse((simulated[i].old_points, &tie_breaks[i])));
+    let mut by_new: Vec<_> = (0..simulated.len()).collect();
+    by_new.sort_by_key(|&i| Reverse((simulated[i].new_points, &tie_breaks[i])));
+    for (rank, i) in by_old.into_iter().enumerate() {
+        simulated[i].old_rank = rank as u32 + 1;
+    }
+    for (rank, i) in by_new.into_iter().enumerate() {
+        simulated[i].new_rank = rank as u32 + 1;
+    }
+
+    let old_total = simulated.iter().map(|member| member.old_points).sum();
+    let new_total = simulated
+        .iter()
+        .filter_map(|member| member.new_points)
+        .sum();
+    Ok(SimulateFunctionResponse {
+        members: simulated,
+        old_total,
+        new_total,
+    })
+}
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn migrate(
     deps: DepsMut<TgradeQuery>,
     _env: Env,
-    _msg: Empty,
+    msg: MigrateMsg,
 ) -> Result<Response, ContractError> {
     ensure_from_older_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    if let Some(admin) = msg.admin {
+        let admin = deps.api.addr_validate(&admin)?;
+        ADMIN.set(deps, Some(admin))?;
+    }
     Ok(Response::new())
 }
 
@@ -532,7 +775,8 @@ pub fn migrate(
 mod tests {
     use super::*;
     use crate::msg::PoEFunctionType;
-    use cosmwasm_std::{coins, Addr, BankMsg, Uint128};
+    use cosmwasm_std::{coins, Addr, BankMsg, Decimal as StdDecimal, Uint128, Uint64};
+    use cw_controllers::AdminError;
     use cw_multi_test::{next_block, AppBuilder, BasicApp, Contract, ContractWrapper, Executor};
     use tg_bindings::{TgradeMsg, TgradeQuery};
 
@@ -591,6 +835,9 @@ mod tests {
             preauths_hooks: 1,
             preauths_slashing: 1,
             halflife: None,
+            halflife_reduction: None,
+            decay_mode: None,
+            halflife_batch: None,
             denom: STAKE_DENOM.to_owned(),
         };
         app.instantiate_contract(group_id, Addr::unchecked(OWNER), &msg, &[], "group", admin)
@@ -613,6 +860,9 @@ mod tests {
             preauths_hooks: 1,
             preauths_slashing: 1,
             auto_return_limit: 0,
+            extra_denoms: vec![],
+            lock_tiers: vec![],
+            instant_unbond: None,
         };
         let contract = app
             .instantiate_contract(
@@ -634,6 +884,7 @@ mod tests {
             // they stake to the contract
             let msg = tg4_stake::msg::ExecuteMsg::Bond {
                 vesting_tokens: None,
+                lock_duration: None,
             };
             app.execute_contract(caller.clone(), contract.clone(), &msg, &balance)
                 .unwrap();
@@ -647,16 +898,27 @@ mod tests {
         left: &Addr,
         right: &Addr,
     ) -> Addr {
+        instantiate_mixer_with(app, left, right, &[], PoEFunctionType::GeometricMean {}).unwrap()
+    }
+
+    fn instantiate_mixer_with(
+        app: &mut BasicApp<TgradeMsg, TgradeQuery>,
+        left: &Addr,
+        right: &Addr,
+        extra: &[&Addr],
+        function_type: PoEFunctionType,
+    ) -> anyhow::Result<Addr> {
         let flex_id = app.store_code(contract_mixer());
         let msg = crate::msg::InstantiateMsg {
+            admin: Some(OWNER.to_owned()),
             left_group: left.to_string(),
             right_group: right.to_string(),
+            extra_groups: extra.iter().map(|addr| addr.to_string()).collect(),
             preauths_hooks: 0,
             preauths_slashing: 1,
-            function_type: PoEFunctionType::GeometricMean {},
+            function_type,
         };
         app.instantiate_contract(flex_id, Addr::unchecked(OWNER), &msg, &[], "mixer", None)
-            .unwrap()
     }
 
     /// this will set up all 3 contracts contracts, instantiating the group with
@@ -829,6 +1091,7 @@ mod tests {
         .unwrap();
         let msg = tg4_stake::msg::ExecuteMsg::Bond {
             vesting_tokens: None,
+            lock_duration: None,
         };
         app.execute_contract(Addr::unchecked(VOTER5), staker_addr, &msg, &balance)
             .unwrap();
@@ -958,6 +1221,413 @@ mod tests {
         );
     }
 
+    #[test]
+    fn mixes_extra_groups() {
+        let stakers = vec![
+            member(VOTER1, 10000), // 10000 stake, 100 points, 100 extra -> 1000 mixed
+            member(VOTER3, 7500),  // 7500 stake, 300 points, no extra -> None
+        ];
+
+        let mut app = AppBuilder::new_custom().build(|router, _, storage| {
+            for staker in &stakers {
+                router
+                    .bank
+                    .init_balance(
+                        storage,
+                        &Addr::unchecked(&staker.addr),
+                        coins(staker.points as u128, STAKE_DENOM),
+                    )
+                    .unwrap();
+            }
+        });
+
+        let group_addr =
+            instantiate_group(&mut app, vec![member(VOTER1, 100), member(VOTER3, 300)]);
+        let stake_addr = instantiate_staking(&mut app, stakers);
+        let extra_addr = instantiate_group(&mut app, vec![member(VOTER1, 100)]);
+        app.update_block(next_block);
+
+        // two weights for three groups is rejected
+        let err = instantiate_mixer_with(
+            &mut app,
+            &group_addr,
+            &stake_addr,
+            &[&extra_addr],
+            PoEFunctionType::GeometricMean {},
+        )
+        .unwrap_err();
+        assert_eq!(
+            ContractError::InputsMismatch {
+                expected: 2,
+                got: 3
+            },
+            err.downcast().unwrap()
+        );
+
+        let mixer_addr = instantiate_mixer_with(
+            &mut app,
+            &group_addr,
+            &stake_addr,
+            &[&extra_addr],
+            PoEFunctionType::WeightedGeometricMean {
+                weights: vec![
+                    StdDecimal::percent(25),
+                    StdDecimal::percent(50),
+                    StdDecimal::percent(25),
+                ],
+            },
+        )
+        .unwrap();
+        app.update_block(next_block);
+
+        let groups: GroupsResponse = app
+            .wrap()
+            .query_wasm_smart(&mixer_addr, &QueryMsg::Groups {})
+            .unwrap();
+        assert_eq!(groups.left, group_addr.to_string());
+        assert_eq!(groups.right, stake_addr.to_string());
+        assert_eq!(
+            groups.groups,
+            vec![
+                group_addr.to_string(),
+                stake_addr.to_string(),
+                extra_addr.to_string()
+            ]
+        );
+
+        check_membership(&app, &mixer_addr, None, Some(1000), None, None, None, None);
+
+        // joining the extra group makes a member of the mixer
+        app.execute_contract(
+            Addr::unchecked(OWNER),
+            extra_addr,
+            &tg4_engagement::msg::ExecuteMsg::UpdateMembers {
+                add: vec![member(VOTER3, 300)],
+                remove: vec![],
+            },
+            &[],
+        )
+        .unwrap();
+        check_membership(
+            &app,
+            &mixer_addr,
+            None,
+            Some(1000),
+            None,
+            Some(1500), // (300, 7500, 300) mixed
+            None,
+            None,
+        );
+
+        // change in the left group is mixed with both other groups
+        app.execute_contract(
+            Addr::unchecked(OWNER),
+            group_addr,
+            &tg4_engagement::msg::ExecuteMsg::UpdateMembers {
+                add: vec![member(VOTER1, 1600)],
+                remove: vec![],
+            },
+            &[],
+        )
+        .unwrap();
+        check_membership(
+            &app,
+            &mixer_addr,
+            None,
+            Some(2000), // (1600, 10000, 100) mixed
+            None,
+            Some(1500),
+            None,
+            None,
+        );
+    }
+
+    #[test]
+    fn update_function_recalculates_points() {
+        let stakers = vec![
+            member(VOTER1, 10000), // 10000 stake, 100 points -> 1000 mixed
+            member(VOTER3, 7500),  // 7500 stake, 300 points -> 1500 mixed
+        ];
+
+        let mut app = AppBuilder::new_custom().build(|router, _, storage| {
+            for staker in &stakers {
+                router
+                    .bank
+                    .init_balance(
+                        storage,
+                        &Addr::unchecked(&staker.addr),
+                        coins(staker.points as u128, STAKE_DENOM),
+                    )
+                    .unwrap();
+            }
+        });
+
+        let (mixer_addr, _, _) = setup_test_case(&mut app, stakers);
+        check_membership(
+            &app,
+            &mixer_addr,
+            None,
+            Some(1000),
+            None,
+            Some(1500),
+            None,
+            None,
+        );
+
+        let recalculate = |app: &mut BasicApp<TgradeMsg, TgradeQuery>, limit| {
+            app.execute_contract(
+                Addr::unchecked(VOTER5),
+                mixer_addr.clone(),
+                &ExecuteMsg::RecalculatePoints { limit: Some(limit) },
+                &[],
+            )
+        };
+        let progress = |app: &BasicApp<TgradeMsg, TgradeQuery>| -> RecalculationResponse {
+            app.wrap()
+                .query_wasm_smart(&mixer_addr, &QueryMsg::PointsRecalculation {})
+                .unwrap()
+        };
+
+        let err = recalculate(&mut app, 10).unwrap_err();
+        assert_eq!(ContractError::NoRecalculation {}, err.downcast().unwrap());
+
+        // `x * y / 10_000`, capped at 1000
+        let function_type = PoEFunctionType::PiecewiseLinear {
+            input: crate::msg::PiecewiseInput::Product,
+            points: vec![
+                (Uint128::zero(), Uint64::zero()),
+                (Uint128::new(10_000_000), Uint64::new(1000)),
+            ],
+        };
+
+        // only admin can update the function
+        let err = app
+            .execute_contract(
+                Addr::unchecked(VOTER1),
+                mixer_addr.clone(),
+                &ExecuteMsg::UpdateFunction {
+                    function_type: function_type.clone(),
+                },
+                &[],
+            )
+            .unwrap_err();
+        assert_eq!(
+            ContractError::Admin(AdminError::NotAdmin {}),
+            err.downcast().unwrap()
+        );
+
+        // and it has to mix all the groups
+        let err = app
+            .execute_contract(
+                Addr::unchecked(OWNER),
+                mixer_addr.clone(),
+                &ExecuteMsg::UpdateFunction {
+                    function_type: PoEFunctionType::WeightedSigmoid {
+                        max_points: Uint64::new(1000),
+                        weights: vec![StdDecimal::percent(50); 3],
+                        s: StdDecimal::percent(1),
+                    },
+                },
+                &[],
+            )
+            .unwrap_err();
+        assert_eq!(
+            ContractError::InputsMismatch {
+                expected: 3,
+                got: 2
+            },
+            err.downcast().unwrap()
+        );
+
+        app.execute_contract(
+            Addr::unchecked(OWNER),
+            mixer_addr.clone(),
+            &ExecuteMsg::UpdateFunction { function_type },
+            &[],
+        )
+        .unwrap();
+
+        // points are not changed until recalculated
+        check_membership(
+            &app,
+            &mixer_addr,
+            None,
+            Some(1000),
+            None,
+            Some(1500),
+            None,
+            None,
+        );
+        assert_eq!(
+            progress(&app),
+            RecalculationResponse {
+                pending: true,
+                start_after: None,
+                processed: 0,
+            }
+        );
+
+        let err = recalculate(&mut app, 0).unwrap_err();
+        assert_eq!(ContractError::ZeroLimit {}, err.downcast().unwrap());
+
+        recalculate(&mut app, 1).unwrap();
+        check_membership(
+            &app,
+            &mixer_addr,
+            None,
+            Some(100),
+            None,
+            Some(1500),
+            None,
+            None,
+        );
+        assert_eq!(
+            progress(&app),
+            RecalculationResponse {
+                pending: true,
+                start_after: Some(VOTER1.to_owned()),
+                processed: 1,
+            }
+        );
+
+        recalculate(&mut app, 2).unwrap();
+        check_membership(
+            &app,
+            &mixer_addr,
+            None,
+            Some(100),
+            None,
+            Some(225),
+            None,
+            None,
+        );
+        assert!(!progress(&app).pending);
+        let total: TotalPointsResponse = app
+            .wrap()
+            .query_wasm_smart(&mixer_addr, &QueryMsg::TotalPoints {})
+            .unwrap();
+        assert_eq!(total.points, 325);
+
+        let err = recalculate(&mut app, 2).unwrap_err();
+        assert_eq!(ContractError::NoRecalculation {}, err.downcast().unwrap());
+    }
+
+    #[test]
+    fn simulate_function() {
+        let stakers = vec![
+            member(VOTER1, 10000), // 10000 stake, 100 points -> 1000 mixed
+            member(VOTER2, 20000), // 20000 stake, 200 points -> 2000 mixed
+            member(VOTER3, 7500),  // 7500 stake, 300 points -> 1500 mixed
+        ];
+
+        let mut app = AppBuilder::new_custom().build(|router, _, storage| {
+            for staker in &stakers {
+                router
+                    .bank
+                    .init_balance(
+                        storage,
+                        &Addr::unchecked(&staker.addr),
+                        coins(staker.points as u128, STAKE_DENOM),
+                    )
+                    .unwrap();
+            }
+        });
+
+        let (mixer_addr, _, _) = setup_test_case(&mut app, stakers);
+
+        // engagement weighted more than stake
+        let poe_function = PoEFunctionType::WeightedGeometricMean {
+            weights: vec![StdDecimal::percent(75), StdDecimal::percent(25)],
+        };
+        let simulate = |start_after: Option<&str>, limit| -> SimulateFunctionResponse {
+            app.wrap()
+                .query_wasm_smart(
+                    &mixer_addr,
+                    &QueryMsg::SimulateFunction {
+                        poe_function: poe_function.clone(),
+                        start_after: start_after.map(str::to_owned),
+                        limit: Some(limit),
+                    },
+                )
+                .unwrap()
+        };
+        let simulated = |addr: &str, old_points, new_points, old_rank, new_rank| SimulatedMember {
+            addr: addr.to_owned(),
+            old_points,
+            new_points: Some(new_points),
+            old_rank,
+            new_rank,
+        };
+
+        assert_eq!(
+            simulate(None, 10),
+            SimulateFunctionResponse {
+                members: vec![
+                    simulated(VOTER1, 1000, 316, 3, 3),
+                    simulated(VOTER2, 2000, 632, 1, 2),
+                    simulated(VOTER3, 1500, 670, 2, 1),
+                ],
+                old_total: 4500,
+                new_total: 1618,
+            }
+        );
+
+        // ranks and totals are within the page
+        assert_eq!(
+            simulate(None, 2),
+            SimulateFunctionResponse {
+                members: vec![
+                    simulated(VOTER1, 1000, 316, 2, 2),
+                    simulated(VOTER2, 2000, 632, 1, 1),
+                ],
+                old_total: 3000,
+                new_total: 948,
+            }
+        );
+        assert_eq!(
+            simulate(Some(VOTER2), 2),
+            SimulateFunctionResponse {
+                members: vec![simulated(VOTER3, 1500, 670, 1, 1)],
+                old_total: 1500,
+                new_total: 670,
+            }
+        );
+
+        // points are not changed
+        check_membership(
+            &app,
+            &mixer_addr,
+            None,
+            Some(1000),
+            Some(2000),
+            Some(1500),
+            None,
+            None,
+        );
+
+        // the function has to mix all the groups
+        let err = app
+            .wrap()
+            .query_wasm_smart::<SimulateFunctionResponse>(
+                &mixer_addr,
+                &QueryMsg::SimulateFunction {
+                    poe_function: PoEFunctionType::WeightedGeometricMean {
+                        weights: vec![
+                            StdDecimal::percent(50),
+                            StdDecimal::percent(50),
+                            StdDecimal::zero(),
+                        ],
+                    },
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("Mixer function expects 3 inputs, got 2"));
+    }
+
     #[test]
     fn slashing_works() {
         let stakers = vec![
@@ -1109,6 +1779,7 @@ mod tests {
         .unwrap();
         let msg = tg4_stake::msg::ExecuteMsg::Bond {
             vesting_tokens: None,
+            lock_duration: None,
         };
         app.execute_contract(Addr::unchecked(VOTER2), staker_addr, &msg, &balance)
             .unwrap();
@@ -1,4 +1,5 @@
 use cosmwasm_std::StdError;
+use cw_controllers::AdminError;
 use tg_utils::{HookError, PreauthError, SlasherError};
 use thiserror::Error;
 
@@ -7,6 +8,9 @@ pub enum ContractError {
     #[error("{0}")]
     Std(#[from] StdError),
 
+    #[error("{0}")]
+    Admin(#[from] AdminError),
+
     #[error("{0}")]
     Hook(#[from] HookError),
 
@@ -33,4 +37,13 @@ pub enum ContractError {
 
     #[error("The parameter '{0}' is out of range: {1}")]
     ParameterRange(&'static str, String),
+
+    #[error("Mixer function expects {expected} inputs, got {got}")]
+    InputsMismatch { expected: usize, got: usize },
+
+    #[error("No points recalculation is pending")]
+    NoRecalculation {},
+
+    #[error("Limit must be greater than zero")]
+    ZeroLimit {},
 }
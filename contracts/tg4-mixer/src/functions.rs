@@ -3,9 +3,10 @@ use rust_decimal::prelude::ToPrimitive;
 use rust_decimal::{Decimal, MathematicalOps};
 use rust_decimal_macros::dec;
 
-use cosmwasm_std::{Decimal as StdDecimal, Fraction, Uint64};
+use cosmwasm_std::{Decimal as StdDecimal, Fraction, Uint128, Uint64};
 
 use crate::error::ContractError;
+use crate::msg::PiecewiseInput;
 
 pub fn std_to_decimal(std_decimal: StdDecimal) -> Decimal {
     Decimal::from_i128_with_scale(std_decimal.numerator().u128() as i128, 18) // FIXME: StdDecimal::DECIMAL_PLACES is private (https://github.com/CosmWasm/cosmwasm/issues/1361)
@@ -16,6 +17,88 @@ pub trait PoEFunction {
     /// Returns the points based on the amount of stake and engagement points.
     /// `f(x)` from the README.
     fn mix(&self, stake: u64, engagement: u64) -> Result<u64, ContractError>;
+
+    /// Number of inputs (groups) the function mixes.
+    fn inputs(&self) -> usize {
+        2
+    }
+
+    /// Returns the points based on the points in every mixed group, in the order of groups.
+    fn mix_all(&self, inputs: &[u64]) -> Result<u64, ContractError> {
+        match inputs {
+            [stake, engagement] => self.mix(*stake, *engagement),
+            _ => Err(ContractError::InputsMismatch {
+                expected: self.inputs(),
+                got: inputs.len(),
+            }),
+        }
+    }
+}
+
+/// Product of inputs, every one raised to the power of its weight. `x_1^w_1 * ... * x_n^w_n`
+fn weighted_product(weights: &[Decimal], inputs: &[u64]) -> Result<Decimal, ContractError> {
+    if inputs.len() != weights.len() {
+        return Err(ContractError::InputsMismatch {
+            expected: weights.len(),
+            got: inputs.len(),
+        });
+    }
+
+    let mut product = dec!(1);
+    for (input, weight) in inputs.iter().zip(weights) {
+        // Cast to i64 because of rust_decimal::Decimal underlying impl
+        let input = Decimal::new(*input as i64, 0);
+        // Rejects u64 values larger than 2^63, which become negative in Decimal
+        if input.is_sign_negative() {
+            return Err(ContractError::PointsOverflow {});
+        }
+        product = product
+            .checked_mul(
+                pow_by_roots(input, *weight).ok_or(ContractError::ComputationOverflow("powd"))?,
+            )
+            .ok_or(ContractError::ComputationOverflow("mul"))?;
+    }
+    Ok(product)
+}
+
+/// Maximum number of binary digits of the weight taken into account by `pow_by_roots`
+const POW_ROOTS: usize = 64;
+
+/// `x^weight` for `0 <= weight <= 1`, as a product of repeated square roots of `x`, following the
+/// binary expansion of `weight`. Unlike `checked_powd`, it stays precise for big inputs, and it
+/// is exact up to the square roots for weights like `0.5` or `0.25`.
+fn pow_by_roots(x: Decimal, weight: Decimal) -> Option<Decimal> {
+    if weight >= dec!(1) {
+        return x.checked_powd(weight);
+    }
+    let mut result = dec!(1);
+    let mut root = x;
+    let mut weight = weight;
+    for _ in 0..POW_ROOTS {
+        if weight.is_zero() {
+            break;
+        }
+        root = root.sqrt()?;
+        weight *= dec!(2);
+        if weight >= dec!(1) {
+            result = result.checked_mul(root)?;
+            weight -= dec!(1);
+        }
+    }
+    Some(result)
+}
+
+fn validate_weights(weights: &[StdDecimal]) -> Result<(), ContractError> {
+    if weights.len() < 2 {
+        return Err(ContractError::ParameterRange(
+            "weights",
+            format!("{} inputs", weights.len()),
+        ));
+    }
+    if let Some(weight) = weights.iter().find(|w| **w > StdDecimal::one()) {
+        return Err(ContractError::ParameterRange("weights", weight.to_string()));
+    }
+    Ok(())
 }
 
 /// This takes a geometric mean of stake and engagement points using integer math
@@ -35,6 +118,64 @@ impl PoEFunction for GeometricMean {
     }
 }
 
+/// Weighted geometric mean of any number of inputs. `f(x) = x_1^w_1 * ... * x_n^w_n`, where the
+/// weights sum up to 1. Results just below an integer are taken as that integer, so with two equal
+/// weights it can be a point above the exact integer root of `GeometricMean`.
+pub struct WeightedGeometricMean {
+    pub weights: Vec<Decimal>,
+}
+
+impl WeightedGeometricMean {
+    pub fn new(weights: Vec<StdDecimal>) -> Result<Self, ContractError> {
+        Self::validate(&weights)?;
+        Ok(Self {
+            weights: weights.into_iter().map(std_to_decimal).collect(),
+        })
+    }
+
+    fn validate(weights: &[StdDecimal]) -> Result<(), ContractError> {
+        validate_weights(weights)?;
+
+        let sum = weights
+            .iter()
+            .fold(StdDecimal::zero(), |sum, weight| sum + *weight);
+        if sum != StdDecimal::one() {
+            return Err(ContractError::ParameterRange(
+                "weights",
+                format!("sum {}", sum),
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl PoEFunction for WeightedGeometricMean {
+    fn mix(&self, stake: u64, engagement: u64) -> Result<u64, ContractError> {
+        self.mix_all(&[stake, engagement])
+    }
+
+    fn inputs(&self) -> usize {
+        self.weights.len()
+    }
+
+    fn mix_all(&self, inputs: &[u64]) -> Result<u64, ContractError> {
+        let points = snap_to_integer(weighted_product(&self.weights, inputs)?);
+        points.to_u64().ok_or(ContractError::MixerOverflow {})
+    }
+}
+
+/// Fractional powers are approximated with a tiny error, so results that close to an integer are
+/// taken as exact, instead of being rounded down. The tolerance is absolute, so big results are
+/// never rounded up by a whole point.
+fn snap_to_integer(points: Decimal) -> Decimal {
+    let nearest = points.round();
+    if (points - nearest).abs() <= dec!(0.0001) {
+        nearest
+    } else {
+        points
+    }
+}
+
 /// Sigmoid function. `f(x) = 1 / (1 + e^-x)`.
 /// Fitting the sigmoid-like function from the PoE white-paper:
 /// `f(x) = r_max * (2 / (1 + e ^(-s * x^p) - 1)`
@@ -120,6 +261,125 @@ impl PoEFunction for Sigmoid {
     }
 }
 
+/// One in the fixed-point format used by `IntegerSigmoid`, with 64 fractional bits
+const FIXED_ONE: u128 = 1 << 64;
+/// `ln(2)` in the fixed-point format
+const FIXED_LN_2: u128 = 12_786_308_645_202_655_659;
+/// `log2(e)` in the fixed-point format
+const FIXED_LOG2_E: u128 = 26_613_026_195_688_644_983;
+/// Number of fractional bits computed by `fixed_log2`
+const LOG2_BITS: u32 = 40;
+
+/// Fixed-point multiplication. The result has to fit in the fixed-point format.
+fn fixed_mul(a: u128, b: u128) -> u128 {
+    let (a_hi, a_lo) = (a >> 64, a & (FIXED_ONE - 1));
+    let (b_hi, b_lo) = (b >> 64, b & (FIXED_ONE - 1));
+    ((a_hi * b_hi) << 64) + a_hi * b_lo + a_lo * b_hi + ((a_lo * b_lo) >> 64)
+}
+
+/// Binary logarithm of a positive integer, in the fixed-point format, rounded down to
+/// `LOG2_BITS` fractional bits. They are computed by repeated squaring of the mantissa.
+fn fixed_log2(n: u128) -> u128 {
+    let int = 127 - n.leading_zeros();
+    // mantissa in `[1, 2)`, with 63 fractional bits
+    let mut mantissa = if int >= 63 {
+        n >> (int - 63)
+    } else {
+        n << (63 - int)
+    };
+    let mut log = (int as u128) << 64;
+    for bit in 1..=LOG2_BITS {
+        mantissa = (mantissa * mantissa) >> 63;
+        if mantissa >= 1 << 64 {
+            mantissa >>= 1;
+            log |= 1 << (64 - bit);
+        }
+    }
+    log
+}
+
+/// `2^x` in the fixed-point format, for a signed fixed-point `x` below 63
+fn fixed_exp2(x: i128) -> u128 {
+    let int = x >> 64;
+    if int < -64 {
+        return 0;
+    }
+    // `2^frac = e^(frac * ln(2))`, summing the Taylor series until the terms vanish
+    let frac = ((x - (int << 64)) as u128 * FIXED_LN_2) >> 64;
+    let mut term = FIXED_ONE;
+    let mut sum = FIXED_ONE;
+    let mut k = 1;
+    while term > 0 {
+        term = ((term * frac) >> 64) / k;
+        sum += term;
+        k += 1;
+    }
+
+    if int >= 0 {
+        sum << int
+    } else {
+        sum >> -int
+    }
+}
+
+/// `Sigmoid` computed with fixed-point integer math instead of `rust_decimal`, which makes it
+/// much cheaper to execute.
+/// `f(x) = r_max * (2 / (1 + e ^(-s * x^p) - 1)`
+/// `x^p` is computed as `2^(p * log2(x))`, with the logarithm rounded down to 40 fractional bits,
+/// so the relative error of the exponent `s * x^p` stays below `1e-11`. The results are rounded
+/// down, and are within 1 point of the exact value for `max_points` up to `10^10`. This is
+/// tighter than `Sigmoid`, where `powd` has a relative error of up to about `1e-7`.
+/// Unlike `Sigmoid`, it doesn't overflow for any stake and engagement points.
+pub struct IntegerSigmoid {
+    pub max_points: u64,
+    /// `p` in the fixed-point format
+    pub p: u128,
+    /// `log2(s)` in the signed fixed-point format, `None` for `s = 0`
+    pub log2_s: Option<i128>,
+}
+
+impl IntegerSigmoid {
+    pub fn new(max_points: Uint64, p: StdDecimal, s: StdDecimal) -> Result<Self, ContractError> {
+        // same parameters as `Sigmoid`
+        Sigmoid::validate(&max_points, &p, &s)?;
+
+        let denominator = StdDecimal::one().atomics().u128();
+        let log2_s = match s.atomics().u128() {
+            0 => None,
+            s => Some(fixed_log2(s) as i128 - fixed_log2(denominator) as i128),
+        };
+        Ok(Self {
+            max_points: max_points.u64(),
+            p: p.atomics().u128() * FIXED_ONE / denominator,
+            log2_s,
+        })
+    }
+}
+
+impl PoEFunction for IntegerSigmoid {
+    fn mix(&self, stake: u64, engagement: u64) -> Result<u64, ContractError> {
+        let product = stake as u128 * engagement as u128;
+        let log2_s = match self.log2_s {
+            Some(log2_s) if product > 0 => log2_s,
+            _ => return Ok(0),
+        };
+
+        // `t = s * (stake * engagement)^p`, so `log2(t) = log2(s) + p * log2(stake * engagement)`
+        let log2_t = log2_s + fixed_mul(self.p, fixed_log2(product)) as i128;
+        // past `t = 2^8`, `e^-t` is far below the fixed-point precision
+        if log2_t >= 8 << 64 {
+            return Ok(self.max_points);
+        }
+        let t = fixed_exp2(log2_t);
+        // `e^-t = 2^(-t * log2(e))`
+        let exp = fixed_exp2(-(fixed_mul(t, FIXED_LOG2_E) as i128));
+
+        // `r_max * (2 / (1 + e^-t) - 1) = r_max * (1 - e^-t) / (1 + e^-t)`
+        let points = self.max_points as u128 * (FIXED_ONE - exp) / (FIXED_ONE + exp);
+        Ok(points as u64)
+    }
+}
+
 /// Sigmoid function. `f(x) = 1 / (1 + e^-x)`.
 /// Fitting the sigmoid-like to a 1/2 (sqrt) exponent.
 /// `f(x) = r_max * (2 / (1 + e ^(-s * sqrt(x)) - 1)`
@@ -185,6 +445,80 @@ impl PoEFunction for SigmoidSqrt {
     }
 }
 
+/// `Sigmoid` generalized to any number of inputs, every one with its own exponent.
+/// `f(x) = r_max * (2 / (1 + e ^(-s * x_1^w_1 * ... * x_n^w_n)) - 1)`
+/// With two inputs weighted `p`, it is the same as `Sigmoid`.
+pub struct WeightedSigmoid {
+    pub max_points: Decimal,
+    pub weights: Vec<Decimal>,
+    pub s: Decimal,
+    zero: Decimal,
+    one: Decimal,
+    two: Decimal,
+}
+
+impl WeightedSigmoid {
+    pub fn new(
+        max_points: Uint64,
+        weights: Vec<StdDecimal>,
+        s: StdDecimal,
+    ) -> Result<Self, ContractError> {
+        Self::validate(&max_points, &weights, &s)?;
+        Ok(Self {
+            max_points: Decimal::new(max_points.u64() as i64, 0),
+            weights: weights.into_iter().map(std_to_decimal).collect(),
+            s: std_to_decimal(s),
+            zero: dec!(0),
+            one: dec!(1),
+            two: dec!(2),
+        })
+    }
+
+    fn validate(
+        max_points: &Uint64,
+        weights: &[StdDecimal],
+        s: &StdDecimal,
+    ) -> Result<(), ContractError> {
+        // validate `max_points`
+        if max_points.u64() > i64::MAX as u64 {
+            return Err(ContractError::ParameterRange(
+                "max_points",
+                max_points.to_string(),
+            ));
+        }
+
+        // validate `weights`
+        validate_weights(weights)?;
+
+        // validate `s`
+        if !(StdDecimal::zero()..=StdDecimal::one()).contains(s) {
+            return Err(ContractError::ParameterRange("s", s.to_string()));
+        }
+        Ok(())
+    }
+}
+
+impl PoEFunction for WeightedSigmoid {
+    fn mix(&self, stake: u64, engagement: u64) -> Result<u64, ContractError> {
+        self.mix_all(&[stake, engagement])
+    }
+
+    fn inputs(&self) -> usize {
+        self.weights.len()
+    }
+
+    fn mix_all(&self, inputs: &[u64]) -> Result<u64, ContractError> {
+        let x = weighted_product(&self.weights, inputs)?;
+
+        // Given that `s` is always positive, we replace the underflowed exponential case
+        // with zero (also to extend the range).
+        let points = self.max_points
+            * (self.two / (self.one + (-self.s * x).checked_exp().unwrap_or(self.zero)) - self.one);
+
+        points.to_u64().ok_or(ContractError::MixerOverflow {})
+    }
+}
+
 /// Algebraic sigmoid. `f(x) = x / sqrt(1 + x^2)`.
 /// Fitting the sigmoid-like function from the PoE whitepaper.
 /// `p` and `s` are just equivalent to the `Sigmoid` parameters.
@@ -281,6 +615,85 @@ impl PoEFunction for AlgebraicSigmoid {
     }
 }
 
+/// Piecewise linear function, interpolating between the given points with integer math.
+/// `x` is either `stake * engagement` or `sqrt(stake * engagement)`, and the result is rounded
+/// down. Points past the last `x` are capped to the last `y`.
+pub struct PiecewiseLinear {
+    pub input: PiecewiseInput,
+    pub points: Vec<(u128, u64)>,
+}
+
+impl PiecewiseLinear {
+    pub fn new(
+        input: PiecewiseInput,
+        points: Vec<(Uint128, Uint64)>,
+    ) -> Result<Self, ContractError> {
+        let points: Vec<_> = points
+            .into_iter()
+            .map(|(x, y)| (x.u128(), y.u64()))
+            .collect();
+        Self::validate(&points)?;
+        Ok(Self { input, points })
+    }
+
+    fn validate(points: &[(u128, u64)]) -> Result<(), ContractError> {
+        if points.len() < 2 {
+            return Err(ContractError::ParameterRange(
+                "points",
+                format!("{} points", points.len()),
+            ));
+        }
+
+        // the function has to be defined for all the inputs
+        if points[0].0 != 0 {
+            return Err(ContractError::ParameterRange(
+                "points",
+                format!("first x {}", points[0].0),
+            ));
+        }
+
+        // and be monotonic
+        for pair in points.windows(2) {
+            let ((x1, y1), (x2, y2)) = (pair[0], pair[1]);
+            if x2 <= x1 {
+                return Err(ContractError::ParameterRange(
+                    "points",
+                    format!("x {} not above {}", x2, x1),
+                ));
+            }
+            if y2 < y1 {
+                return Err(ContractError::ParameterRange(
+                    "points",
+                    format!("y {} below {}", y2, y1),
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl PoEFunction for PiecewiseLinear {
+    fn mix(&self, stake: u64, engagement: u64) -> Result<u64, ContractError> {
+        let product = (stake as u128) * (engagement as u128);
+        let x = match self.input {
+            PiecewiseInput::Product => product,
+            PiecewiseInput::GeometricMean => product.integer_sqrt(),
+        };
+
+        // index of the first point past `x`, never 0 as the first point is at `x = 0`
+        let idx = self.points.partition_point(|(px, _)| *px <= x);
+        if idx == self.points.len() {
+            return Ok(self.points[idx - 1].1);
+        }
+
+        // `y1 + (y2 - y1) * (x - x1) / (x2 - x1)`, which never exceeds `y2`
+        let (x1, y1) = self.points[idx - 1];
+        let (x2, y2) = self.points[idx];
+        let delta = Uint128::from(y2 - y1).multiply_ratio(x - x1, x2 - x1);
+        Ok(y1 + delta.u128() as u64)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -304,6 +717,125 @@ mod tests {
         assert_eq!(geometric.mix(very_big, very_big).unwrap(), very_big);
     }
 
+    #[test]
+    fn snapping_to_integer_is_absolute() {
+        assert_eq!(snap_to_integer(dec!(999.99999)), dec!(1000));
+        assert_eq!(snap_to_integer(dec!(1000.00001)), dec!(1000));
+        assert_eq!(snap_to_integer(dec!(15.49)), dec!(15.49));
+        // a relative tolerance would round these up by a whole point
+        assert_eq!(snap_to_integer(dec!(1732050807.9)), dec!(1732050807.9));
+        assert_eq!(
+            snap_to_integer(dec!(1000000000000000.5)),
+            dec!(1000000000000000.5)
+        );
+    }
+
+    #[test]
+    fn mixer_weighted_geometric_works() {
+        let geometric = WeightedGeometricMean::new(vec![
+            StdDecimal::percent(50),
+            StdDecimal::percent(25),
+            StdDecimal::percent(25),
+        ])
+        .unwrap();
+        assert_eq!(geometric.inputs(), 3);
+
+        // any 0 -> 0
+        assert_eq!(geometric.mix_all(&[0, 123456, 10]).unwrap(), 0);
+        assert_eq!(geometric.mix_all(&[7777, 10, 0]).unwrap(), 0);
+
+        // basic math checks (no rounding)
+        assert_eq!(geometric.mix_all(&[10000, 100, 100]).unwrap(), 1000);
+        assert_eq!(geometric.mix_all(&[4, 16, 1]).unwrap(), 4);
+
+        // rounding down (sqrt(240) = 15.49...)
+        assert_eq!(geometric.mix_all(&[240, 1, 1]).unwrap(), 15);
+
+        // wrong number of inputs
+        let err = geometric.mix(4, 9).unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::InputsMismatch {
+                expected: 3,
+                got: 2
+            }
+        );
+
+        // equal weights approximate the geometric mean
+        let equal =
+            WeightedGeometricMean::new(vec![StdDecimal::percent(50), StdDecimal::percent(50)])
+                .unwrap();
+        assert_eq!(equal.mix(4, 9).unwrap(), 6);
+        assert_eq!(equal.mix(12, 20).unwrap(), 15);
+        assert_eq!(equal.mix(1_000_000, 1_000_000).unwrap(), 1_000_000);
+        assert_eq!(equal.mix(12_345_678, 87_654_321).unwrap(), 32_896_079);
+        // but results within the snapping tolerance below an integer are taken as that integer
+        // (sqrt(9999999999999999) = 99999999.999999995)
+        assert_eq!(equal.mix(99_999_999, 100_000_001).unwrap(), 100_000_000);
+        assert_eq!(
+            GeometricMean::new().mix(99_999_999, 100_000_001).unwrap(),
+            99_999_999
+        );
+
+        // weights have to sum up to 1
+        let err =
+            WeightedGeometricMean::new(vec![StdDecimal::percent(50), StdDecimal::percent(60)])
+                .err()
+                .unwrap();
+        assert_eq!(
+            err,
+            ContractError::ParameterRange("weights", "sum 1.1".to_owned())
+        );
+        let err = WeightedGeometricMean::new(vec![StdDecimal::one()])
+            .err()
+            .unwrap();
+        assert_eq!(
+            err,
+            ContractError::ParameterRange("weights", "1 inputs".to_owned())
+        );
+    }
+
+    #[test]
+    fn mixer_weighted_sigmoid_works() {
+        let p = StdDecimal::from_ratio(68u128, 100u128);
+        let s = StdDecimal::from_ratio(3u128, 100000u128);
+        let sigmoid = Sigmoid::new(Uint64::new(1000), p, s).unwrap();
+        let weighted = WeightedSigmoid::new(Uint64::new(1000), vec![p, p], s).unwrap();
+
+        // two inputs with equal weights are the same as sigmoid
+        for (stake, engagement) in [(0, 123456), (5, 1000), (1000, 1000), (100, 100000)] {
+            assert_eq!(
+                weighted.mix(stake, engagement).unwrap(),
+                sigmoid.mix(stake, engagement).unwrap()
+            );
+        }
+
+        // third input with zero weight doesn't change anything
+        let weighted =
+            WeightedSigmoid::new(Uint64::new(1000), vec![p, p, StdDecimal::zero()], s).unwrap();
+        assert_eq!(weighted.inputs(), 3);
+        assert_eq!(weighted.mix_all(&[1000, 1000, 77]).unwrap(), 178);
+
+        let weighted =
+            WeightedSigmoid::new(Uint64::new(1000), vec![p, p, StdDecimal::percent(50)], s)
+                .unwrap();
+        assert_eq!(weighted.mix_all(&[1000, 1000, 0]).unwrap(), 0);
+        assert_eq!(weighted.mix_all(&[1000, 1000, 4]).unwrap(), 345);
+
+        // Overflow checks
+        let err = weighted.mix_all(&[u64::MAX, 1, 1]).unwrap_err();
+        assert_eq!(err, ContractError::PointsOverflow {});
+
+        // weights are exponents between 0 and 1
+        let err = WeightedSigmoid::new(Uint64::new(1000), vec![p, StdDecimal::percent(101)], s)
+            .err()
+            .unwrap();
+        assert_eq!(
+            err,
+            ContractError::ParameterRange("weights", "1.01".to_owned())
+        );
+    }
+
     #[test]
     fn mixer_sigmoid_works() {
         let sigmoid = Sigmoid::new(
@@ -344,6 +876,146 @@ mod tests {
         assert_eq!(err, ContractError::ComputationOverflow("powd"));
     }
 
+    #[test]
+    fn mixer_integer_sigmoid_works() {
+        let sigmoid = IntegerSigmoid::new(
+            Uint64::new(1000),
+            StdDecimal::from_ratio(68u128, 100u128),
+            StdDecimal::from_ratio(3u128, 100000u128),
+        )
+        .unwrap();
+
+        // either 0 -> 0
+        assert_eq!(sigmoid.mix(0, 123456).unwrap(), 0);
+        assert_eq!(sigmoid.mix(7777, 0).unwrap(), 0);
+
+        // Basic math checks (no rounding)
+        // Values from PoE paper, Appendix A, "root of engagement" curve
+        assert_eq!(sigmoid.mix(5, 1000).unwrap(), 4);
+        assert_eq!(sigmoid.mix(5, 100000).unwrap(), 112);
+        assert_eq!(sigmoid.mix(1000, 1000).unwrap(), 178);
+        assert_eq!(sigmoid.mix(1000, 100000).unwrap(), 999);
+        assert_eq!(sigmoid.mix(100000, 100000).unwrap(), 1000);
+
+        // Rounding down (697.8821566)
+        assert_eq!(sigmoid.mix(100, 100000).unwrap(), 697);
+
+        // No overflow
+        assert_eq!(sigmoid.mix(u64::MAX, u64::MAX).unwrap(), 1000);
+        assert_eq!(sigmoid.mix(u64::MAX, 1).unwrap(), 1000);
+
+        // `s = 0` -> 0
+        let flat = IntegerSigmoid::new(
+            Uint64::new(1000),
+            StdDecimal::from_ratio(68u128, 100u128),
+            StdDecimal::zero(),
+        )
+        .unwrap();
+        assert_eq!(flat.mix(u64::MAX, u64::MAX).unwrap(), 0);
+
+        // Same parameters as `Sigmoid`
+        let err = IntegerSigmoid::new(
+            Uint64::new(1000),
+            StdDecimal::percent(101),
+            StdDecimal::zero(),
+        )
+        .err()
+        .unwrap();
+        assert_eq!(err, ContractError::ParameterRange("p", "1.01".to_owned()));
+    }
+
+    #[test]
+    fn mixer_integer_sigmoid_matches_sigmoid() {
+        for (p, s) in [
+            (
+                StdDecimal::percent(68),
+                StdDecimal::from_ratio(3u128, 100000u128),
+            ),
+            (
+                StdDecimal::percent(50),
+                StdDecimal::from_ratio(3u128, 10000u128),
+            ),
+        ] {
+            let sigmoid = Sigmoid::new(Uint64::new(1000), p, s).unwrap();
+            let integer = IntegerSigmoid::new(Uint64::new(1000), p, s).unwrap();
+
+            // `Sigmoid` overflows above it
+            let max = 32_313_447u64;
+            let mut stake = 0u64;
+            while stake < max {
+                let mut engagement = 0u64;
+                while engagement < max {
+                    let decimal = sigmoid.mix(stake, engagement).unwrap();
+                    let points = integer.mix(stake, engagement).unwrap();
+                    // both are rounded down from slightly different approximations
+                    assert!(
+                        decimal.abs_diff(points) <= 1,
+                        "p: {}, s: {}, mix({}, {}) = {}, Sigmoid gives {}",
+                        p,
+                        s,
+                        stake,
+                        engagement,
+                        points,
+                        decimal
+                    );
+                    engagement = engagement * 3 + 1;
+                }
+                stake = stake * 4 + 1;
+            }
+        }
+    }
+
+    #[test]
+    fn mixer_integer_sigmoid_precision() {
+        // exact value of `r_max * (2 / (1 + e^(-s * x^p)) - 1)`, within f64 precision
+        let max_points = 10_000_000_000u64;
+        let exact = |p: StdDecimal, s: StdDecimal, x: u128| {
+            let p = p.to_string().parse::<f64>().unwrap();
+            let s = s.to_string().parse::<f64>().unwrap();
+            let t = s * (x as f64).powf(p);
+            max_points as f64 * (2.0 / (1.0 + (-t).exp()) - 1.0)
+        };
+
+        for (p, s) in [
+            (
+                StdDecimal::percent(68),
+                StdDecimal::from_ratio(3u128, 100000u128),
+            ),
+            (
+                StdDecimal::percent(50),
+                StdDecimal::from_ratio(3u128, 10000u128),
+            ),
+            (
+                StdDecimal::percent(100),
+                StdDecimal::from_ratio(1u128, 10u128.pow(12)),
+            ),
+            (StdDecimal::percent(5), StdDecimal::percent(7)),
+        ] {
+            let integer = IntegerSigmoid::new(Uint64::new(max_points), p, s).unwrap();
+
+            let mut stake = 1u64;
+            while stake < 100_000_000 {
+                let mut engagement = 1u64;
+                while engagement < 100_000_000 {
+                    let expected = exact(p, s, stake as u128 * engagement as u128);
+                    let points = integer.mix(stake, engagement).unwrap();
+                    assert!(
+                        (expected - points as f64).abs() <= 1.0,
+                        "p: {}, s: {}, mix({}, {}) = {}, expected {}",
+                        p,
+                        s,
+                        stake,
+                        engagement,
+                        points,
+                        expected
+                    );
+                    engagement = engagement * 7 + 3;
+                }
+                stake = stake * 5 + 1;
+            }
+        }
+    }
+
     #[test]
     fn mixer_sigmoid_half_works() {
         let sigmoid = Sigmoid::new(
@@ -408,6 +1080,88 @@ mod tests {
         assert_eq!(err, ContractError::PointsOverflow {});
     }
 
+    #[test]
+    fn mixer_piecewise_linear_works() {
+        let points = |points: &[(u128, u64)]| {
+            points
+                .iter()
+                .map(|(x, y)| (Uint128::new(*x), Uint64::new(*y)))
+                .collect::<Vec<_>>()
+        };
+
+        let linear = PiecewiseLinear::new(
+            PiecewiseInput::Product,
+            points(&[(0, 0), (1000, 100), (1_000_000, 900), (10_000_000, 1000)]),
+        )
+        .unwrap();
+
+        // either 0 -> first point
+        assert_eq!(linear.mix(0, 123456).unwrap(), 0);
+        assert_eq!(linear.mix(7777, 0).unwrap(), 0);
+
+        // exactly at the points
+        assert_eq!(linear.mix(10, 100).unwrap(), 100);
+        assert_eq!(linear.mix(1000, 1000).unwrap(), 900);
+
+        // interpolation, rounding down (100 + 800 * 9000 / 999000 = 107.2...)
+        assert_eq!(linear.mix(5, 100).unwrap(), 50);
+        assert_eq!(linear.mix(100, 100).unwrap(), 107);
+        assert_eq!(linear.mix(5000, 1100).unwrap(), 950);
+
+        // capped past the last point
+        assert_eq!(linear.mix(100000, 100000).unwrap(), 1000);
+        assert_eq!(linear.mix(u64::MAX, u64::MAX).unwrap(), 1000);
+
+        let linear = PiecewiseLinear::new(
+            PiecewiseInput::GeometricMean,
+            points(&[(0, 10), (100, 10), (u64::MAX as u128, u64::MAX)]),
+        )
+        .unwrap();
+        // flat segment
+        assert_eq!(linear.mix(0, 0).unwrap(), 10);
+        assert_eq!(linear.mix(4, 9).unwrap(), 10);
+        assert_eq!(linear.mix(100, 100).unwrap(), 10);
+        // no overflow on the widest segment
+        assert_eq!(linear.mix(u64::MAX, u64::MAX).unwrap(), u64::MAX);
+        assert_eq!(linear.mix(u64::MAX, 0).unwrap(), 10);
+
+        // validation
+        let err = PiecewiseLinear::new(PiecewiseInput::Product, points(&[(0, 10)]))
+            .err()
+            .unwrap();
+        assert_eq!(
+            err,
+            ContractError::ParameterRange("points", "1 points".to_owned())
+        );
+        let err = PiecewiseLinear::new(PiecewiseInput::Product, points(&[(1, 0), (10, 10)]))
+            .err()
+            .unwrap();
+        assert_eq!(
+            err,
+            ContractError::ParameterRange("points", "first x 1".to_owned())
+        );
+        let err = PiecewiseLinear::new(
+            PiecewiseInput::Product,
+            points(&[(0, 0), (10, 10), (10, 20)]),
+        )
+        .err()
+        .unwrap();
+        assert_eq!(
+            err,
+            ContractError::ParameterRange("points", "x 10 not above 10".to_owned())
+        );
+        let err = PiecewiseLinear::new(
+            PiecewiseInput::Product,
+            points(&[(0, 0), (10, 10), (20, 5)]),
+        )
+        .err()
+        .unwrap();
+        assert_eq!(
+            err,
+            ContractError::ParameterRange("points", "y 5 below 10".to_owned())
+        );
+    }
+
     #[test]
     fn mixer_algebraic_sigmoid_works() {
         let algebraic_sigmoid = AlgebraicSigmoid::new(
@@ -1,18 +1,28 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use cosmwasm_std::{Decimal as StdDecimal, Uint64};
+use cosmwasm_std::{Decimal as StdDecimal, Uint128, Uint64};
 use tg4::{Member, MemberChangedHookMsg};
 
 use crate::error::ContractError;
-use crate::functions::{AlgebraicSigmoid, GeometricMean, PoEFunction, Sigmoid, SigmoidSqrt};
+use crate::functions::{
+    AlgebraicSigmoid, GeometricMean, IntegerSigmoid, PiecewiseLinear, PoEFunction, Sigmoid,
+    SigmoidSqrt, WeightedGeometricMean, WeightedSigmoid,
+};
 
 #[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
 pub struct InstantiateMsg {
+    /// Admin can change the mixing function with `UpdateFunction`
+    #[serde(default)]
+    pub admin: Option<String>,
     /// One of the groups we feed to the mixer function
     pub left_group: String,
     /// The other group we feed to the mixer function
     pub right_group: String,
+    /// Further groups we feed to the mixer function, after `left_group` and `right_group`.
+    /// Only functions mixing any number of inputs accept them.
+    #[serde(default)]
+    pub extra_groups: Vec<String>,
     /// Preauthorize some hooks on init (only way to add them)
     #[serde(default)]
     pub preauths_hooks: u64,
@@ -35,6 +45,14 @@ pub enum PoEFunctionType {
         p: StdDecimal,
         s: StdDecimal,
     },
+    /// `IntegerSigmoid` returns the same sigmoid-like value as `Sigmoid`, but computed with
+    /// fixed-point integer math, which makes it much cheaper to execute. It is within 1 point of
+    /// the exact value for `max_points` up to `10^10`.
+    IntegerSigmoid {
+        max_points: Uint64,
+        p: StdDecimal,
+        s: StdDecimal,
+    },
     /// SigmoidSqrt returns a sigmoid-like value of the geometric mean of staked amount and
     /// engagement points.
     /// It is equal to `Sigmoid` with `p = 0.5`, but implemented using integer sqrt instead of
@@ -48,6 +66,36 @@ pub enum PoEFunctionType {
         p: StdDecimal,
         s: StdDecimal,
     },
+    /// `WeightedGeometricMean` returns the geometric mean of points in any number of groups,
+    /// every one weighted by its own weight. `weights` are given in the order of groups, and
+    /// have to sum up to 1.
+    WeightedGeometricMean { weights: Vec<StdDecimal> },
+    /// `WeightedSigmoid` returns a sigmoid-like value of the product of points in any number of
+    /// groups, every one raised to its own exponent. `weights` are given in the order of groups.
+    /// It is equal to `Sigmoid` for two groups weighted `p`.
+    WeightedSigmoid {
+        max_points: Uint64,
+        weights: Vec<StdDecimal>,
+        s: StdDecimal,
+    },
+    /// `PiecewiseLinear` returns points linearly interpolated between the given `(x, y)` points,
+    /// with `x` computed from staked amount and engagement points as set by `input`.
+    /// Points have to start at `x = 0`, with `x` strictly increasing and `y` non-decreasing.
+    /// Beyond the last point, its `y` is returned.
+    PiecewiseLinear {
+        input: PiecewiseInput,
+        points: Vec<(Uint128, Uint64)>,
+    },
+}
+
+/// Value of the inputs `PiecewiseLinear` is evaluated at
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PiecewiseInput {
+    /// Staked amount times engagement points
+    Product,
+    /// Geometric mean of staked amount and engagement points
+    GeometricMean,
 }
 
 impl PoEFunctionType {
@@ -57,6 +105,9 @@ impl PoEFunctionType {
             PoEFunctionType::Sigmoid { max_points, p, s } => {
                 Ok(Box::new(Sigmoid::new(max_points, p, s)?))
             }
+            PoEFunctionType::IntegerSigmoid { max_points, p, s } => {
+                Ok(Box::new(IntegerSigmoid::new(max_points, p, s)?))
+            }
             PoEFunctionType::SigmoidSqrt { max_points, s } => {
                 Ok(Box::new(SigmoidSqrt::new(max_points, s)?))
             }
@@ -66,6 +117,17 @@ impl PoEFunctionType {
                 p,
                 s,
             } => Ok(Box::new(AlgebraicSigmoid::new(max_points, a, p, s)?)),
+            PoEFunctionType::WeightedGeometricMean { weights } => {
+                Ok(Box::new(WeightedGeometricMean::new(weights)?))
+            }
+            PoEFunctionType::WeightedSigmoid {
+                max_points,
+                weights,
+                s,
+            } => Ok(Box::new(WeightedSigmoid::new(max_points, weights, s)?)),
+            PoEFunctionType::PiecewiseLinear { input, points } => {
+                Ok(Box::new(PiecewiseLinear::new(input, points)?))
+            }
         }
     }
 }
@@ -85,6 +147,15 @@ pub enum ExecuteMsg {
     RemoveSlasher { addr: String },
     /// Slash engagement points from address
     Slash { addr: String, portion: StdDecimal },
+    /// Change the admin
+    UpdateAdmin { admin: Option<String> },
+    /// Replaces the mixing function. Must be called by Admin.
+    /// Starts the recalculation of all members' points, see `RecalculatePoints`.
+    UpdateFunction { function_type: PoEFunctionType },
+    /// Recomputes the points of up to `limit` members, continuing a points recalculation started
+    /// by `UpdateFunction`. Can be called by anyone until all members are processed. Membership
+    /// changes are sent to the hooks in a single message. `limit` must not be zero.
+    RecalculatePoints { limit: Option<u32> },
 }
 
 #[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
@@ -124,13 +195,29 @@ pub enum QueryMsg {
     IsSlasher { addr: String },
     /// Shows all active slashers as vector of addresses
     ListSlashers {},
+    /// Return AdminResponse
+    Admin {},
+    /// Shows the progress of the points recalculation after the mixing function was updated.
+    /// Returns RecalculationResponse.
+    PointsRecalculation {},
+    /// Points of up to `limit` current members, ordered by address, as if they were recomputed
+    /// with `poe_function`. Returns SimulateFunctionResponse.
+    SimulateFunction {
+        poe_function: PoEFunctionType,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
 }
 
-/// Return the two groups we are listening to
+/// Return the groups we are listening to
 #[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
 pub struct GroupsResponse {
     pub left: String,
     pub right: String,
+    /// All the groups, in the order they are fed to the mixer function, starting with `left`
+    /// and `right`
+    #[serde(default)]
+    pub groups: Vec<String>,
 }
 
 #[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
@@ -142,3 +229,44 @@ pub struct PreauthResponse {
 pub struct MixerFunctionResponse {
     pub points: u64,
 }
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct RecalculationResponse {
+    /// Whether there are members left to recompute
+    pub pending: bool,
+    /// Last address recomputed so far
+    pub start_after: Option<String>,
+    /// Number of addresses recomputed so far
+    pub processed: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
+pub struct SimulatedMember {
+    pub addr: String,
+    /// Current points of the member
+    pub old_points: u64,
+    /// Points the member would have with the simulated function, `None` if it is no longer in
+    /// all the groups
+    pub new_points: Option<u64>,
+    /// Position of the member in the returned page, ordered by `old_points` as in
+    /// `ListMembersByPoints`, starting from 1
+    pub old_rank: u32,
+    /// Position of the member in the returned page, ordered by `new_points`
+    pub new_rank: u32,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
+pub struct SimulateFunctionResponse {
+    pub members: Vec<SimulatedMember>,
+    /// Sum of `old_points` of the returned members
+    pub old_total: u64,
+    /// Sum of `new_points` of the returned members
+    pub new_total: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug, Default)]
+#[serde(rename_all = "snake_case")]
+pub struct MigrateMsg {
+    /// Sets the admin, allowed to change the mixing function
+    pub admin: Option<String>,
+}
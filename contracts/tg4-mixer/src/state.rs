@@ -1,6 +1,8 @@
 use serde::{Deserialize, Serialize};
+use std::iter;
 
 use crate::msg::PoEFunctionType;
+use cosmwasm_std::Addr;
 use cw_storage_plus::Item;
 use tg4::Tg4Contract;
 
@@ -10,6 +12,30 @@ pub const POE_FUNCTION_TYPE: Item<PoEFunctionType> = Item::new("poe-function-typ
 pub struct Groups {
     pub left: Tg4Contract,
     pub right: Tg4Contract,
+    /// Groups mixed in addition to `left` and `right`
+    #[serde(default)]
+    pub extra: Vec<Tg4Contract>,
+}
+
+impl Groups {
+    /// All the groups, in the order they are fed to the mixer function
+    pub fn all(&self) -> impl Iterator<Item = &Tg4Contract> {
+        iter::once(&self.left)
+            .chain(iter::once(&self.right))
+            .chain(&self.extra)
+    }
 }
 
 pub const GROUPS: Item<Groups> = Item::new("groups");
+
+/// Progress of recomputing the points of all members, pending after the mixing function was
+/// updated
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug, Default)]
+pub struct Recalculation {
+    /// Last address recomputed so far
+    pub start_after: Option<Addr>,
+    /// Number of addresses recomputed so far
+    pub processed: u64,
+}
+
+pub const RECALCULATION: Item<Recalculation> = Item::new("points_recalculation");
@@ -5,9 +5,11 @@ use cosmwasm_schema::{export_schema, export_schema_with_title, remove_schemas, s
 
 pub use tg4::{AdminResponse, MemberListResponse, MemberResponse, TotalPointsResponse};
 pub use tg4_stake::msg::{
-    ClaimsResponse, ExecuteMsg, InstantiateMsg, MigrateMsg, PreauthResponse, QueryMsg,
-    StakedResponse, UnbondingPeriodResponse,
+    ClaimsResponse, ExecuteMsg, InstantiateMsg, LocksResponse, MigrateMsg, PendingReleasesResponse,
+    PreauthResponse, QueryMsg, SlashesResponse, StakeChangedHookMsg, StakedResponse,
+    UnbondingPeriodResponse,
 };
+pub use tg4_stake::state::StakeState;
 
 fn main() {
     let mut out_dir = current_dir().unwrap();
@@ -24,6 +26,11 @@ fn main() {
     export_schema(&schema_for!(MemberResponse), &out_dir);
     export_schema(&schema_for!(TotalPointsResponse), &out_dir);
     export_schema(&schema_for!(ClaimsResponse), &out_dir);
+    export_schema(&schema_for!(LocksResponse), &out_dir);
+    export_schema(&schema_for!(SlashesResponse), &out_dir);
+    export_schema(&schema_for!(PendingReleasesResponse), &out_dir);
+    export_schema(&schema_for!(StakeState), &out_dir);
+    export_schema(&schema_for!(StakeChangedHookMsg), &out_dir);
     export_schema(&schema_for!(UnbondingPeriodResponse), &out_dir);
     export_schema(&schema_for!(StakedResponse), &out_dir);
     export_schema(&schema_for!(PreauthResponse), &out_dir);
@@ -1,6 +1,8 @@
 // Copied from cw-plus repository: https://github.com/CosmWasm/cw-plus/tree/main/packages/controllers
 // Original file distributed on Apache license
 
+use std::cmp::min;
+
 use itertools::Itertools;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
@@ -8,7 +10,7 @@ use serde::{Deserialize, Serialize};
 use crate::msg::Undelegation;
 use crate::state::CONFIG;
 use cosmwasm_std::{
-    coin, Addr, BlockInfo, CustomQuery, Decimal, Deps, Order, StdResult, Storage, Uint128,
+    coin, Addr, BlockInfo, Coin, CustomQuery, Decimal, Deps, Order, StdResult, Storage, Uint128,
 };
 use cw_storage_plus::{Bound, Index, IndexList, IndexedMap, MultiIndex, PrefixBound};
 use tg_bindings::TgradeMsg;
@@ -19,16 +21,25 @@ use tg_utils::Expiration;
 const MAX_LIMIT: u32 = 100;
 const DEFAULT_LIMIT: u32 = 30;
 
+const NANOS_PER_DAY: u64 = 86_400_000_000_000;
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub(crate) struct TokenReleaseInfo {
     pub addr: Addr,
     pub amount: Uint128,
 }
 
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct CoinsReleaseInfo {
+    pub addr: Addr,
+    pub amount: Vec<Coin>,
+}
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub(crate) struct ReleaseData {
     pub liquid_releases: Vec<TokenReleaseInfo>,
     pub vesting_releases: Vec<TokenReleaseInfo>,
+    pub extra_releases: Vec<CoinsReleaseInfo>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
@@ -39,6 +50,9 @@ pub struct Claim {
     pub amount: Uint128,
     /// Vesting amount of tokens in claim
     pub vesting_amount: Option<Uint128>,
+    /// Liquid amounts of additional staking denoms in claim
+    #[serde(default)]
+    pub extra_amounts: Vec<Coin>,
     /// Release time of the claim. Originally in `cw_controllers` it is an `Expiration` type, but
     /// here we need to query for claims via release time, and expiration is impossible to be
     /// properly sorted, as it is impossible to properly compare expiration by height and
@@ -48,6 +62,21 @@ pub struct Claim {
     pub creation_height: u64,
 }
 
+/// Totals of all pending claims to be released on a single day (UTC)
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct DailyRelease {
+    /// Number of days since the Unix epoch
+    pub day: u64,
+    /// Number of claims released this day
+    pub claims: u32,
+    /// Liquid amount of tokens released this day
+    pub amount: Uint128,
+    /// Vesting amount of tokens released this day
+    pub vesting_amount: Uint128,
+    /// Liquid amounts of additional staking denoms released this day
+    pub extra_amounts: Vec<Coin>,
+}
+
 struct ClaimIndexes<'a> {
     // Last type param defines the pk deserialization type
     pub release_at: MultiIndex<'a, u64, Claim, (Addr, u64)>,
@@ -72,6 +101,7 @@ impl Claim {
             addr,
             amount: amount.into(),
             vesting_amount: Some(vesting_amount.into()),
+            extra_amounts: vec![],
             release_at: released,
             creation_height,
         }
@@ -100,12 +130,14 @@ impl<'a> Claims<'a> {
 
     /// This creates a claim, such that the given address can claim an amount of tokens after
     /// the release date.
+    #[allow(clippy::too_many_arguments)]
     pub fn create_claim(
         &self,
         storage: &mut dyn Storage,
         addr: Addr,
         amount: Uint128,
         vesting_amount: Uint128,
+        extra_amounts: &[Coin],
         release_at: Expiration,
         creation_height: u64,
     ) -> StdResult<()> {
@@ -120,12 +152,16 @@ impl<'a> Claims<'a> {
                         claim.amount += amount;
                         claim.vesting_amount =
                             Some(claim.vesting_amount.unwrap_or_default() + vesting_amount);
+                        for extra in extra_amounts {
+                            add_coin(&mut claim.extra_amounts, extra);
+                        }
                         Ok(claim)
                     }
                     None => Ok(Claim {
                         addr: addr.clone(),
                         amount,
                         vesting_amount: Some(vesting_amount),
+                        extra_amounts: extra_amounts.to_vec(),
                         release_at,
                         creation_height,
                     }),
@@ -144,7 +180,7 @@ impl<'a> Claims<'a> {
         addr: &Addr,
         block: &BlockInfo,
         limit: impl Into<Option<u64>>,
-    ) -> StdResult<(Uint128, Uint128)> {
+    ) -> StdResult<(Uint128, Uint128, Vec<Coin>)> {
         let claims = self
             .claims
             .prefix(addr)
@@ -162,10 +198,11 @@ impl<'a> Claims<'a> {
             .iter()
             .map(|claim| claim.vesting_amount.unwrap_or_default())
             .sum();
+        let extra_amounts = sum_coins(claims.iter().flat_map(|claim| &claim.extra_amounts));
 
         self.release_claims(storage, claims)?;
 
-        Ok((amount, vesting_amount))
+        Ok((amount, vesting_amount, extra_amounts))
     }
 
     /// This iterates over all mature claims of any addresses, and removes them. Up to `limit`
@@ -219,11 +256,24 @@ impl<'a> Claims<'a> {
             })
             .collect();
 
+        let extra_releases = claims
+            .iter()
+            // TODO: use `slice::group_by` in place of `Itertools::group_by` when `slice_group_by`
+            // is stabilized [https://github.com/rust-lang/rust/issues/80552]
+            .group_by(|claim| &claim.addr)
+            .into_iter()
+            .map(|(addr, group)| CoinsReleaseInfo {
+                addr: addr.clone(),
+                amount: sum_coins(group.flat_map(|claim| &claim.extra_amounts)),
+            })
+            .collect();
+
         self.release_claims(storage, claims)?;
 
         let release_data = ReleaseData {
             liquid_releases,
             vesting_releases,
+            extra_releases,
         };
 
         Ok(release_data)
@@ -262,12 +312,148 @@ impl<'a> Claims<'a> {
         Ok(())
     }
 
+    /// This iterates over pending (not yet mature) claims for the address, starting from the
+    /// newest one, and cancels them until `amount` tokens are covered. Claims are consumed
+    /// liquid part first, and a partially consumed claim is kept with the remaining amounts.
+    /// Returns the liquid and vesting amounts which were taken back from claims. If there are not
+    /// enough tokens unbonding, no claim is modified, and the total amounts unbonding are
+    /// returned instead (their sum being lower than `amount`).
+    pub fn rebond_claims(
+        &self,
+        storage: &mut dyn Storage,
+        addr: &Addr,
+        block: &BlockInfo,
+        amount: Uint128,
+    ) -> StdResult<(Uint128, Uint128)> {
+        let claims: Vec<_> = self
+            .claims
+            .prefix(addr)
+            // take all claims for the addr which are not expired yet
+            .range(
+                storage,
+                Some(Bound::exclusive(Expiration::now(block).as_key())),
+                None,
+                Order::Descending,
+            )
+            .collect::<StdResult<_>>()?;
+
+        let unbonding: Uint128 = claims
+            .iter()
+            .map(|(_, claim)| claim.amount + claim.vesting_amount.unwrap_or_default())
+            .sum();
+        if unbonding < amount {
+            let liquid = claims.iter().map(|(_, claim)| claim.amount).sum();
+            return Ok((liquid, unbonding - liquid));
+        }
+
+        let mut remaining = amount;
+        let mut liquid_rebonded = Uint128::zero();
+        let mut vesting_rebonded = Uint128::zero();
+
+        for (release_at, claim) in claims {
+            if remaining.is_zero() {
+                break;
+            }
+
+            let liquid = min(claim.amount, remaining);
+            remaining -= liquid;
+            let claim_vesting = claim.vesting_amount.unwrap_or_default();
+            let vesting = min(claim_vesting, remaining);
+            remaining -= vesting;
+
+            let key = (addr, release_at);
+            if liquid == claim.amount && vesting == claim_vesting && claim.extra_amounts.is_empty()
+            {
+                self.claims.remove(storage, key)?;
+            } else {
+                let mut new_claim = claim.clone();
+                new_claim.amount -= liquid;
+                new_claim.vesting_amount = Some(claim_vesting - vesting);
+                self.claims
+                    .replace(storage, key, Some(&new_claim), Some(&claim))?;
+            }
+
+            liquid_rebonded += liquid;
+            vesting_rebonded += vesting;
+        }
+
+        Ok((liquid_rebonded, vesting_rebonded))
+    }
+
+    /// Works as `rebond_claims`, but cancels the unbonding of `tokens` in one of the
+    /// additional staking denoms. Returns the amount which was taken back from claims, or the
+    /// total amount unbonding (leaving claims untouched) if it is lower than requested.
+    pub fn rebond_extra_claims(
+        &self,
+        storage: &mut dyn Storage,
+        addr: &Addr,
+        block: &BlockInfo,
+        tokens: &Coin,
+    ) -> StdResult<Uint128> {
+        let claims: Vec<_> = self
+            .claims
+            .prefix(addr)
+            // take all claims for the addr which are not expired yet
+            .range(
+                storage,
+                Some(Bound::exclusive(Expiration::now(block).as_key())),
+                None,
+                Order::Descending,
+            )
+            .collect::<StdResult<_>>()?;
+
+        let claimed_amount = |claim: &Claim| {
+            claim
+                .extra_amounts
+                .iter()
+                .find(|c| c.denom == tokens.denom)
+                .map(|c| c.amount)
+                .unwrap_or_default()
+        };
+        let unbonding: Uint128 = claims.iter().map(|(_, claim)| claimed_amount(claim)).sum();
+        if unbonding < tokens.amount {
+            return Ok(unbonding);
+        }
+
+        let mut remaining = tokens.amount;
+        for (release_at, claim) in claims {
+            if remaining.is_zero() {
+                break;
+            }
+
+            let claimed = claimed_amount(&claim);
+            if claimed.is_zero() {
+                continue;
+            }
+            let rebonded = min(claimed, remaining);
+            remaining -= rebonded;
+
+            let mut new_claim = claim.clone();
+            sub_coin(
+                &mut new_claim.extra_amounts,
+                &coin(rebonded.u128(), &tokens.denom),
+            );
+            let key = (addr, release_at);
+            if new_claim.amount.is_zero()
+                && new_claim.vesting_amount.unwrap_or_default().is_zero()
+                && new_claim.extra_amounts.is_empty()
+            {
+                self.claims.remove(storage, key)?;
+            } else {
+                self.claims
+                    .replace(storage, key, Some(&new_claim), Some(&claim))?;
+            }
+        }
+
+        Ok(tokens.amount - remaining)
+    }
+
     pub fn slash_claims_for_addr(
         &self,
         storage: &mut dyn Storage,
         address: Addr,
         portion: Decimal,
-    ) -> StdResult<(Uint128, Uint128)> {
+    ) -> StdResult<(Uint128, Uint128, Vec<Coin>)> {
         let claims: StdResult<Vec<_>> = self
             .claims
             .prefix(&address)
@@ -277,6 +463,7 @@ impl<'a> Claims<'a> {
 
         let mut total_slashed = Uint128::zero();
         let mut total_vesting_slashed = Uint128::zero();
+        let mut total_extra_slashed = vec![];
 
         for (release_at, claim) in claims {
             let key = (&address, release_at);
@@ -287,6 +474,11 @@ impl<'a> Claims<'a> {
             new_claim.amount -= slashed;
             new_claim.vesting_amount =
                 Some(claim.vesting_amount.unwrap_or_default() - vesting_slashed);
+            for extra in &claim.extra_amounts {
+                let extra_slashed = coin((extra.amount * portion).u128(), &extra.denom);
+                sub_coin(&mut new_claim.extra_amounts, &extra_slashed);
+                add_coin(&mut total_extra_slashed, &extra_slashed);
+            }
 
             self.claims
                 .replace(storage, key, Some(&new_claim), Some(&claim))?;
@@ -295,7 +487,7 @@ impl<'a> Claims<'a> {
             total_vesting_slashed += vesting_slashed;
         }
 
-        Ok((total_slashed, total_vesting_slashed))
+        Ok((total_slashed, total_vesting_slashed, total_extra_slashed))
     }
 
     pub fn query_claims<Q: CustomQuery>(
@@ -318,6 +510,148 @@ impl<'a> Claims<'a> {
             .take(limit)
             .collect()
     }
+
+    /// Returns all claims of all addresses, for the state export
+    pub(crate) fn all_claims(&self, storage: &dyn Storage) -> StdResult<Vec<Claim>> {
+        self.claims
+            .range(storage, None, None, Order::Ascending)
+            .map(|claim| claim.map(|(_, claim)| claim))
+            .collect()
+    }
+
+    /// Replaces all existing claims with `claims`, for the state import
+    pub(crate) fn import_claims(
+        &self,
+        storage: &mut dyn Storage,
+        claims: &[Claim],
+    ) -> StdResult<()> {
+        let keys = self
+            .claims
+            .keys(storage, None, None, Order::Ascending)
+            .collect::<StdResult<Vec<_>>>()?;
+        for (addr, release_at) in keys {
+            self.claims.remove(storage, (&addr, release_at))?;
+        }
+        for claim in claims {
+            self.claims
+                .save(storage, (&claim.addr, claim.release_at.as_key()), claim)?;
+        }
+        Ok(())
+    }
+
+    /// Lists pending claims of all addresses ordered by their release time, and then by address.
+    /// `start_after` is the release time and address of the last claim of the previous page.
+    pub fn query_pending_claims<Q: CustomQuery>(
+        &self,
+        deps: Deps<Q>,
+        limit: Option<u32>,
+        start_after: Option<(Expiration, Addr)>,
+    ) -> StdResult<Vec<Claim>> {
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+        let start = start_after.map(|(release_at, addr)| {
+            Bound::exclusive((release_at.as_key(), (addr, release_at.as_key())))
+        });
+
+        self.claims
+            .idx
+            .release_at
+            .range(deps.storage, start, None, Order::Ascending)
+            .map(|claim| claim.map(|(_, claim)| claim))
+            .take(limit)
+            .collect()
+    }
+
+    /// Aggregates pending claims of all addresses by the day of their release.
+    /// At most `limit` claims are aggregated per call, so a day may be split between pages.
+    /// `start_after` is the release time and address of the last claim aggregated by the
+    /// previous page, which is returned along with the days while there are claims left.
+    #[allow(clippy::type_complexity)]
+    pub fn query_releases_by_day<Q: CustomQuery>(
+        &self,
+        deps: Deps<Q>,
+        limit: Option<u32>,
+        start_after: Option<(Expiration, Addr)>,
+    ) -> StdResult<(Vec<DailyRelease>, Option<(Expiration, Addr)>)> {
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+        let start = start_after.map(|(release_at, addr)| {
+            Bound::exclusive((release_at.as_key(), (addr, release_at.as_key())))
+        });
+
+        let mut claims = self
+            .claims
+            .idx
+            .release_at
+            .range(deps.storage, start, None, Order::Ascending)
+            .take(limit + 1)
+            .map(|claim| claim.map(|(_, claim)| claim))
+            .collect::<StdResult<Vec<_>>>()?;
+        let next = if claims.len() > limit {
+            claims.truncate(limit);
+            claims
+                .last()
+                .map(|claim| (claim.release_at, claim.addr.clone()))
+        } else {
+            None
+        };
+
+        let mut days: Vec<DailyRelease> = vec![];
+        for claim in claims {
+            let day = claim.release_at.as_key() / NANOS_PER_DAY;
+            let current = match days.last_mut() {
+                Some(current) if current.day == day => current,
+                _ => {
+                    days.push(DailyRelease {
+                        day,
+                        claims: 0,
+                        amount: Uint128::zero(),
+                        vesting_amount: Uint128::zero(),
+                        extra_amounts: vec![],
+                    });
+                    days.last_mut().unwrap()
+                }
+            };
+            current.claims += 1;
+            current.amount += claim.amount;
+            current.vesting_amount += claim.vesting_amount.unwrap_or_default();
+            for extra in &claim.extra_amounts {
+                add_coin(&mut current.extra_amounts, extra);
+            }
+        }
+
+        Ok((days, next))
+    }
+}
+
+/// Adds `coin` to the `coins` list, merging it with the same denom if present.
+/// Zero amounts are not added.
+pub(crate) fn add_coin(coins: &mut Vec<Coin>, coin: &Coin) {
+    if coin.amount.is_zero() {
+        return;
+    }
+    match coins.iter_mut().find(|c| c.denom == coin.denom) {
+        Some(c) => c.amount += coin.amount,
+        None => coins.push(coin.clone()),
+    }
+}
+
+/// Subtracts `coin` from the `coins` list, removing the denom entirely when it drops to zero.
+/// Panics on underflow, which is a logic error of the caller.
+fn sub_coin(coins: &mut Vec<Coin>, coin: &Coin) {
+    if let Some(pos) = coins.iter().position(|c| c.denom == coin.denom) {
+        coins[pos].amount -= coin.amount;
+        if coins[pos].amount.is_zero() {
+            coins.remove(pos);
+        }
+    }
+}
+
+/// Sums coins, merging them by denom.
+fn sum_coins<'c>(coins: impl IntoIterator<Item = &'c Coin>) -> Vec<Coin> {
+    let mut sum = vec![];
+    for coin in coins {
+        add_coin(&mut sum, coin);
+    }
+    sum
 }
 
 // Helper to repair the auto-release claims bug (#198)
@@ -1,15 +1,15 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    coin, coins, to_binary, Addr, BankMsg, Binary, Coin, CustomQuery, Decimal, Deps, DepsMut, Env,
-    MessageInfo, Order, StdError, StdResult, Storage, Uint128,
+    coin, coins, to_binary, Addr, BankMsg, Binary, BlockInfo, Coin, CustomQuery, Decimal, Deps,
+    DepsMut, Env, MessageInfo, Order, StdResult, Storage, Uint128, WasmMsg,
 };
 use std::cmp::min;
 use std::ops::Sub;
 
-use crate::claim::process_pending_undelegations;
+use crate::claim::{add_coin, process_pending_undelegations};
 use cw2::set_contract_version;
-use cw_storage_plus::Bound;
+use cw_storage_plus::{Bound, PrefixBound};
 use cw_utils::{ensure_from_older_version, maybe_addr};
 use tg4::{
     HooksResponse, Member, MemberChangedHookMsg, MemberDiff, MemberInfo, MemberListResponse,
@@ -19,16 +19,21 @@ use tg_bindings::{
     request_privileges, Privilege, PrivilegeChangeMsg, TgradeMsg, TgradeQuery, TgradeSudoMsg,
 };
 use tg_utils::{
-    members, validate_portion, Duration, ADMIN, HOOKS, PREAUTH_HOOKS, PREAUTH_SLASHING, SLASHERS,
-    TOTAL,
+    members, validate_portion, Duration, Expiration, ADMIN, HOOKS, PREAUTH_HOOKS, PREAUTH_SLASHING,
+    SLASHERS, TOTAL,
 };
 
 use crate::error::ContractError;
 use crate::msg::{
-    ClaimsResponse, ExecuteMsg, InstantiateMsg, MigrateMsg, PreauthResponse, QueryMsg,
-    StakedResponse, UnbondingPeriodResponse,
+    ClaimsResponse, DistributionMsg, ExecuteMsg, InstantiateMsg, LocksResponse, MigrateMsg,
+    PendingClaimCursor, PendingReleasesResponse, PreauthResponse, QueryMsg, RecalculationResponse,
+    SlashesResponse, StakeChangeKind, StakeChangedHookMsg, StakedResponse, UnbondingPeriodResponse,
+};
+use crate::state::{
+    claims, export, import, locks, slashes, Config, Lock, LockTier, Recalculation, SlashRecord,
+    StakeDenom, StakeState, CONFIG, RECALCULATION, SLASH_COUNT, STAKE, STAKE_EXTRA, STAKE_HOOKS,
+    STAKE_VESTING,
 };
-use crate::state::{claims, Config, CONFIG, STAKE, STAKE_VESTING};
 
 pub type Response = cosmwasm_std::Response<TgradeMsg>;
 pub type SubMsg = cosmwasm_std::SubMsg<TgradeMsg>;
@@ -61,6 +66,12 @@ pub fn instantiate(
     };
 
     let config = Config {
+        extra_denoms: validate_extra_denoms(&msg.denom, msg.extra_denoms)?,
+        lock_tiers: validate_lock_tiers(msg.lock_tiers)?,
+        instant_unbond: msg
+            .instant_unbond
+            .map(|instant_unbond| instant_unbond.validate(api))
+            .transpose()?,
         denom: msg.denom,
         tokens_per_point: msg.tokens_per_point,
         min_bond,
@@ -74,6 +85,45 @@ pub fn instantiate(
     Ok(Response::default())
 }
 
+/// Validates additional staking denoms: they cannot repeat, nor be the same as the main staking
+/// denom, and have to have a non-zero `tokens_per_point`. Zero `min_bond` is bumped to 1, so
+/// 0 stake doesn't count towards points.
+fn validate_extra_denoms(
+    denom: &str,
+    extra_denoms: Vec<StakeDenom>,
+) -> Result<Vec<StakeDenom>, ContractError> {
+    let mut validated: Vec<StakeDenom> = Vec::with_capacity(extra_denoms.len());
+    for mut extra in extra_denoms {
+        if extra.denom == denom || validated.iter().any(|d| d.denom == extra.denom) {
+            return Err(ContractError::DuplicatedDenom(extra.denom));
+        }
+        if extra.tokens_per_point.is_zero() {
+            return Err(ContractError::InvalidTokensPerPoint(extra.denom));
+        }
+        if extra.min_bond.is_zero() {
+            extra.min_bond = Uint128::new(1);
+        }
+        validated.push(extra);
+    }
+    Ok(validated)
+}
+
+/// Validates lockup tiers: durations have to be non-zero and unique, and multipliers cannot
+/// be lower than 1. Returns tiers sorted by duration.
+fn validate_lock_tiers(mut lock_tiers: Vec<LockTier>) -> Result<Vec<LockTier>, ContractError> {
+    lock_tiers.sort_by_key(|tier| tier.duration.seconds());
+    let durations_valid = lock_tiers
+        .windows(2)
+        .all(|tiers| tiers[0].duration != tiers[1].duration);
+    let tiers_valid = lock_tiers
+        .iter()
+        .all(|tier| tier.duration.seconds() > 0 && tier.multiplier >= Decimal::one());
+    if !durations_valid || !tiers_valid {
+        return Err(ContractError::InvalidLockTiers {});
+    }
+    Ok(lock_tiers)
+}
+
 // And declare a custom Error variant for the ones where you will want to make use of it
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn execute(
@@ -89,11 +139,27 @@ pub fn execute(
             .map_err(Into::into),
         ExecuteMsg::AddHook { addr } => execute_add_hook(deps, info, addr),
         ExecuteMsg::RemoveHook { addr } => execute_remove_hook(deps, info, addr),
-        ExecuteMsg::Bond { vesting_tokens } => execute_bond(deps, env, info, vesting_tokens),
+        ExecuteMsg::AddStakeHook { addr } => execute_add_stake_hook(deps, info, addr),
+        ExecuteMsg::RemoveStakeHook { addr } => execute_remove_stake_hook(deps, info, addr),
+        ExecuteMsg::Bond {
+            vesting_tokens,
+            lock_duration,
+        } => execute_bond(deps, env, info, vesting_tokens, lock_duration),
+        ExecuteMsg::BondFor {
+            beneficiary,
+            vesting_tokens,
+        } => execute_bond_for(deps, env, info, beneficiary, vesting_tokens),
         ExecuteMsg::Unbond {
             tokens: Coin { amount, denom },
         } => execute_unbond(deps, env, info, amount, denom),
         ExecuteMsg::Claim {} => execute_claim(deps, env, info),
+        ExecuteMsg::Rebond {
+            tokens: Coin { amount, denom },
+        } => execute_rebond(deps, env, info, amount, denom),
+        ExecuteMsg::InstantUnbond {
+            tokens: Coin { amount, denom },
+        } => execute_instant_unbond(deps, env, info, amount, denom),
+        ExecuteMsg::RecalculatePoints { limit } => execute_recalculate_points(deps, env, limit),
         ExecuteMsg::AddSlasher { addr } => execute_add_slasher(deps, info, addr),
         ExecuteMsg::RemoveSlasher { addr } => execute_remove_slasher(deps, info, addr),
         ExecuteMsg::Slash { addr, portion } => execute_slash(deps, env, info, addr, portion),
@@ -145,37 +211,140 @@ pub fn execute_remove_hook<Q: CustomQuery>(
     Ok(res)
 }
 
+pub fn execute_add_stake_hook<Q: CustomQuery>(
+    deps: DepsMut<Q>,
+    info: MessageInfo,
+    hook: String,
+) -> Result<Response, ContractError> {
+    // custom guard: using a preauth OR being admin
+    if !ADMIN.is_admin(deps.as_ref(), &info.sender)? {
+        PREAUTH_HOOKS.use_auth(deps.storage)?;
+    }
+
+    // add the hook
+    STAKE_HOOKS.add_hook(deps.storage, deps.api.addr_validate(&hook)?)?;
+
+    // response
+    let res = Response::new()
+        .add_attribute("action", "add_stake_hook")
+        .add_attribute("hook", hook)
+        .add_attribute("sender", info.sender);
+    Ok(res)
+}
+
+pub fn execute_remove_stake_hook<Q: CustomQuery>(
+    deps: DepsMut<Q>,
+    info: MessageInfo,
+    hook: String,
+) -> Result<Response, ContractError> {
+    // custom guard: self-removal OR being admin
+    let hook_addr = deps.api.addr_validate(&hook)?;
+    if info.sender != hook_addr && !ADMIN.is_admin(deps.as_ref(), &info.sender)? {
+        return Err(ContractError::Unauthorized(
+            "Hook address is not same as sender's and sender is not an admin".to_owned(),
+        ));
+    }
+
+    // remove the hook
+    STAKE_HOOKS.remove_hook(deps.storage, hook_addr)?;
+
+    // response
+    let res = Response::new()
+        .add_attribute("action", "remove_stake_hook")
+        .add_attribute("hook", hook)
+        .add_attribute("sender", info.sender);
+    Ok(res)
+}
+
 pub fn execute_bond<Q: CustomQuery>(
     deps: DepsMut<Q>,
     env: Env,
     info: MessageInfo,
     vesting_tokens: Option<Coin>,
+    lock_duration: Option<u64>,
+) -> Result<Response, ContractError> {
+    let beneficiary = info.sender.clone();
+    bond_tokens(deps, env, info, beneficiary, vesting_tokens, lock_duration)
+}
+
+pub fn execute_bond_for<Q: CustomQuery>(
+    deps: DepsMut<Q>,
+    env: Env,
+    info: MessageInfo,
+    beneficiary: String,
+    vesting_tokens: Option<Coin>,
+) -> Result<Response, ContractError> {
+    let beneficiary = deps.api.addr_validate(&beneficiary)?;
+    // Vesting tokens are returned to the staker's vesting account on claim, so only their
+    // owner can bond them
+    if beneficiary != info.sender
+        && vesting_tokens
+            .as_ref()
+            .is_some_and(|tokens| !tokens.amount.is_zero())
+    {
+        return Err(ContractError::VestingBondFor {});
+    }
+
+    bond_tokens(deps, env, info, beneficiary, vesting_tokens, None)
+}
+
+/// Bonds the funds sent with the message and the optional `vesting_tokens`, crediting the
+/// stake (and membership points) to `beneficiary`. If `lock_duration` is set, the bonded
+/// tokens are locked for that long.
+fn bond_tokens<Q: CustomQuery>(
+    deps: DepsMut<Q>,
+    env: Env,
+    info: MessageInfo,
+    beneficiary: Addr,
+    vesting_tokens: Option<Coin>,
+    lock_duration: Option<u64>,
 ) -> Result<Response, ContractError> {
     let cfg = CONFIG.load(deps.storage)?;
-    let amount = validate_funds(&info.funds, &cfg.denom)?;
+    let (amount, extra_amounts) = validate_bond_funds(&info.funds, &cfg)?;
     let vesting_amount = vesting_tokens
         .map(|v| validate_funds(&[v], &cfg.denom))
         .transpose()?
         .unwrap_or_default();
-    if amount + vesting_amount == Uint128::zero() {
+    if amount + vesting_amount == Uint128::zero() && extra_amounts.is_empty() {
         return Err(ContractError::NoFunds {});
     }
+    let lock_multiplier = lock_duration
+        .map(|duration| {
+            if !extra_amounts.is_empty() {
+                return Err(ContractError::LockedExtraDenoms {});
+            }
+            cfg.lock_multiplier(duration)
+                .ok_or(ContractError::InvalidLockDuration(duration))
+        })
+        .transpose()?;
 
-    // update the sender's stake
-    let new_stake = STAKE.update(deps.storage, &info.sender, |stake| -> StdResult<_> {
+    // update the beneficiary's stake
+    STAKE.update(deps.storage, &beneficiary, |stake| -> StdResult<_> {
         Ok(stake.unwrap_or_default() + amount)
     })?;
+    for extra in &extra_amounts {
+        STAKE_EXTRA.update(
+            deps.storage,
+            (&beneficiary, &extra.denom),
+            |stake| -> StdResult<_> { Ok(stake.unwrap_or_default() + extra.amount) },
+        )?;
+    }
 
     let mut res = Response::new()
         .add_attribute("action", "bond")
         .add_attribute("amount", amount)
         .add_attribute("sender", &info.sender);
+    if beneficiary != info.sender {
+        res = res.add_attribute("beneficiary", &beneficiary);
+    }
+    if !extra_amounts.is_empty() {
+        res = res.add_attribute("extra_amounts", coins_to_string(&extra_amounts));
+    }
 
-    // Update the sender's vesting stake
-    let new_vesting_stake =
-        STAKE_VESTING.update(deps.storage, &info.sender, |stake| -> StdResult<_> {
-            Ok(stake.unwrap_or_default() + vesting_amount)
-        })?;
+    // Update the beneficiary's vesting stake
+    STAKE_VESTING.update(deps.storage, &beneficiary, |stake| -> StdResult<_> {
+        Ok(stake.unwrap_or_default() + vesting_amount)
+    })?;
     // Delegate (stake to contract) to sender's vesting account
     if vesting_amount > Uint128::zero() {
         let msg = TgradeMsg::Delegate {
@@ -187,13 +356,49 @@ pub fn execute_bond<Q: CustomQuery>(
             .add_attribute("vesting_amount", vesting_amount);
     }
 
+    // Lock the bonded tokens, if requested
+    remove_expired_locks(deps.storage, &beneficiary, &env.block)?;
+    if let (Some(duration), Some(multiplier)) = (lock_duration, lock_multiplier) {
+        let unlock_at = Duration::new(duration).after(&env.block);
+        locks().update(
+            deps.storage,
+            (&beneficiary, unlock_at.as_key()),
+            |lock| -> Result<_, ContractError> {
+                Ok(match lock {
+                    Some(mut lock) => {
+                        // merging would apply the old multiplier to the new tokens
+                        if lock.multiplier != multiplier {
+                            return Err(ContractError::LockMultiplierMismatch {});
+                        }
+                        lock.amount += amount + vesting_amount;
+                        lock
+                    }
+                    None => Lock {
+                        addr: beneficiary.clone(),
+                        amount: amount + vesting_amount,
+                        multiplier,
+                        unlock_at,
+                    },
+                })
+            },
+        )?;
+        res = res.add_attribute("unlock_time", unlock_at.time().nanos().to_string());
+    }
+
     // Update membership messages
     res = res.add_submessages(update_membership(
         deps.storage,
-        info.sender,
-        new_stake + new_vesting_stake,
+        beneficiary.clone(),
         &cfg,
-        env.block.height,
+        &env.block,
+    )?);
+    res = res.add_submessages(stake_changed_hooks(
+        deps.storage,
+        &beneficiary,
+        StakeChangeKind::Bond,
+        amount,
+        vesting_amount,
+        &extra_amounts,
     )?);
 
     Ok(res)
@@ -213,18 +418,8 @@ pub fn execute_unbond<Q: CustomQuery>(
     // provide them a claim
     let cfg = CONFIG.load(deps.storage)?;
 
-    if cfg.denom != denom {
-        return Err(ContractError::InvalidDenom {});
-    }
-
-    // Load stake first for comparison
-    let stake = STAKE
-        .may_load(deps.storage, &info.sender)?
-        .unwrap_or_default();
-    // Reduce the sender's stake - saturating if insufficient
-    let new_stake = STAKE.update(deps.storage, &info.sender, |stake| -> StdResult<_> {
-        Ok(stake.unwrap_or_default().saturating_sub(amount))
-    })?;
+    let (liquid_amount, vesting_amount, extra_amounts) =
+        unbond_tokens(deps.storage, &env.block, &info.sender, amount, &denom, &cfg)?;
 
     let mut res = Response::new()
         .add_attribute("action", "unbond")
@@ -232,20 +427,14 @@ pub fn execute_unbond<Q: CustomQuery>(
         .add_attribute("denom", &denom)
         .add_attribute("sender", &info.sender);
 
-    // Reduce the sender's vesting stake - aborting if insufficient
-    let vesting_amount = amount.saturating_sub(stake);
-    let new_vesting_stake =
-        STAKE_VESTING.update(deps.storage, &info.sender, |stake| -> StdResult<_> {
-            Ok(stake.unwrap_or_default().checked_sub(vesting_amount)?)
-        })?;
-
     // Create claim for unbonded liquid and vesting amounts
     let completion = cfg.unbonding_period.after(&env.block);
     claims().create_claim(
         deps.storage,
         info.sender.clone(),
-        min(stake, amount),
+        liquid_amount,
         vesting_amount,
+        &extra_amounts,
         completion,
         env.block.height,
     )?;
@@ -254,10 +443,228 @@ pub fn execute_unbond<Q: CustomQuery>(
     // Update membership messages
     res = res.add_submessages(update_membership(
         deps.storage,
-        info.sender,
-        new_stake + new_vesting_stake,
+        info.sender.clone(),
         &cfg,
-        env.block.height,
+        &env.block,
+    )?);
+    res = res.add_submessages(stake_changed_hooks(
+        deps.storage,
+        &info.sender,
+        StakeChangeKind::Unbond,
+        liquid_amount,
+        vesting_amount,
+        &extra_amounts,
+    )?);
+
+    Ok(res)
+}
+
+/// Removes `amount` of `denom` tokens from the stake of `addr`. Tokens of the main staking denom
+/// are taken from the liquid stake first, and then from the vesting stake if available.
+/// Locked tokens cannot be removed.
+/// Returns the liquid, vesting and additional denoms amounts removed from the stake.
+fn unbond_tokens(
+    storage: &mut dyn Storage,
+    block: &BlockInfo,
+    addr: &Addr,
+    amount: Uint128,
+    denom: &str,
+    cfg: &Config,
+) -> Result<(Uint128, Uint128, Vec<Coin>), ContractError> {
+    if cfg.denom == denom {
+        // Load stake first for comparison
+        let stake = STAKE.may_load(storage, addr)?.unwrap_or_default();
+
+        // Locked tokens stay where they are
+        remove_expired_locks(storage, addr, block)?;
+        let locked = locked_amount(storage, addr)?;
+        if !locked.is_zero() {
+            let vesting_stake = STAKE_VESTING.may_load(storage, addr)?.unwrap_or_default();
+            let unlocked = (stake + vesting_stake).saturating_sub(locked);
+            if amount > unlocked {
+                return Err(ContractError::LockedTokens(unlocked));
+            }
+        }
+
+        // Reduce the sender's stake - saturating if insufficient
+        STAKE.update(storage, addr, |stake| -> StdResult<_> {
+            Ok(stake.unwrap_or_default().saturating_sub(amount))
+        })?;
+
+        // Reduce the sender's vesting stake - aborting if insufficient
+        let vesting_amount = amount.saturating_sub(stake);
+        STAKE_VESTING.update(storage, addr, |stake| -> StdResult<_> {
+            Ok(stake.unwrap_or_default().checked_sub(vesting_amount)?)
+        })?;
+
+        Ok((min(stake, amount), vesting_amount, vec![]))
+    } else if cfg.extra_denom(denom).is_some() {
+        // Reduce the sender's stake in the additional denom - aborting if insufficient
+        STAKE_EXTRA.update(storage, (addr, denom), |stake| -> StdResult<_> {
+            Ok(stake.unwrap_or_default().checked_sub(amount)?)
+        })?;
+
+        Ok((
+            Uint128::zero(),
+            Uint128::zero(),
+            coins(amount.u128(), denom),
+        ))
+    } else {
+        Err(ContractError::InvalidDenom {})
+    }
+}
+
+pub fn execute_instant_unbond<Q: CustomQuery>(
+    deps: DepsMut<Q>,
+    env: Env,
+    info: MessageInfo,
+    amount: Uint128,
+    denom: String,
+) -> Result<Response, ContractError> {
+    if amount.is_zero() {
+        return Err(ContractError::ZeroAmount {});
+    }
+
+    let cfg = CONFIG.load(deps.storage)?;
+    let instant_unbond = cfg
+        .instant_unbond
+        .clone()
+        .ok_or(ContractError::InstantUnbondDisabled {})?;
+
+    // The penalty recipient only distributes the main denom
+    if cfg.denom != denom {
+        return Err(ContractError::InvalidDenom {});
+    }
+    // Vesting stake has to go through the regular unbonding
+    let stake = STAKE
+        .may_load(deps.storage, &info.sender)?
+        .unwrap_or_default();
+    if amount > stake {
+        return Err(ContractError::InsufficientLiquidStake(stake));
+    }
+    // Locked tokens are taken from the liquid stake first
+    remove_expired_locks(deps.storage, &info.sender, &env.block)?;
+    let unlocked = stake.saturating_sub(locked_amount(deps.storage, &info.sender)?);
+    if amount > unlocked {
+        return Err(ContractError::LockedTokens(unlocked));
+    }
+
+    let (liquid_amount, _, extra_amounts) =
+        unbond_tokens(deps.storage, &env.block, &info.sender, amount, &denom, &cfg)?;
+
+    let penalty = amount * instant_unbond.penalty;
+    let payout = amount - penalty;
+
+    let mut res = Response::new()
+        .add_attribute("action", "instant_unbond")
+        .add_attribute("amount", amount)
+        .add_attribute("denom", &denom)
+        .add_attribute("penalty", penalty)
+        .add_attribute("sender", &info.sender);
+
+    if !payout.is_zero() {
+        res = res.add_message(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: coins(payout.u128(), &denom),
+        });
+    }
+    if !penalty.is_zero() {
+        res = res.add_message(WasmMsg::Execute {
+            contract_addr: instant_unbond.recipient.to_string(),
+            msg: to_binary(&DistributionMsg::DistributeRewards {})?,
+            funds: coins(penalty.u128(), &denom),
+        });
+    }
+
+    // Update membership messages
+    res = res.add_submessages(update_membership(
+        deps.storage,
+        info.sender.clone(),
+        &cfg,
+        &env.block,
+    )?);
+    res = res.add_submessages(stake_changed_hooks(
+        deps.storage,
+        &info.sender,
+        StakeChangeKind::Unbond,
+        liquid_amount,
+        Uint128::zero(),
+        &extra_amounts,
+    )?);
+
+    Ok(res)
+}
+
+pub fn execute_rebond<Q: CustomQuery>(
+    deps: DepsMut<Q>,
+    env: Env,
+    info: MessageInfo,
+    amount: Uint128,
+    denom: String,
+) -> Result<Response, ContractError> {
+    if amount.is_zero() {
+        return Err(ContractError::ZeroAmount {});
+    }
+
+    let cfg = CONFIG.load(deps.storage)?;
+
+    // Take the tokens back from the pending claims, newest first
+    let (liquid_amount, vesting_amount) = if cfg.denom == denom {
+        claims().rebond_claims(deps.storage, &info.sender, &env.block, amount)?
+    } else if cfg.extra_denom(&denom).is_some() {
+        let tokens = coin(amount.u128(), &denom);
+        let rebonded =
+            claims().rebond_extra_claims(deps.storage, &info.sender, &env.block, &tokens)?;
+        (rebonded, Uint128::zero())
+    } else {
+        return Err(ContractError::InvalidDenom {});
+    };
+    if liquid_amount + vesting_amount < amount {
+        return Err(ContractError::InsufficientUnbonding(amount));
+    }
+
+    // Restore the sender's liquid and vesting stake
+    if cfg.denom == denom {
+        STAKE.update(deps.storage, &info.sender, |stake| -> StdResult<_> {
+            Ok(stake.unwrap_or_default() + liquid_amount)
+        })?;
+        STAKE_VESTING.update(deps.storage, &info.sender, |stake| -> StdResult<_> {
+            Ok(stake.unwrap_or_default() + vesting_amount)
+        })?;
+    } else {
+        STAKE_EXTRA.update(
+            deps.storage,
+            (&info.sender, &denom),
+            |stake| -> StdResult<_> { Ok(stake.unwrap_or_default() + liquid_amount) },
+        )?;
+    }
+
+    let mut res = Response::new()
+        .add_attribute("action", "rebond")
+        .add_attribute("amount", liquid_amount)
+        .add_attribute("vesting_amount", vesting_amount)
+        .add_attribute("denom", &denom)
+        .add_attribute("sender", &info.sender);
+
+    // Update membership messages
+    res = res.add_submessages(update_membership(
+        deps.storage,
+        info.sender.clone(),
+        &cfg,
+        &env.block,
+    )?);
+    let (liquid_delta, extra_delta) = if cfg.denom == denom {
+        (liquid_amount, vec![])
+    } else {
+        (Uint128::zero(), coins(liquid_amount.u128(), &denom))
+    };
+    res = res.add_submessages(stake_changed_hooks(
+        deps.storage,
+        &info.sender,
+        StakeChangeKind::Bond,
+        liquid_delta,
+        vesting_amount,
+        &extra_delta,
     )?);
 
     Ok(res)
@@ -328,9 +735,10 @@ pub fn execute_slash<Q: CustomQuery>(
 
     let liquid_stake = STAKE.may_load(deps.storage, &addr)?;
     let vesting_stake = STAKE_VESTING.may_load(deps.storage, &addr)?;
+    let extra_stake = load_extra_stake(deps.storage, &addr)?;
 
     // If address doesn't match anyone, leave early
-    if liquid_stake.is_none() && vesting_stake.is_none() {
+    if liquid_stake.is_none() && vesting_stake.is_none() && extra_stake.is_empty() {
         return Ok(Response::new());
     }
 
@@ -338,33 +746,87 @@ pub fn execute_slash<Q: CustomQuery>(
     let mut res = Response::new()
         .add_attribute("action", "slash")
         .add_attribute("addr", &addr)
-        .add_attribute("sender", info.sender);
+        .add_attribute("sender", &info.sender);
 
     // slash the liquid stake, if any
-    let mut new_liquid_stake = Uint128::zero();
     let mut liquid_slashed = Uint128::zero();
     if let Some(liquid_stake) = liquid_stake {
         liquid_slashed = liquid_stake * portion;
-        new_liquid_stake = STAKE.update(deps.storage, &addr, |stake| -> StdResult<_> {
+        STAKE.update(deps.storage, &addr, |stake| -> StdResult<_> {
             Ok(stake.unwrap_or_default().sub(liquid_slashed))
         })?;
     }
 
     // slash the vesting stake, if any
-    let mut new_vesting_stake = Uint128::zero();
     let mut vesting_slashed = Uint128::zero();
     if let Some(vesting_stake) = vesting_stake {
         vesting_slashed = vesting_stake * portion;
-        new_vesting_stake = STAKE_VESTING.update(deps.storage, &addr, |stake| -> StdResult<_> {
+        STAKE_VESTING.update(deps.storage, &addr, |stake| -> StdResult<_> {
             Ok(stake.unwrap_or_default().sub(vesting_slashed))
         })?;
     }
 
+    // slash the locked stake proportionally, so locks never exceed the stake
+    let addr_locks = locks()
+        .prefix(&addr)
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    for (unlock_at, mut lock) in addr_locks {
+        lock.amount -= lock.amount * portion;
+        locks().save(deps.storage, (&addr, unlock_at), &lock)?;
+    }
+
+    // slash the stake in additional denoms, if any
+    let mut extra_slashed: Vec<Coin> = vec![];
+    for stake in extra_stake {
+        let slashed = stake.amount * portion;
+        STAKE_EXTRA.save(
+            deps.storage,
+            (&addr, &stake.denom),
+            &(stake.amount - slashed),
+        )?;
+        extra_slashed.push(coin(slashed.u128(), stake.denom));
+    }
+
+    extra_slashed.retain(|c| !c.amount.is_zero());
+
     // slash the liquid and vesting claims
-    let (liquid_claims_slashed, vesting_claims_slashed) =
+    let (liquid_claims_slashed, vesting_claims_slashed, extra_claims_slashed) =
         claims().slash_claims_for_addr(deps.storage, addr.clone(), portion)?;
+
+    // keep the record of the slashing
+    let id = SLASH_COUNT.may_load(deps.storage)?.unwrap_or_default() + 1;
+    SLASH_COUNT.save(deps.storage, &id)?;
+    let record = SlashRecord {
+        id,
+        addr: addr.clone(),
+        slasher: info.sender,
+        height: env.block.height,
+        time: env.block.time,
+        portion,
+        liquid: liquid_slashed,
+        vesting: vesting_slashed,
+        extra: extra_slashed.clone(),
+        claims_liquid: liquid_claims_slashed,
+        claims_vesting: vesting_claims_slashed,
+        claims_extra: extra_claims_slashed.clone(),
+    };
+    slashes().save(deps.storage, id, &record)?;
+    res = res.add_attribute("slash_id", id.to_string());
+    let stake_hooks = stake_changed_hooks(
+        deps.storage,
+        &addr,
+        StakeChangeKind::Slash,
+        record.liquid,
+        record.vesting,
+        &record.extra,
+    )?;
+
     liquid_slashed += liquid_claims_slashed;
     vesting_slashed += vesting_claims_slashed;
+    for claim_slashed in &extra_claims_slashed {
+        add_coin(&mut extra_slashed, claim_slashed);
+    }
 
     // burn the liquid slashed tokens
     if liquid_slashed > Uint128::zero() {
@@ -382,13 +844,17 @@ pub fn execute_slash<Q: CustomQuery>(
         res = res.add_message(burn_vesting_msg);
     }
 
-    res.messages.extend(update_membership(
-        deps.storage,
-        addr,
-        new_liquid_stake + new_vesting_stake,
-        &cfg,
-        env.block.height,
-    )?);
+    // burn the slashed tokens of additional denoms
+    if !extra_slashed.is_empty() {
+        let burn_extra_msg = BankMsg::Burn {
+            amount: extra_slashed,
+        };
+        res = res.add_message(burn_extra_msg);
+    }
+
+    res.messages
+        .extend(update_membership(deps.storage, addr, &cfg, &env.block)?);
+    res.messages.extend(stake_hooks);
 
     Ok(res)
 }
@@ -407,25 +873,109 @@ pub fn validate_funds(funds: &[Coin], stake_denom: &str) -> Result<Uint128, Cont
     }
 }
 
+/// Validates funds sent with a bond message. Without additional staking denoms configured,
+/// this works exactly as `validate_funds`. Otherwise, any number of accepted denoms can be sent.
+/// Returns the amount of the main staking denom, and the non-zero amounts of additional denoms.
+fn validate_bond_funds(
+    funds: &[Coin],
+    cfg: &Config,
+) -> Result<(Uint128, Vec<Coin>), ContractError> {
+    if cfg.extra_denoms.is_empty() {
+        return Ok((validate_funds(funds, &cfg.denom)?, vec![]));
+    }
+
+    let mut amount = Uint128::zero();
+    let mut extra_amounts = vec![];
+    for fund in funds {
+        if fund.denom == cfg.denom {
+            amount += fund.amount;
+        } else if cfg.extra_denom(&fund.denom).is_some() {
+            if !fund.amount.is_zero() {
+                extra_amounts.push(fund.clone());
+            }
+        } else {
+            return Err(ContractError::UnsupportedDenom(fund.denom.clone()));
+        }
+    }
+    Ok((amount, extra_amounts))
+}
+
+/// Loads the non-zero liquid stake of `addr` in additional staking denoms.
+fn load_extra_stake(storage: &dyn Storage, addr: &Addr) -> StdResult<Vec<Coin>> {
+    STAKE_EXTRA
+        .prefix(addr)
+        .range(storage, None, None, Order::Ascending)
+        .filter(|item| !matches!(item, Ok((_, amount)) if amount.is_zero()))
+        .map(|item| item.map(|(denom, amount)| coin(amount.u128(), denom)))
+        .collect()
+}
+
+fn coins_to_string(coins: &[Coin]) -> String {
+    coins
+        .iter()
+        .map(Coin::to_string)
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
 fn update_membership(
     storage: &mut dyn Storage,
     sender: Addr,
-    new_stake: Uint128,
     cfg: &Config,
-    height: u64,
+    block: &BlockInfo,
+) -> StdResult<Vec<SubMsg>> {
+    let diff = match update_points(storage, sender, cfg, block)? {
+        Some(diff) => diff,
+        None => return Ok(vec![]),
+    };
+
+    // alert the hooks
+    HOOKS.prepare_hooks(storage, |h| {
+        MemberChangedHookMsg::one(diff.clone())
+            .into_cosmos_msg(h)
+            .map(SubMsg::new)
+    })
+}
+
+/// Prepares messages informing the stake hooks about the change of stake of `addr`
+fn stake_changed_hooks(
+    storage: &dyn Storage,
+    addr: &Addr,
+    kind: StakeChangeKind,
+    liquid_delta: Uint128,
+    vesting_delta: Uint128,
+    extra_delta: &[Coin],
 ) -> StdResult<Vec<SubMsg>> {
+    let msg = StakeChangedHookMsg {
+        addr: addr.to_string(),
+        kind,
+        liquid_delta,
+        vesting_delta,
+        extra_delta: extra_delta.to_vec(),
+    };
+    STAKE_HOOKS.prepare_hooks(storage, |h| msg.clone().into_cosmos_msg(h).map(SubMsg::new))
+}
+
+/// Recomputes membership points of `addr` from its stake, and records them if changed.
+/// Returns the change to be sent to the hooks, if any.
+fn update_points(
+    storage: &mut dyn Storage,
+    addr: Addr,
+    cfg: &Config,
+    block: &BlockInfo,
+) -> StdResult<Option<MemberDiff>> {
     // update their membership points
-    let new = calc_points(new_stake, cfg);
-    let old = members().may_load(storage, &sender)?.map(|mi| mi.points);
+    let new = calc_points(storage, &addr, cfg, block)?;
+    let old = members().may_load(storage, &addr)?.map(|mi| mi.points);
 
     // short-circuit if no change
     if new == old {
-        return Ok(vec![]);
+        return Ok(None);
     }
     // otherwise, record change of points
     match new.as_ref() {
-        Some(&p) => members().save(storage, &sender, &MemberInfo::new(p), height),
-        None => members().remove(storage, &sender, height),
+        Some(&p) => members().save(storage, &addr, &MemberInfo::new(p), block.height),
+        None => members().remove(storage, &addr, block.height),
     }?;
 
     // update total
@@ -433,20 +983,188 @@ fn update_membership(
         Ok(total + new.unwrap_or_default() - old.unwrap_or_default())
     })?;
 
-    // alert the hooks
-    let diff = MemberDiff::new(sender, old, new);
-    HOOKS.prepare_hooks(storage, |h| {
-        MemberChangedHookMsg::one(diff.clone())
-            .into_cosmos_msg(h)
-            .map(SubMsg::new)
-    })
+    Ok(Some(MemberDiff::new(addr, old, new)))
+}
+
+pub fn execute_recalculate_points<Q: CustomQuery>(
+    deps: DepsMut<Q>,
+    env: Env,
+    limit: Option<u32>,
+) -> Result<Response, ContractError> {
+    if limit == Some(0) {
+        return Err(ContractError::ZeroLimit {});
+    }
+    let mut recalculation = RECALCULATION
+        .may_load(deps.storage)?
+        .ok_or(ContractError::NoRecalculation {})?;
+    let cfg = CONFIG.load(deps.storage)?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+
+    let stakers = list_stakers(deps.storage, recalculation.start_after.as_ref(), limit)?;
+    let done = stakers.len() < limit;
+
+    let mut diffs = vec![];
+    for addr in stakers {
+        recalculation.start_after = Some(addr.clone());
+        recalculation.processed += 1;
+        if let Some(diff) = update_points(deps.storage, addr, &cfg, &env.block)? {
+            diffs.push(diff);
+        }
+    }
+
+    if done {
+        RECALCULATION.remove(deps.storage);
+    } else {
+        RECALCULATION.save(deps.storage, &recalculation)?;
+    }
+
+    let mut res = Response::new()
+        .add_attribute("action", "recalculate_points")
+        .add_attribute("processed", recalculation.processed.to_string())
+        .add_attribute("finished", done.to_string());
+
+    // alert the hooks with all the changes at once
+    if !diffs.is_empty() {
+        res = res.add_submessages(HOOKS.prepare_hooks(deps.storage, |h| {
+            MemberChangedHookMsg::new(diffs.clone())
+                .into_cosmos_msg(h)
+                .map(SubMsg::new)
+        })?);
+    }
+
+    Ok(res)
+}
+
+/// Lists up to `limit` addresses having stake in any denom, in ascending order, starting after
+/// `start_after`.
+fn list_stakers(
+    storage: &dyn Storage,
+    start_after: Option<&Addr>,
+    limit: usize,
+) -> StdResult<Vec<Addr>> {
+    // Every map gives its first `limit` addresses, so the first `limit` of all of them together
+    // are among those
+    let mut stakers = STAKE
+        .keys(
+            storage,
+            start_after.map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .take(limit)
+        .collect::<StdResult<Vec<_>>>()?;
+    stakers.extend(
+        STAKE_VESTING
+            .keys(
+                storage,
+                start_after.map(Bound::exclusive),
+                None,
+                Order::Ascending,
+            )
+            .take(limit)
+            .collect::<StdResult<Vec<_>>>()?,
+    );
+    let mut extra_stakers: Vec<Addr> = vec![];
+    for item in STAKE_EXTRA.prefix_range(
+        storage,
+        start_after.map(PrefixBound::exclusive),
+        None,
+        Order::Ascending,
+    ) {
+        let ((addr, _), _) = item?;
+        if extra_stakers.last() != Some(&addr) {
+            if extra_stakers.len() == limit {
+                break;
+            }
+            extra_stakers.push(addr);
+        }
+    }
+    stakers.extend(extra_stakers);
+
+    stakers.sort();
+    stakers.dedup();
+    stakers.truncate(limit);
+    Ok(stakers)
+}
+
+/// Computes membership points of `addr` as a sum of points of its stake in every accepted denom.
+/// Stake in a given denom counts only if it reaches the `min_bond` of that denom; if none does,
+/// `addr` is not a member. Locks expired at `block` no longer add to the points, even if not
+/// released yet.
+fn calc_points(
+    storage: &dyn Storage,
+    addr: &Addr,
+    cfg: &Config,
+    block: &BlockInfo,
+) -> StdResult<Option<u64>> {
+    let stake = STAKE.may_load(storage, addr)?.unwrap_or_default()
+        + STAKE_VESTING.may_load(storage, addr)?.unwrap_or_default();
+    // locked tokens count with their multipliers
+    let lock_bonus: Uint128 = locks()
+        .prefix(addr)
+        .range(
+            storage,
+            Some(Bound::exclusive(Expiration::now(block).as_key())),
+            None,
+            Order::Ascending,
+        )
+        .map(|item| item.map(|(_, lock)| lock.amount * (lock.multiplier - Decimal::one())))
+        .sum::<StdResult<_>>()?;
+    let mut points = if stake < cfg.min_bond {
+        None
+    } else {
+        let p = (stake + lock_bonus).u128() / cfg.tokens_per_point.u128();
+        Some(p as u64)
+    };
+
+    for extra in load_extra_stake(storage, addr)? {
+        if let Some(extra_cfg) = cfg.extra_denom(&extra.denom) {
+            if let Some(p) =
+                denom_points(extra.amount, extra_cfg.tokens_per_point, extra_cfg.min_bond)
+            {
+                points = Some(points.unwrap_or_default() + p);
+            }
+        }
+    }
+
+    Ok(points)
+}
+
+/// Sums up the stake of `addr` which is still locked.
+fn locked_amount(storage: &dyn Storage, addr: &Addr) -> StdResult<Uint128> {
+    locks()
+        .prefix(addr)
+        .range(storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(_, lock)| lock.amount))
+        .sum()
+}
+
+/// Removes the locks of `addr` which are already expired. It doesn't update membership.
+fn remove_expired_locks(
+    storage: &mut dyn Storage,
+    addr: &Addr,
+    block: &BlockInfo,
+) -> StdResult<()> {
+    let expired = locks()
+        .prefix(addr)
+        .keys(
+            storage,
+            None,
+            Some(Bound::inclusive(Expiration::now(block).as_key())),
+            Order::Ascending,
+        )
+        .collect::<StdResult<Vec<_>>>()?;
+    for unlock_at in expired {
+        locks().remove(storage, (addr, unlock_at))?;
+    }
+    Ok(())
 }
 
-fn calc_points(stake: Uint128, cfg: &Config) -> Option<u64> {
-    if stake < cfg.min_bond {
+fn denom_points(stake: Uint128, tokens_per_point: Uint128, min_bond: Uint128) -> Option<u64> {
+    if stake < min_bond {
         None
     } else {
-        let p = stake.u128() / cfg.tokens_per_point.u128();
+        let p = stake.u128() / tokens_per_point.u128();
         Some(p as u64)
     }
 }
@@ -456,9 +1174,9 @@ pub fn execute_claim<Q: CustomQuery>(
     env: Env,
     info: MessageInfo,
 ) -> Result<Response, ContractError> {
-    let (release, vesting_release) =
+    let (release, vesting_release, extra_release) =
         claims().claim_addr(deps.storage, &info.sender, &env.block, None)?;
-    if release.is_zero() && vesting_release.is_zero() {
+    if release.is_zero() && vesting_release.is_zero() && extra_release.is_empty() {
         return Err(ContractError::NothingToClaim {});
     }
 
@@ -466,16 +1184,31 @@ pub fn execute_claim<Q: CustomQuery>(
 
     let mut res = Response::new()
         .add_attribute("action", "claim")
-        .add_attribute("sender", &info.sender);
-
+        .add_attribute("sender", &info.sender)
+        .add_submessages(stake_changed_hooks(
+            deps.storage,
+            &info.sender,
+            StakeChangeKind::Claim,
+            release,
+            vesting_release,
+            &extra_release,
+        )?);
+
+    let mut liquid_release = vec![];
     if !release.is_zero() {
         let amount = coin(release.into(), config.denom.clone());
-        res = res
-            .add_attribute("liquid_tokens", amount.to_string())
-            .add_message(BankMsg::Send {
-                to_address: info.sender.clone().into(),
-                amount: vec![amount],
-            });
+        res = res.add_attribute("liquid_tokens", amount.to_string());
+        liquid_release.push(amount);
+    }
+    if !extra_release.is_empty() {
+        res = res.add_attribute("extra_tokens", coins_to_string(&extra_release));
+        liquid_release.extend(extra_release);
+    }
+    if !liquid_release.is_empty() {
+        res = res.add_message(BankMsg::Send {
+            to_address: info.sender.clone().into(),
+            amount: liquid_release,
+        });
     }
 
     if !vesting_release.is_zero() {
@@ -496,11 +1229,13 @@ pub fn execute_claim<Q: CustomQuery>(
 pub fn sudo(
     deps: DepsMut<TgradeQuery>,
     env: Env,
-    msg: TgradeSudoMsg,
+    msg: TgradeSudoMsg<StakeState>,
 ) -> Result<Response, ContractError> {
     match msg {
         TgradeSudoMsg::PrivilegeChange(PrivilegeChangeMsg::Promoted {}) => privilege_promote(deps),
         TgradeSudoMsg::EndBlock {} => end_block(deps, env),
+        TgradeSudoMsg::Export {} => export(deps.as_ref()),
+        TgradeSudoMsg::Import(state) => import(deps, env, state),
         _ => Err(ContractError::UnknownSudoMsg {}),
     }
 }
@@ -513,17 +1248,19 @@ fn privilege_promote<Q: CustomQuery>(deps: DepsMut<Q>) -> Result<Response, Contr
         let msgs = request_privileges(&[Privilege::EndBlocker]);
         res = res.add_submessages(msgs);
     }
-    let msgs = request_privileges(&[Privilege::Delegator]);
+    let msgs = request_privileges(&[Privilege::Delegator, Privilege::StateExporterImporter]);
     res = res.add_submessages(msgs);
 
     Ok(res)
 }
 
-fn end_block<Q: CustomQuery>(deps: DepsMut<Q>, env: Env) -> Result<Response, ContractError> {
+fn end_block<Q: CustomQuery>(mut deps: DepsMut<Q>, env: Env) -> Result<Response, ContractError> {
     let mut resp = Response::new();
 
     let config = CONFIG.load(deps.storage)?;
     if config.auto_return_limit > 0 {
+        let sub_msgs = release_expired_locks(deps.branch(), &env, &config)?;
+        resp = resp.add_submessages(sub_msgs);
         let sub_msgs = release_expired_claims(deps, env, config)?;
         resp = resp.add_submessages(sub_msgs);
     }
@@ -531,6 +1268,42 @@ fn end_block<Q: CustomQuery>(deps: DepsMut<Q>, env: Env) -> Result<Response, Con
     Ok(resp)
 }
 
+/// Removes up to `auto_return_limit` expired locks (of any addresses), starting from the oldest,
+/// and updates the membership of their owners, as they lose their points multipliers.
+fn release_expired_locks<Q: CustomQuery>(
+    deps: DepsMut<Q>,
+    env: &Env,
+    config: &Config,
+) -> Result<Vec<SubMsg>, ContractError> {
+    let expired = locks()
+        .idx
+        .unlock_at
+        // raw range, as the (addr, unlock_at) pk is not needed, values carry it
+        .prefix_range_raw(
+            deps.storage,
+            None,
+            Some(PrefixBound::inclusive(env.block.time.nanos())),
+            Order::Ascending,
+        )
+        .take(config.auto_return_limit as usize)
+        .map(|item| item.map(|(_, lock)| lock))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    let mut addrs = vec![];
+    for lock in expired {
+        locks().remove(deps.storage, (&lock.addr, lock.unlock_at.as_key()))?;
+        if !addrs.contains(&lock.addr) {
+            addrs.push(lock.addr);
+        }
+    }
+
+    let mut msgs = vec![];
+    for addr in addrs {
+        msgs.extend(update_membership(deps.storage, addr, config, &env.block)?);
+    }
+    Ok(msgs)
+}
+
 fn release_expired_claims<Q: CustomQuery>(
     deps: DepsMut<Q>,
     env: Env,
@@ -539,6 +1312,24 @@ fn release_expired_claims<Q: CustomQuery>(
     let release_data =
         claims().claim_expired(deps.storage, &env.block, config.auto_return_limit)?;
 
+    // Releases are grouped by the same addresses, in the same order
+    let mut hook_msgs = vec![];
+    for ((liquid, vesting), extra) in release_data
+        .liquid_releases
+        .iter()
+        .zip(&release_data.vesting_releases)
+        .zip(&release_data.extra_releases)
+    {
+        hook_msgs.extend(stake_changed_hooks(
+            deps.storage,
+            &liquid.addr,
+            StakeChangeKind::Claim,
+            liquid.amount,
+            vesting.amount,
+            &extra.amount,
+        )?);
+    }
+
     let send_msgs = release_data
         .liquid_releases
         .into_iter()
@@ -565,7 +1356,23 @@ fn release_expired_claims<Q: CustomQuery>(
         })
         .collect::<StdResult<Vec<_>>>()?;
 
-    Ok(send_msgs.into_iter().chain(undelegate_msgs).collect())
+    let extra_send_msgs = release_data
+        .extra_releases
+        .into_iter()
+        .filter(|release_info| !release_info.amount.is_empty())
+        .map(|release_info| {
+            SubMsg::new(BankMsg::Send {
+                to_address: release_info.addr.into(),
+                amount: release_info.amount,
+            })
+        });
+
+    Ok(send_msgs
+        .into_iter()
+        .chain(undelegate_msgs)
+        .chain(extra_send_msgs)
+        .chain(hook_msgs)
+        .collect())
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
@@ -594,16 +1401,53 @@ pub fn query(deps: Deps<TgradeQuery>, _env: Env, msg: QueryMsg) -> StdResult<Bin
                 start_after,
             )?,
         }),
+        ListPendingClaims { start_after, limit } => {
+            let start_after = validate_claim_cursor(deps, start_after)?;
+            to_binary(&ClaimsResponse {
+                claims: claims().query_pending_claims(deps, limit, start_after)?,
+            })
+        }
+        PendingReleasesByDay { start_after, limit } => {
+            let start_after = validate_claim_cursor(deps, start_after)?;
+            let (days, next) = claims().query_releases_by_day(deps, limit, start_after)?;
+            to_binary(&PendingReleasesResponse {
+                days,
+                next: next.map(|(release_at, addr)| PendingClaimCursor {
+                    release_at,
+                    addr: addr.into(),
+                }),
+            })
+        }
         Staked { address } => to_binary(&query_staked(deps, address)?),
+        ListLocks {
+            address,
+            start_after,
+            limit,
+        } => to_binary(&list_locks(deps, address, start_after, limit)?),
         Admin {} => to_binary(&ADMIN.query_admin(deps)?),
         Hooks {} => {
             let hooks = HOOKS.list_hooks(deps.storage)?;
             to_binary(&HooksResponse { hooks })
         }
+        StakeHooks {} => {
+            let hooks = STAKE_HOOKS.list_hooks(deps.storage)?;
+            to_binary(&HooksResponse { hooks })
+        }
         Preauths {} => {
             let preauths_hooks = PREAUTH_HOOKS.get_auth(deps.storage)?;
             to_binary(&PreauthResponse { preauths_hooks })
         }
+        PointsRecalculation {} => {
+            let recalculation = RECALCULATION.may_load(deps.storage)?;
+            to_binary(&RecalculationResponse {
+                pending: recalculation.is_some(),
+                start_after: recalculation
+                    .as_ref()
+                    .and_then(|r| r.start_after.as_ref())
+                    .map(Addr::to_string),
+                processed: recalculation.map(|r| r.processed).unwrap_or_default(),
+            })
+        }
         UnbondingPeriod {} => {
             let Config {
                 unbonding_period, ..
@@ -615,6 +1459,14 @@ pub fn query(deps: Deps<TgradeQuery>, _env: Env, msg: QueryMsg) -> StdResult<Bin
             to_binary(&SLASHERS.is_slasher(deps.storage, &addr)?)
         }
         ListSlashers {} => to_binary(&SLASHERS.list_slashers(deps.storage)?),
+        ListSlashes {
+            address,
+            start_after,
+            limit,
+        } => to_binary(&list_slashes(deps, address, start_after, limit)?),
+        ListAllSlashes { start_after, limit } => {
+            to_binary(&list_all_slashes(deps, start_after, limit)?)
+        }
     }
 }
 
@@ -623,6 +1475,15 @@ fn query_total_points<Q: CustomQuery>(deps: Deps<Q>) -> StdResult<TotalPointsRes
     Ok(TotalPointsResponse { points })
 }
 
+fn validate_claim_cursor<Q: CustomQuery>(
+    deps: Deps<Q>,
+    cursor: Option<PendingClaimCursor>,
+) -> StdResult<Option<(Expiration, Addr)>> {
+    cursor
+        .map(|cursor| Ok((cursor.release_at, deps.api.addr_validate(&cursor.addr)?)))
+        .transpose()
+}
+
 pub fn query_staked<Q: CustomQuery>(deps: Deps<Q>, addr: String) -> StdResult<StakedResponse> {
     let addr = deps.api.addr_validate(&addr)?;
     let stake = STAKE.may_load(deps.storage, &addr)?.unwrap_or_default();
@@ -630,13 +1491,74 @@ pub fn query_staked<Q: CustomQuery>(deps: Deps<Q>, addr: String) -> StdResult<St
         .may_load(deps.storage, &addr)?
         .unwrap_or_default();
     let config = CONFIG.load(deps.storage)?;
+    let extra = load_extra_stake(deps.storage, &addr)?;
 
     Ok(StakedResponse {
         liquid: coin(stake.u128(), config.denom.clone()),
         vesting: coin(vesting.u128(), config.denom),
+        extra,
     })
 }
 
+fn list_locks<Q: CustomQuery>(
+    deps: Deps<Q>,
+    addr: String,
+    start_after: Option<Expiration>,
+    limit: Option<u32>,
+) -> StdResult<LocksResponse> {
+    let addr = deps.api.addr_validate(&addr)?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(|s| Bound::exclusive(s.as_key()));
+
+    let locks = locks()
+        .prefix(&addr)
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(_, lock)| lock))
+        .collect::<StdResult<_>>()?;
+
+    Ok(LocksResponse { locks })
+}
+
+fn list_slashes<Q: CustomQuery>(
+    deps: Deps<Q>,
+    addr: String,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<SlashesResponse> {
+    let addr = deps.api.addr_validate(&addr)?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+
+    let slashes = slashes()
+        .idx
+        .addr
+        .prefix(addr)
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(_, slash)| slash))
+        .collect::<StdResult<_>>()?;
+
+    Ok(SlashesResponse { slashes })
+}
+
+fn list_all_slashes<Q: CustomQuery>(
+    deps: Deps<Q>,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<SlashesResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+
+    let slashes = slashes()
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(_, slash)| slash))
+        .collect::<StdResult<_>>()?;
+
+    Ok(SlashesResponse { slashes })
+}
+
 fn query_member<Q: CustomQuery>(
     deps: Deps<Q>,
     addr: String,
@@ -731,7 +1653,8 @@ pub fn migrate(
 ) -> Result<Response, ContractError> {
     ensure_from_older_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
 
-    CONFIG.update::<_, StdError>(deps.storage, |mut cfg| {
+    let api = deps.api;
+    CONFIG.update::<_, ContractError>(deps.storage, |mut cfg| {
         if let Some(tokens_per_point) = msg.tokens_per_point {
             let tokens_per_point = if tokens_per_point == Uint128::zero() {
                 Uint128::new(1)
@@ -754,9 +1677,33 @@ pub fn migrate(
         if let Some(auto_return_limit) = msg.auto_return_limit {
             cfg.auto_return_limit = auto_return_limit;
         }
+        if let Some(extra_denoms) = msg.extra_denoms.clone() {
+            // denoms can be added or reconfigured, but never removed as they may still be staked
+            if let Some(removed) = cfg
+                .extra_denoms
+                .iter()
+                .find(|old| extra_denoms.iter().all(|new| new.denom != old.denom))
+            {
+                return Err(ContractError::DenomRemoved(removed.denom.clone()));
+            }
+            cfg.extra_denoms = validate_extra_denoms(&cfg.denom, extra_denoms)?;
+        }
+        if let Some(instant_unbond) = msg.instant_unbond.clone() {
+            if msg.disable_instant_unbond {
+                return Err(ContractError::InstantUnbondConflict {});
+            }
+            cfg.instant_unbond = Some(instant_unbond.validate(api)?);
+        } else if msg.disable_instant_unbond {
+            cfg.instant_unbond = None;
+        }
         Ok(cfg)
     })?;
 
+    // points of existing stakers are outdated, start recomputing them from scratch
+    if msg.tokens_per_point.is_some() || msg.min_bond.is_some() || msg.extra_denoms.is_some() {
+        RECALCULATION.save(deps.storage, &Recalculation::default())?;
+    }
+
     if let Some(undelegations) = msg.undelegations {
         let msgs = process_pending_undelegations(deps.as_ref(), &undelegations)?;
         Ok(Response::new().add_messages(msgs))
@@ -767,7 +1714,7 @@ pub fn migrate(
 
 #[cfg(test)]
 mod tests {
-    use crate::claim::Claim;
+    use crate::claim::{Claim, DailyRelease};
     use cosmwasm_std::testing::{mock_env, mock_info};
     use cosmwasm_std::{
         from_slice, CosmosMsg, OverflowError, OverflowOperation, StdError, Storage,
@@ -776,6 +1723,8 @@ mod tests {
     use tg_utils::{Expiration, HookError, PreauthError, SlasherError};
 
     use crate::error::ContractError;
+    use crate::msg::UnvalidatedInstantUnbond;
+    use crate::state::StakeInfo;
 
     use super::*;
     use tg_bindings_test::mock_deps_tgrade;
@@ -809,6 +1758,9 @@ mod tests {
             preauths_hooks: 1,
             preauths_slashing: 1,
             auto_return_limit,
+            extra_denoms: vec![],
+            lock_tiers: vec![],
+            instant_unbond: None,
         };
         let info = mock_info("creator", &[]);
         instantiate(deps, mock_env(), info, msg).unwrap();
@@ -858,7 +1810,10 @@ mod tests {
                 } else {
                     None
                 };
-                let msg = ExecuteMsg::Bond { vesting_tokens };
+                let msg = ExecuteMsg::Bond {
+                    vesting_tokens,
+                    lock_duration: None,
+                };
                 let info = mock_info(addr, &coins(stake.0, DENOM));
                 execute(deps.branch(), env.clone(), info, msg).unwrap();
             }
@@ -910,6 +1865,9 @@ mod tests {
                 min_bond: MIN_BOND,
                 unbonding_period: Duration::new(UNBONDING_DURATION),
                 auto_return_limit: 0,
+                extra_denoms: vec![],
+                lock_tiers: vec![],
+                instant_unbond: None,
             }
         );
 
@@ -1555,99 +2513,353 @@ mod tests {
     }
 
     #[test]
-    fn add_remove_hooks() {
-        // add will over-write and remove have no effect
+    fn pending_claims_across_addresses() {
         let mut deps = mock_deps_tgrade();
         default_instantiate(deps.as_mut());
 
-        let hooks = HOOKS.list_hooks(&deps.storage).unwrap();
-        assert!(hooks.is_empty());
-
-        let contract1 = String::from("hook1");
-        let contract2 = String::from("hook2");
+        bond_liquid(deps.as_mut(), 12_000, 7_500, 4_000, 1);
+        unbond(deps.as_mut(), 1_000, 2_000, 0, 2, 0);
+        unbond(deps.as_mut(), 500, 0, 300, 3, 3_600);
+        unbond(deps.as_mut(), 0, 700, 0, 4, 2 * 86_400);
 
-        let add_msg = ExecuteMsg::AddHook {
-            addr: contract1.clone(),
+        let release_at = |time_delta: u64| {
+            Duration::new(UNBONDING_DURATION + time_delta).after(&mock_env().block)
         };
-
-        // anyone can add the first one, until preauth is consume
-        assert_eq!(1, PREAUTH_HOOKS.get_auth(&deps.storage).unwrap());
-        let user_info = mock_info(USER1, &[]);
-        let _ = execute(deps.as_mut(), mock_env(), user_info, add_msg.clone()).unwrap();
-        let hooks = HOOKS.list_hooks(&deps.storage).unwrap();
-        assert_eq!(hooks, vec![contract1.clone()]);
-
-        // non-admin cannot add hook without preauth
-        assert_eq!(0, PREAUTH_HOOKS.get_auth(&deps.storage).unwrap());
-        let user_info = mock_info(USER1, &[]);
-        let err = execute(
-            deps.as_mut(),
-            mock_env(),
-            user_info.clone(),
-            add_msg.clone(),
-        )
-        .unwrap_err();
-        assert_eq!(err, PreauthError::NoPreauth {}.into());
-
-        // cannot remove a non-registered contract
-        let admin_info = mock_info(INIT_ADMIN, &[]);
-        let remove_msg = ExecuteMsg::RemoveHook {
-            addr: contract2.clone(),
+        let first = release_at(0);
+        let second = release_at(3_600);
+        let third = release_at(2 * 86_400);
+        let claim = |addr: &str, amount: u128, release_at, height_delta: u64| {
+            Claim::new(
+                Addr::unchecked(addr),
+                amount,
+                0,
+                release_at,
+                mock_env().block.height + height_delta,
+            )
         };
-        let err = execute(deps.as_mut(), mock_env(), admin_info.clone(), remove_msg).unwrap_err();
-        assert_eq!(err, HookError::HookNotRegistered {}.into());
 
-        // admin can second contract, and it appears in the query
-        let add_msg2 = ExecuteMsg::AddHook {
-            addr: contract2.clone(),
+        let list_pending = |deps: Deps<TgradeQuery>, start_after, limit| {
+            let msg = QueryMsg::ListPendingClaims { start_after, limit };
+            let res: ClaimsResponse = from_slice(&query(deps, mock_env(), msg).unwrap()).unwrap();
+            res.claims
         };
-        execute(deps.as_mut(), mock_env(), admin_info.clone(), add_msg2).unwrap();
-        let hooks = HOOKS.list_hooks(&deps.storage).unwrap();
-        assert_eq!(hooks, vec![contract1.clone(), contract2.clone()]);
 
-        // cannot re-add an existing contract
-        let err = execute(deps.as_mut(), mock_env(), admin_info.clone(), add_msg).unwrap_err();
-        assert_eq!(err, HookError::HookAlreadyRegistered {}.into());
+        let cursor = |release_at, addr: &str| {
+            Some(PendingClaimCursor {
+                release_at,
+                addr: addr.to_owned(),
+            })
+        };
 
-        // non-admin cannot remove
-        let remove_msg = ExecuteMsg::RemoveHook { addr: contract1 };
-        let err = execute(deps.as_mut(), mock_env(), user_info, remove_msg.clone()).unwrap_err();
+        // claims released at the same time are ordered by address
         assert_eq!(
-            err,
-            ContractError::Unauthorized(
-                "Hook address is not same as sender's and sender is not an admin".to_owned()
-            )
+            list_pending(deps.as_ref(), None, Some(1)),
+            vec![claim(USER1, 1_000, first, 2)]
+        );
+        assert_eq!(
+            list_pending(deps.as_ref(), cursor(first, USER1), Some(2)),
+            vec![claim(USER2, 2_000, first, 2), claim(USER1, 500, second, 3)]
         );
+        assert_eq!(
+            list_pending(deps.as_ref(), cursor(second, USER1), None),
+            vec![claim(USER3, 300, second, 3), claim(USER2, 700, third, 4)]
+        );
+        assert_eq!(
+            list_pending(deps.as_ref(), cursor(third, USER2), None),
+            vec![]
+        );
+        assert_eq!(list_pending(deps.as_ref(), None, None).len(), 5);
 
-        // remove the original
-        execute(deps.as_mut(), mock_env(), admin_info, remove_msg).unwrap();
-        let hooks = HOOKS.list_hooks(&deps.storage).unwrap();
-        assert_eq!(hooks, vec![contract2.clone()]);
+        let releases_by_day = |deps: Deps<TgradeQuery>, start_after, limit| {
+            let msg = QueryMsg::PendingReleasesByDay { start_after, limit };
+            let res: PendingReleasesResponse =
+                from_slice(&query(deps, mock_env(), msg).unwrap()).unwrap();
+            (res.days, res.next)
+        };
 
-        // contract can self-remove
-        let contract_info = mock_info(&contract2, &[]);
-        let remove_msg2 = ExecuteMsg::RemoveHook { addr: contract2 };
-        execute(deps.as_mut(), mock_env(), contract_info, remove_msg2).unwrap();
-        let hooks = HOOKS.list_hooks(&deps.storage).unwrap();
-        assert_eq!(hooks, Vec::<String>::new());
+        let day = first.as_key() / 86_400_000_000_000;
+        let release = |day, claims, amount: u128| DailyRelease {
+            day,
+            claims,
+            amount: Uint128::new(amount),
+            vesting_amount: Uint128::zero(),
+            extra_amounts: vec![],
+        };
+        assert_eq!(
+            releases_by_day(deps.as_ref(), None, None),
+            (vec![release(day, 4, 3_800), release(day + 2, 1, 700)], None)
+        );
+        // limit caps the claims aggregated, so the first day is split between pages
+        assert_eq!(
+            releases_by_day(deps.as_ref(), None, Some(3)),
+            (vec![release(day, 3, 3_500)], cursor(second, USER1))
+        );
+        assert_eq!(
+            releases_by_day(deps.as_ref(), cursor(second, USER1), Some(1)),
+            (vec![release(day, 1, 300)], cursor(second, USER3))
+        );
+        assert_eq!(
+            releases_by_day(deps.as_ref(), cursor(second, USER3), Some(1)),
+            (vec![release(day + 2, 1, 700)], None)
+        );
+        assert_eq!(
+            releases_by_day(deps.as_ref(), cursor(third, USER2), None),
+            (vec![], None)
+        );
     }
 
-    mod slash {
-        use super::*;
+    fn rebond(deps: DepsMut<TgradeQuery>, addr: &str, amount: u128, time_delta: u64) -> Response {
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(time_delta);
+        let msg = ExecuteMsg::Rebond {
+            tokens: coin(amount, DENOM),
+        };
+        execute(deps, env, mock_info(addr, &[]), msg).unwrap()
+    }
 
-        fn query_is_slasher(deps: Deps<TgradeQuery>, env: Env, addr: String) -> StdResult<bool> {
-            let msg = QueryMsg::IsSlasher { addr };
-            let raw = query(deps, env, msg)?;
-            let is_slasher: bool = from_slice(&raw)?;
-            Ok(is_slasher)
-        }
+    #[test]
+    fn rebond_validations() {
+        let mut deps = mock_deps_tgrade();
+        default_instantiate(deps.as_mut());
 
-        fn query_list_slashers(deps: Deps<TgradeQuery>, env: Env) -> StdResult<Vec<String>> {
-            let msg = QueryMsg::ListSlashers {};
-            let raw = query(deps, env, msg)?;
-            let slashers: Vec<String> = from_slice(&raw)?;
-            Ok(slashers)
-        }
+        bond(deps.as_mut(), (10_000, 0), (0, 0), (0, 0), 1);
+        unbond(deps.as_mut(), 3_000, 0, 0, 2, 0);
+        let claims = get_claims(deps.as_ref(), Addr::unchecked(USER1), None, None);
+
+        // Zero amount rebonds are rejected
+        let msg = ExecuteMsg::Rebond {
+            tokens: coin(0, DENOM),
+        };
+        let err = execute(deps.as_mut(), mock_env(), mock_info(USER1, &[]), msg).unwrap_err();
+        assert_eq!(ContractError::ZeroAmount {}, err);
+
+        // Invalid denom rebonds are rejected
+        let msg = ExecuteMsg::Rebond {
+            tokens: coin(1_000, "INV"),
+        };
+        let err = execute(deps.as_mut(), mock_env(), mock_info(USER1, &[]), msg).unwrap_err();
+        assert_eq!(ContractError::InvalidDenom {}, err);
+
+        // Cannot rebond more than is unbonding
+        let msg = ExecuteMsg::Rebond {
+            tokens: coin(3_001, DENOM),
+        };
+        let err = execute(deps.as_mut(), mock_env(), mock_info(USER1, &[]), msg).unwrap_err();
+        assert_eq!(
+            ContractError::InsufficientUnbonding(Uint128::new(3_001)),
+            err
+        );
+
+        // Matured claims cannot be rebonded
+        let msg = ExecuteMsg::Rebond {
+            tokens: coin(1_000, DENOM),
+        };
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(UNBONDING_DURATION);
+        let err = execute(deps.as_mut(), env, mock_info(USER1, &[]), msg).unwrap_err();
+        assert_eq!(
+            ContractError::InsufficientUnbonding(Uint128::new(1_000)),
+            err
+        );
+
+        // Nothing changed on failures
+        assert_stake_liquid(deps.as_ref(), 7_000, 0, 0);
+        assert_users(deps.as_ref(), Some(7), None, None, None);
+        assert_eq!(
+            get_claims(deps.as_ref(), Addr::unchecked(USER1), None, None),
+            claims
+        );
+    }
+
+    #[test]
+    fn rebond_restores_stake_from_newest_claims() {
+        let mut deps = mock_deps_tgrade();
+        default_instantiate(deps.as_mut());
+
+        // 4_000 liquid and 8_000 vesting
+        bond(deps.as_mut(), (4_000, 8_000), (6_000, 0), (0, 0), 1);
+        // first claim: 3_000 liquid
+        unbond(deps.as_mut(), 3_000, 0, 0, 2, 0);
+        // second claim: 1_000 liquid and 2_000 vesting
+        unbond(deps.as_mut(), 3_000, 0, 0, 3, 10);
+        assert_stake_liquid(deps.as_ref(), 0, 6_000, 0);
+        assert_stake_vesting(deps.as_ref(), 6_000, 0, 0);
+        assert_users(deps.as_ref(), Some(6), Some(6), None, None);
+
+        let mut env = mock_env();
+        env.block.height += 2;
+        let expires1 = Duration::new(UNBONDING_DURATION).after(&env.block);
+        env.block.height += 1;
+        let expires2 = Duration::new(UNBONDING_DURATION + 10).after(&env.block);
+
+        // rebonding consumes the newest claim first, liquid part first
+        let res = rebond(deps.as_mut(), USER1, 1_500, 20);
+        assert!(res.messages.is_empty());
+        assert_stake_liquid(deps.as_ref(), 1_000, 6_000, 0);
+        assert_stake_vesting(deps.as_ref(), 6_500, 0, 0);
+        assert_users(deps.as_ref(), Some(7), Some(6), None, None);
+        assert_eq!(
+            get_claims(deps.as_ref(), Addr::unchecked(USER1), None, None),
+            vec![
+                Claim::new(
+                    Addr::unchecked(USER1),
+                    3_000,
+                    0,
+                    expires1,
+                    env.block.height - 1
+                ),
+                Claim::new(Addr::unchecked(USER1), 0, 1_500, expires2, env.block.height),
+            ]
+        );
+
+        // rebonding more spans over the older claim as well
+        rebond(deps.as_mut(), USER1, 2_500, 20);
+        assert_stake_liquid(deps.as_ref(), 2_000, 6_000, 0);
+        assert_stake_vesting(deps.as_ref(), 8_000, 0, 0);
+        assert_users(deps.as_ref(), Some(10), Some(6), None, None);
+        assert_eq!(
+            get_claims(deps.as_ref(), Addr::unchecked(USER1), None, None),
+            vec![Claim::new(
+                Addr::unchecked(USER1),
+                2_000,
+                0,
+                expires1,
+                env.block.height - 1
+            )]
+        );
+
+        // USER2 has nothing to rebond
+        let msg = ExecuteMsg::Rebond {
+            tokens: coin(1, DENOM),
+        };
+        let err = execute(deps.as_mut(), mock_env(), mock_info(USER2, &[]), msg).unwrap_err();
+        assert_eq!(ContractError::InsufficientUnbonding(Uint128::new(1)), err);
+    }
+
+    #[test]
+    fn rebond_fires_hooks() {
+        let mut deps = mock_deps_tgrade();
+        default_instantiate(deps.as_mut());
+
+        let hook = String::from("hook1");
+        let add_msg = ExecuteMsg::AddHook { addr: hook.clone() };
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(INIT_ADMIN, &[]),
+            add_msg,
+        )
+        .unwrap();
+
+        bond(deps.as_mut(), (13_800, 0), (0, 0), (0, 0), 1);
+        unbond(deps.as_mut(), 10_000, 0, 0, 2, 0);
+        assert_users(deps.as_ref(), None, None, None, None);
+
+        let res = rebond(deps.as_mut(), USER1, 7_000, 0);
+        assert_users(deps.as_ref(), Some(10), None, None, None);
+
+        let diff = MemberDiff::new(USER1, None, Some(10));
+        let hook_msg = MemberChangedHookMsg::one(diff)
+            .into_cosmos_msg(hook)
+            .map(SubMsg::new)
+            .unwrap();
+        assert_eq!(res.messages, vec![hook_msg]);
+    }
+
+    #[test]
+    fn add_remove_hooks() {
+        // add will over-write and remove have no effect
+        let mut deps = mock_deps_tgrade();
+        default_instantiate(deps.as_mut());
+
+        let hooks = HOOKS.list_hooks(&deps.storage).unwrap();
+        assert!(hooks.is_empty());
+
+        let contract1 = String::from("hook1");
+        let contract2 = String::from("hook2");
+
+        let add_msg = ExecuteMsg::AddHook {
+            addr: contract1.clone(),
+        };
+
+        // anyone can add the first one, until preauth is consume
+        assert_eq!(1, PREAUTH_HOOKS.get_auth(&deps.storage).unwrap());
+        let user_info = mock_info(USER1, &[]);
+        let _ = execute(deps.as_mut(), mock_env(), user_info, add_msg.clone()).unwrap();
+        let hooks = HOOKS.list_hooks(&deps.storage).unwrap();
+        assert_eq!(hooks, vec![contract1.clone()]);
+
+        // non-admin cannot add hook without preauth
+        assert_eq!(0, PREAUTH_HOOKS.get_auth(&deps.storage).unwrap());
+        let user_info = mock_info(USER1, &[]);
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            user_info.clone(),
+            add_msg.clone(),
+        )
+        .unwrap_err();
+        assert_eq!(err, PreauthError::NoPreauth {}.into());
+
+        // cannot remove a non-registered contract
+        let admin_info = mock_info(INIT_ADMIN, &[]);
+        let remove_msg = ExecuteMsg::RemoveHook {
+            addr: contract2.clone(),
+        };
+        let err = execute(deps.as_mut(), mock_env(), admin_info.clone(), remove_msg).unwrap_err();
+        assert_eq!(err, HookError::HookNotRegistered {}.into());
+
+        // admin can second contract, and it appears in the query
+        let add_msg2 = ExecuteMsg::AddHook {
+            addr: contract2.clone(),
+        };
+        execute(deps.as_mut(), mock_env(), admin_info.clone(), add_msg2).unwrap();
+        let hooks = HOOKS.list_hooks(&deps.storage).unwrap();
+        assert_eq!(hooks, vec![contract1.clone(), contract2.clone()]);
+
+        // cannot re-add an existing contract
+        let err = execute(deps.as_mut(), mock_env(), admin_info.clone(), add_msg).unwrap_err();
+        assert_eq!(err, HookError::HookAlreadyRegistered {}.into());
+
+        // non-admin cannot remove
+        let remove_msg = ExecuteMsg::RemoveHook { addr: contract1 };
+        let err = execute(deps.as_mut(), mock_env(), user_info, remove_msg.clone()).unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::Unauthorized(
+                "Hook address is not same as sender's and sender is not an admin".to_owned()
+            )
+        );
+
+        // remove the original
+        execute(deps.as_mut(), mock_env(), admin_info, remove_msg).unwrap();
+        let hooks = HOOKS.list_hooks(&deps.storage).unwrap();
+        assert_eq!(hooks, vec![contract2.clone()]);
+
+        // contract can self-remove
+        let contract_info = mock_info(&contract2, &[]);
+        let remove_msg2 = ExecuteMsg::RemoveHook { addr: contract2 };
+        execute(deps.as_mut(), mock_env(), contract_info, remove_msg2).unwrap();
+        let hooks = HOOKS.list_hooks(&deps.storage).unwrap();
+        assert_eq!(hooks, Vec::<String>::new());
+    }
+
+    mod slash {
+        use super::*;
+
+        fn query_is_slasher(deps: Deps<TgradeQuery>, env: Env, addr: String) -> StdResult<bool> {
+            let msg = QueryMsg::IsSlasher { addr };
+            let raw = query(deps, env, msg)?;
+            let is_slasher: bool = from_slice(&raw)?;
+            Ok(is_slasher)
+        }
+
+        fn query_list_slashers(deps: Deps<TgradeQuery>, env: Env) -> StdResult<Vec<String>> {
+            let msg = QueryMsg::ListSlashers {};
+            let raw = query(deps, env, msg)?;
+            let slashers: Vec<String> = from_slice(&raw)?;
+            Ok(slashers)
+        }
 
         fn add_slasher(deps: DepsMut<TgradeQuery>) -> String {
             let slasher = String::from("slasher");
@@ -1958,6 +3170,89 @@ mod tests {
             assert_burned(res, &coins(2_400, &cfg.denom), &coins(200, &cfg.denom));
         }
 
+        fn list_slashes(
+            deps: Deps<TgradeQuery>,
+            addr: Option<&str>,
+            start_after: Option<u64>,
+            limit: Option<u32>,
+        ) -> Vec<SlashRecord> {
+            let msg = match addr {
+                Some(addr) => QueryMsg::ListSlashes {
+                    address: addr.to_owned(),
+                    start_after,
+                    limit,
+                },
+                None => QueryMsg::ListAllSlashes { start_after, limit },
+            };
+            let raw = query(deps, mock_env(), msg).unwrap();
+            let res: SlashesResponse = from_slice(&raw).unwrap();
+            res.slashes
+        }
+
+        #[test]
+        fn slashing_history_is_recorded() {
+            let mut deps = mock_deps_tgrade();
+            default_instantiate(deps.as_mut());
+            let slasher = add_slasher(deps.as_mut());
+
+            bond_liquid(deps.as_mut(), 12_000, 7_500, 0, 1);
+            unbond(deps.as_mut(), 2_000, 0, 0, 2, 0);
+
+            assert!(list_slashes(deps.as_ref(), None, None, None).is_empty());
+
+            slash(deps.as_mut(), &slasher, USER1, Decimal::percent(20)).unwrap();
+            slash(deps.as_mut(), &slasher, USER2, Decimal::percent(10)).unwrap();
+            let res = slash(deps.as_mut(), &slasher, USER1, Decimal::percent(50)).unwrap();
+            assert!(res
+                .attributes
+                .iter()
+                .any(|attr| attr.key == "slash_id" && attr.value == "3"));
+
+            let env = mock_env();
+            let record = |id, addr: &str, portion, liquid: u128, claims_liquid: u128| SlashRecord {
+                id,
+                addr: Addr::unchecked(addr),
+                slasher: Addr::unchecked(&slasher),
+                height: env.block.height,
+                time: env.block.time,
+                portion,
+                liquid: liquid.into(),
+                vesting: Uint128::zero(),
+                extra: vec![],
+                claims_liquid: claims_liquid.into(),
+                claims_vesting: Uint128::zero(),
+                claims_extra: vec![],
+            };
+            let first = record(1, USER1, Decimal::percent(20), 2_000, 400);
+            let second = record(2, USER2, Decimal::percent(10), 750, 0);
+            let third = record(3, USER1, Decimal::percent(50), 4_000, 800);
+
+            // per member history
+            assert_eq!(
+                list_slashes(deps.as_ref(), Some(USER1), None, None),
+                vec![first.clone(), third.clone()]
+            );
+            assert_eq!(
+                list_slashes(deps.as_ref(), Some(USER1), Some(1), None),
+                vec![third.clone()]
+            );
+            assert_eq!(
+                list_slashes(deps.as_ref(), Some(USER2), None, None),
+                vec![second.clone()]
+            );
+            assert!(list_slashes(deps.as_ref(), Some(USER3), None, None).is_empty());
+
+            // whole history, paginated
+            assert_eq!(
+                list_slashes(deps.as_ref(), None, None, Some(2)),
+                vec![first, second]
+            );
+            assert_eq!(
+                list_slashes(deps.as_ref(), None, Some(2), Some(2)),
+                vec![third]
+            );
+        }
+
         #[test]
         fn random_user_cannot_slash() {
             let mut deps = mock_deps_tgrade();
@@ -2028,6 +3323,82 @@ mod tests {
         }
     }
 
+    #[test]
+    fn stake_hooks_fire() {
+        let mut deps = mock_deps_tgrade();
+        default_instantiate(deps.as_mut());
+
+        let hook = String::from("stake_hook");
+        let admin_info = mock_info(INIT_ADMIN, &[]);
+        let add_msg = ExecuteMsg::AddStakeHook { addr: hook.clone() };
+        execute(deps.as_mut(), mock_env(), admin_info.clone(), add_msg).unwrap();
+        let raw = query(deps.as_ref(), mock_env(), QueryMsg::StakeHooks {}).unwrap();
+        let hooks: HooksResponse = from_slice(&raw).unwrap();
+        assert_eq!(hooks.hooks, vec![hook.clone()]);
+        // membership hooks are separate
+        assert!(HOOKS.list_hooks(&deps.storage).unwrap().is_empty());
+
+        let stake_changed = |kind, liquid: u128, vesting: u128| {
+            SubMsg::new(
+                StakeChangedHookMsg {
+                    addr: USER1.to_owned(),
+                    kind,
+                    liquid_delta: liquid.into(),
+                    vesting_delta: vesting.into(),
+                    extra_delta: vec![],
+                }
+                .into_cosmos_msg(&hook)
+                .unwrap(),
+            )
+        };
+
+        // bond both liquid and vesting tokens
+        let msg = ExecuteMsg::Bond {
+            vesting_tokens: Some(coin(3_000, DENOM)),
+            lock_duration: None,
+        };
+        let info = mock_info(USER1, &coins(12_000, DENOM));
+        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert_eq!(
+            res.messages.last().unwrap(),
+            &stake_changed(StakeChangeKind::Bond, 12_000, 3_000)
+        );
+
+        // unbonding takes liquid tokens first
+        let msg = ExecuteMsg::Unbond {
+            tokens: coin(13_000, DENOM),
+        };
+        let res = execute(deps.as_mut(), mock_env(), mock_info(USER1, &[]), msg).unwrap();
+        assert_eq!(
+            res.messages,
+            vec![stake_changed(StakeChangeKind::Unbond, 12_000, 1_000)]
+        );
+
+        // claiming reports the released tokens
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(UNBONDING_DURATION);
+        let res = execute(
+            deps.as_mut(),
+            env,
+            mock_info(USER1, &[]),
+            ExecuteMsg::Claim {},
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages[0],
+            stake_changed(StakeChangeKind::Claim, 12_000, 1_000)
+        );
+
+        // no more messages once the hook is removed
+        let remove_msg = ExecuteMsg::RemoveStakeHook { addr: hook };
+        execute(deps.as_mut(), mock_env(), admin_info, remove_msg).unwrap();
+        let msg = ExecuteMsg::Unbond {
+            tokens: coin(1_000, DENOM),
+        };
+        let res = execute(deps.as_mut(), mock_env(), mock_info(USER1, &[]), msg).unwrap();
+        assert!(res.messages.is_empty());
+    }
+
     #[test]
     fn hooks_fire() {
         let mut deps = mock_deps_tgrade();
@@ -2060,6 +3431,7 @@ mod tests {
             info,
             ExecuteMsg::Bond {
                 vesting_tokens: None,
+                lock_duration: None,
             },
         )
         .unwrap();
@@ -2105,35 +3477,107 @@ mod tests {
     }
 
     #[test]
-    fn only_bond_valid_coins() {
+    fn bond_for_credits_beneficiary() {
         let mut deps = mock_deps_tgrade();
         default_instantiate(deps.as_mut());
+        let faucet = "faucet";
 
-        // cannot bond with 0 coins
-        let info = mock_info(USER1, &[]);
-        let err = execute(
-            deps.as_mut(),
-            mock_env(),
-            info,
-            ExecuteMsg::Bond {
-                vesting_tokens: None,
-            },
-        )
-        .unwrap_err();
-        assert_eq!(err, ContractError::NoFunds {});
+        let msg = ExecuteMsg::BondFor {
+            beneficiary: USER2.to_owned(),
+            vesting_tokens: None,
+        };
+        let info = mock_info(faucet, &coins(7_500, DENOM));
+        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert!(res.messages.is_empty());
 
-        // cannot bond with incorrect denom
-        let info = mock_info(USER1, &[coin(500, "FOO")]);
-        let err = execute(
-            deps.as_mut(),
-            mock_env(),
-            info,
-            ExecuteMsg::Bond {
-                vesting_tokens: None,
-            },
-        )
-        .unwrap_err();
-        assert_eq!(err, ContractError::MissingDenom(DENOM.to_string()));
+        // stake and points belong to the beneficiary
+        assert_stake_liquid(deps.as_ref(), 0, 7_500, 0);
+        assert_users(deps.as_ref(), None, Some(7), None, None);
+        let stake = query_staked(deps.as_ref(), faucet.to_owned()).unwrap();
+        assert_eq!(stake.liquid, coin(0, DENOM));
+
+        // the bonding contract cannot unbond it
+        let msg = ExecuteMsg::Unbond {
+            tokens: coin(2_500, DENOM),
+        };
+        execute(deps.as_mut(), mock_env(), mock_info(faucet, &[]), msg).unwrap_err();
+
+        // but the beneficiary can, and gets the claim
+        unbond(deps.as_mut(), 0, 2_500, 0, 1, 0);
+        assert_users(deps.as_ref(), None, Some(5), None, None);
+        let claims = get_claims(deps.as_ref(), Addr::unchecked(USER2), None, None);
+        assert_eq!(claims.len(), 1);
+        assert_eq!(claims[0].amount, Uint128::new(2_500));
+        assert_eq!(
+            get_claims(deps.as_ref(), Addr::unchecked(faucet), None, None),
+            vec![]
+        );
+    }
+
+    #[test]
+    fn bond_for_vesting_only_for_sender() {
+        let mut deps = mock_deps_tgrade();
+        default_instantiate(deps.as_mut());
+
+        let msg = ExecuteMsg::BondFor {
+            beneficiary: USER2.to_owned(),
+            vesting_tokens: Some(coin(6_000, DENOM)),
+        };
+        let info = mock_info(USER1, &coins(1_000, DENOM));
+        let err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        assert_eq!(ContractError::VestingBondFor {}, err);
+
+        // bonding vesting tokens for oneself works as a regular bond
+        let msg = ExecuteMsg::BondFor {
+            beneficiary: USER1.to_owned(),
+            vesting_tokens: Some(coin(6_000, DENOM)),
+        };
+        let info = mock_info(USER1, &coins(1_000, DENOM));
+        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert_eq!(
+            res.messages,
+            vec![SubMsg::new(TgradeMsg::Delegate {
+                funds: coin(6_000, DENOM),
+                staker: USER1.to_owned(),
+            })]
+        );
+        assert_stake_liquid(deps.as_ref(), 1_000, 0, 0);
+        assert_stake_vesting(deps.as_ref(), 6_000, 0, 0);
+        assert_users(deps.as_ref(), Some(7), None, None, None);
+    }
+
+    #[test]
+    fn only_bond_valid_coins() {
+        let mut deps = mock_deps_tgrade();
+        default_instantiate(deps.as_mut());
+
+        // cannot bond with 0 coins
+        let info = mock_info(USER1, &[]);
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            ExecuteMsg::Bond {
+                vesting_tokens: None,
+                lock_duration: None,
+            },
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::NoFunds {});
+
+        // cannot bond with incorrect denom
+        let info = mock_info(USER1, &[coin(500, "FOO")]);
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            ExecuteMsg::Bond {
+                vesting_tokens: None,
+                lock_duration: None,
+            },
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::MissingDenom(DENOM.to_string()));
 
         // cannot bond with 2 coins (even if one is correct)
         let info = mock_info(USER1, &[coin(1234, DENOM), coin(5000, "BAR")]);
@@ -2143,6 +3587,7 @@ mod tests {
             info,
             ExecuteMsg::Bond {
                 vesting_tokens: None,
+                lock_duration: None,
             },
         )
         .unwrap_err();
@@ -2157,6 +3602,7 @@ mod tests {
             info,
             ExecuteMsg::Bond {
                 vesting_tokens: None,
+                lock_duration: None,
             },
         )
         .unwrap();
@@ -2224,6 +3670,7 @@ mod tests {
         let mut env = mock_env();
         let msg = ExecuteMsg::Bond {
             vesting_tokens: None,
+            lock_duration: None,
         };
         let info = mock_info(USER1, &coins(500, DENOM));
         execute(deps.as_mut(), env.clone(), info, msg).unwrap();
@@ -2856,4 +4303,1023 @@ mod tests {
             assert_eq!(ContractError::InvalidDenom {}, err);
         }
     }
+
+    mod extra_denoms {
+        use super::*;
+
+        const LP: &str = "lp";
+        const IBC: &str = "ibc";
+
+        fn do_instantiate(deps: DepsMut<TgradeQuery>, auto_return_limit: u64) {
+            let msg = InstantiateMsg {
+                denom: DENOM.to_owned(),
+                tokens_per_point: TOKENS_PER_POINT,
+                min_bond: MIN_BOND,
+                unbonding_period: UNBONDING_DURATION,
+                admin: Some(INIT_ADMIN.into()),
+                preauths_hooks: 0,
+                preauths_slashing: 1,
+                auto_return_limit,
+                extra_denoms: vec![
+                    StakeDenom {
+                        denom: LP.to_owned(),
+                        tokens_per_point: Uint128::new(100),
+                        min_bond: Uint128::new(1_000),
+                    },
+                    StakeDenom {
+                        denom: IBC.to_owned(),
+                        tokens_per_point: Uint128::new(10),
+                        min_bond: Uint128::zero(),
+                    },
+                ],
+                lock_tiers: vec![],
+                instant_unbond: None,
+            };
+            instantiate(deps, mock_env(), mock_info("creator", &[]), msg).unwrap();
+        }
+
+        fn bond_coins(deps: DepsMut<TgradeQuery>, addr: &str, funds: &[Coin]) {
+            let msg = ExecuteMsg::Bond {
+                vesting_tokens: None,
+                lock_duration: None,
+            };
+            execute(deps, mock_env(), mock_info(addr, funds), msg).unwrap();
+        }
+
+        fn unbond_coin(deps: DepsMut<TgradeQuery>, addr: &str, tokens: Coin) {
+            let msg = ExecuteMsg::Unbond { tokens };
+            execute(deps, mock_env(), mock_info(addr, &[]), msg).unwrap();
+        }
+
+        #[test]
+        fn instantiate_validates_denoms() {
+            let mut deps = mock_deps_tgrade();
+            let mut msg = InstantiateMsg {
+                denom: DENOM.to_owned(),
+                tokens_per_point: TOKENS_PER_POINT,
+                min_bond: MIN_BOND,
+                unbonding_period: UNBONDING_DURATION,
+                admin: None,
+                preauths_hooks: 0,
+                preauths_slashing: 0,
+                auto_return_limit: 0,
+                extra_denoms: vec![StakeDenom {
+                    denom: DENOM.to_owned(),
+                    tokens_per_point: Uint128::new(1),
+                    min_bond: Uint128::new(1),
+                }],
+                lock_tiers: vec![],
+                instant_unbond: None,
+            };
+            let err = instantiate(
+                deps.as_mut(),
+                mock_env(),
+                mock_info("creator", &[]),
+                msg.clone(),
+            )
+            .unwrap_err();
+            assert_eq!(ContractError::DuplicatedDenom(DENOM.to_owned()), err);
+
+            msg.extra_denoms[0].denom = LP.to_owned();
+            msg.extra_denoms[0].tokens_per_point = Uint128::zero();
+            let err =
+                instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap_err();
+            assert_eq!(ContractError::InvalidTokensPerPoint(LP.to_owned()), err);
+        }
+
+        #[test]
+        fn points_are_summed_over_denoms() {
+            let mut deps = mock_deps_tgrade();
+            do_instantiate(deps.as_mut(), 0);
+
+            // 7 from main denom, 15 from lp, 4 from ibc
+            bond_coins(
+                deps.as_mut(),
+                USER1,
+                &[coin(7_000, DENOM), coin(1_500, LP), coin(45, IBC)],
+            );
+            // main denom below min bond, so only 12 from lp counts
+            bond_coins(deps.as_mut(), USER2, &[coin(4_000, DENOM), coin(1_200, LP)]);
+            // lp below min bond, nothing counts
+            bond_coins(deps.as_mut(), USER3, &[coin(999, LP)]);
+            assert_users(deps.as_ref(), Some(26), Some(12), None, None);
+
+            let staked = query_staked(deps.as_ref(), USER1.to_owned()).unwrap();
+            assert_eq!(
+                staked,
+                StakedResponse {
+                    liquid: coin(7_000, DENOM),
+                    vesting: coin(0, DENOM),
+                    extra: vec![coin(45, IBC), coin(1_500, LP)],
+                }
+            );
+
+            // unsupported denoms are rejected
+            let msg = ExecuteMsg::Bond {
+                vesting_tokens: None,
+                lock_duration: None,
+            };
+            let info = mock_info(USER1, &[coin(7_000, DENOM), coin(1_000, "foo")]);
+            let err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+            assert_eq!(ContractError::UnsupportedDenom("foo".to_owned()), err);
+
+            // vesting tokens can be only in the main denom
+            let msg = ExecuteMsg::Bond {
+                vesting_tokens: Some(coin(1_000, LP)),
+                lock_duration: None,
+            };
+            let err = execute(deps.as_mut(), mock_env(), mock_info(USER1, &[]), msg).unwrap_err();
+            assert_eq!(ContractError::MissingDenom(DENOM.to_owned()), err);
+        }
+
+        #[test]
+        fn unbond_claim_and_rebond_extra_denom() {
+            let mut deps = mock_deps_tgrade();
+            do_instantiate(deps.as_mut(), 0);
+
+            bond_coins(deps.as_mut(), USER1, &[coin(6_000, DENOM), coin(2_000, LP)]);
+            assert_users(deps.as_ref(), Some(26), None, None, None);
+
+            unbond_coin(deps.as_mut(), USER1, coin(1_500, LP));
+            unbond_coin(deps.as_mut(), USER1, coin(1_000, DENOM));
+            // lp falls below its min bond
+            assert_users(deps.as_ref(), Some(5), None, None, None);
+
+            // cannot unbond more than staked in a denom
+            let msg = ExecuteMsg::Unbond {
+                tokens: coin(501, LP),
+            };
+            execute(deps.as_mut(), mock_env(), mock_info(USER1, &[]), msg).unwrap_err();
+
+            let expires = Duration::new(UNBONDING_DURATION).after(&mock_env().block);
+            let mut expected_claim = Claim::new(Addr::unchecked(USER1), 1_000, 0, expires, 12_345);
+            expected_claim.extra_amounts = vec![coin(1_500, LP)];
+            assert_eq!(
+                get_claims(deps.as_ref(), Addr::unchecked(USER1), None, None),
+                vec![expected_claim]
+            );
+
+            // rebond part of the lp
+            let msg = ExecuteMsg::Rebond {
+                tokens: coin(700, LP),
+            };
+            execute(deps.as_mut(), mock_env(), mock_info(USER1, &[]), msg).unwrap();
+            assert_users(deps.as_ref(), Some(17), None, None, None);
+            let msg = ExecuteMsg::Rebond {
+                tokens: coin(801, LP),
+            };
+            let err = execute(deps.as_mut(), mock_env(), mock_info(USER1, &[]), msg).unwrap_err();
+            assert_eq!(ContractError::InsufficientUnbonding(Uint128::new(801)), err);
+
+            // claim everything at once
+            let mut env = mock_env();
+            env.block.time = env.block.time.plus_seconds(UNBONDING_DURATION);
+            let res = execute(
+                deps.as_mut(),
+                env,
+                mock_info(USER1, &[]),
+                ExecuteMsg::Claim {},
+            )
+            .unwrap();
+            assert_eq!(
+                res.messages,
+                vec![SubMsg::new(BankMsg::Send {
+                    to_address: USER1.into(),
+                    amount: vec![coin(1_000, DENOM), coin(800, LP)],
+                })]
+            );
+        }
+
+        #[test]
+        fn extra_denoms_auto_released() {
+            let mut deps = mock_deps_tgrade();
+            do_instantiate(deps.as_mut(), 10);
+
+            bond_coins(deps.as_mut(), USER1, &[coin(6_000, DENOM), coin(2_000, LP)]);
+            unbond_coin(deps.as_mut(), USER1, coin(1_500, LP));
+
+            let mut env = mock_env();
+            env.block.time = env.block.time.plus_seconds(UNBONDING_DURATION);
+            let res = sudo(deps.as_mut(), env, TgradeSudoMsg::EndBlock {}).unwrap();
+            assert_eq!(
+                res.messages,
+                vec![SubMsg::new(BankMsg::Send {
+                    to_address: USER1.into(),
+                    amount: vec![coin(1_500, LP)],
+                })]
+            );
+        }
+
+        #[test]
+        fn slashing_burns_extra_denoms() {
+            let mut deps = mock_deps_tgrade();
+            do_instantiate(deps.as_mut(), 0);
+
+            let slasher = "slasher";
+            let msg = ExecuteMsg::AddSlasher {
+                addr: slasher.to_owned(),
+            };
+            execute(deps.as_mut(), mock_env(), mock_info(INIT_ADMIN, &[]), msg).unwrap();
+
+            bond_coins(
+                deps.as_mut(),
+                USER1,
+                &[coin(6_000, DENOM), coin(2_000, LP), coin(100, IBC)],
+            );
+            unbond_coin(deps.as_mut(), USER1, coin(1_000, LP));
+
+            let msg = ExecuteMsg::Slash {
+                addr: USER1.to_owned(),
+                portion: Decimal::percent(50),
+            };
+            let res = execute(deps.as_mut(), mock_env(), mock_info(slasher, &[]), msg).unwrap();
+            assert_eq!(
+                res.messages,
+                vec![
+                    SubMsg::new(BankMsg::Burn {
+                        amount: coins(3_000, DENOM),
+                    }),
+                    SubMsg::new(BankMsg::Burn {
+                        amount: vec![coin(50, IBC), coin(1_000, LP)],
+                    }),
+                ]
+            );
+
+            let staked = query_staked(deps.as_ref(), USER1.to_owned()).unwrap();
+            assert_eq!(staked.liquid, coin(3_000, DENOM));
+            assert_eq!(staked.extra, vec![coin(50, IBC), coin(500, LP)]);
+            // only 5 from ibc, main denom and lp are below their min bonds
+            assert_users(deps.as_ref(), Some(5), None, None, None);
+
+            let claims = get_claims(deps.as_ref(), Addr::unchecked(USER1), None, None);
+            assert_eq!(claims[0].extra_amounts, vec![coin(500, LP)]);
+        }
+
+        #[test]
+        fn migrate_cannot_remove_denoms() {
+            let mut deps = mock_deps_tgrade();
+            do_instantiate(deps.as_mut(), 0);
+            set_contract_version(&mut deps.storage, CONTRACT_NAME, "0.1.0").unwrap();
+
+            let mut msg = MigrateMsg {
+                tokens_per_point: None,
+                min_bond: None,
+                unbonding_period: None,
+                auto_return_limit: None,
+                undelegations: None,
+                extra_denoms: Some(vec![StakeDenom {
+                    denom: LP.to_owned(),
+                    tokens_per_point: Uint128::new(200),
+                    min_bond: Uint128::new(1_000),
+                }]),
+                instant_unbond: None,
+                disable_instant_unbond: false,
+            };
+            let err = migrate(deps.as_mut(), mock_env(), msg.clone()).unwrap_err();
+            assert_eq!(ContractError::DenomRemoved(IBC.to_owned()), err);
+
+            msg.extra_denoms.as_mut().unwrap().push(StakeDenom {
+                denom: IBC.to_owned(),
+                tokens_per_point: Uint128::new(10),
+                min_bond: Uint128::new(1),
+            });
+            migrate(deps.as_mut(), mock_env(), msg).unwrap();
+            let cfg = CONFIG.load(&deps.storage).unwrap();
+            assert_eq!(cfg.extra_denom(LP).unwrap().tokens_per_point.u128(), 200);
+        }
+    }
+
+    mod locks {
+        use super::*;
+
+        fn do_instantiate(deps: DepsMut<TgradeQuery>, auto_return_limit: u64) {
+            let msg = InstantiateMsg {
+                denom: DENOM.to_owned(),
+                tokens_per_point: TOKENS_PER_POINT,
+                min_bond: MIN_BOND,
+                unbonding_period: UNBONDING_DURATION,
+                admin: Some(INIT_ADMIN.into()),
+                preauths_hooks: 0,
+                preauths_slashing: 1,
+                auto_return_limit,
+                extra_denoms: vec![],
+                lock_tiers: vec![
+                    LockTier {
+                        duration: Duration::new(1_000),
+                        multiplier: Decimal::percent(150),
+                    },
+                    LockTier {
+                        duration: Duration::new(100),
+                        multiplier: Decimal::percent(125),
+                    },
+                ],
+                instant_unbond: None,
+            };
+            instantiate(deps, mock_env(), mock_info("creator", &[]), msg).unwrap();
+        }
+
+        fn bond_locked(
+            deps: DepsMut<TgradeQuery>,
+            addr: &str,
+            amount: u128,
+            lock_duration: Option<u64>,
+        ) -> Result<Response, ContractError> {
+            let msg = ExecuteMsg::Bond {
+                vesting_tokens: None,
+                lock_duration,
+            };
+            execute(
+                deps,
+                mock_env(),
+                mock_info(addr, &coins(amount, DENOM)),
+                msg,
+            )
+        }
+
+        fn list_locks(deps: Deps<TgradeQuery>, addr: &str) -> Vec<Lock> {
+            let msg = QueryMsg::ListLocks {
+                address: addr.to_owned(),
+                start_after: None,
+                limit: None,
+            };
+            let raw = query(deps, mock_env(), msg).unwrap();
+            let res: LocksResponse = from_slice(&raw).unwrap();
+            res.locks
+        }
+
+        #[test]
+        fn instantiate_validates_tiers() {
+            let mut deps = mock_deps_tgrade();
+            let msg = InstantiateMsg {
+                denom: DENOM.to_owned(),
+                tokens_per_point: TOKENS_PER_POINT,
+                min_bond: MIN_BOND,
+                unbonding_period: UNBONDING_DURATION,
+                admin: None,
+                preauths_hooks: 0,
+                preauths_slashing: 0,
+                auto_return_limit: 0,
+                extra_denoms: vec![],
+                lock_tiers: vec![LockTier {
+                    duration: Duration::new(100),
+                    multiplier: Decimal::percent(90),
+                }],
+                instant_unbond: None,
+            };
+            let err =
+                instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap_err();
+            assert_eq!(ContractError::InvalidLockTiers {}, err);
+        }
+
+        #[test]
+        fn locked_tokens_multiply_points() {
+            let mut deps = mock_deps_tgrade();
+            do_instantiate(deps.as_mut(), 0);
+
+            // 150s lock falls into the 100s tier
+            bond_locked(deps.as_mut(), USER1, 10_000, Some(150)).unwrap();
+            // 1_000s lock falls into the 1_000s tier
+            bond_locked(deps.as_mut(), USER2, 10_000, Some(1_000)).unwrap();
+            bond_locked(deps.as_mut(), USER3, 10_000, None).unwrap();
+            assert_users(deps.as_ref(), Some(12), Some(15), Some(10), None);
+
+            // too short locks are rejected
+            let err = bond_locked(deps.as_mut(), USER3, 1_000, Some(99)).unwrap_err();
+            assert_eq!(ContractError::InvalidLockDuration(99), err);
+
+            let unlock_at = Duration::new(150).after(&mock_env().block);
+            assert_eq!(
+                list_locks(deps.as_ref(), USER1),
+                vec![Lock {
+                    addr: Addr::unchecked(USER1),
+                    amount: Uint128::new(10_000),
+                    multiplier: Decimal::percent(125),
+                    unlock_at,
+                }]
+            );
+            assert_eq!(list_locks(deps.as_ref(), USER3), vec![]);
+        }
+
+        #[test]
+        fn locked_tokens_cannot_be_unbonded() {
+            let mut deps = mock_deps_tgrade();
+            do_instantiate(deps.as_mut(), 0);
+
+            bond_locked(deps.as_mut(), USER1, 10_000, Some(100)).unwrap();
+            bond_locked(deps.as_mut(), USER1, 3_000, None).unwrap();
+            assert_users(deps.as_ref(), Some(15), None, None, None);
+
+            let msg = ExecuteMsg::Unbond {
+                tokens: coin(3_001, DENOM),
+            };
+            let err = execute(deps.as_mut(), mock_env(), mock_info(USER1, &[]), msg).unwrap_err();
+            assert_eq!(ContractError::LockedTokens(Uint128::new(3_000)), err);
+
+            // unlocked part can be unbonded
+            unbond(deps.as_mut(), 3_000, 0, 0, 0, 0);
+            assert_users(deps.as_ref(), Some(12), None, None, None);
+
+            // once the lock expires, everything can be unbonded
+            unbond(deps.as_mut(), 10_000, 0, 0, 1, 100);
+            assert_users(deps.as_ref(), None, None, None, None);
+            assert_eq!(list_locks(deps.as_ref(), USER1), vec![]);
+        }
+
+        #[test]
+        fn expired_locks_released_at_end_block() {
+            let mut deps = mock_deps_tgrade();
+            do_instantiate(deps.as_mut(), 10);
+
+            bond_locked(deps.as_mut(), USER1, 10_000, Some(100)).unwrap();
+            bond_locked(deps.as_mut(), USER2, 10_000, Some(1_000)).unwrap();
+            assert_users(deps.as_ref(), Some(12), Some(15), None, None);
+
+            let mut env = mock_env();
+            env.block.height += 1;
+            env.block.time = env.block.time.plus_seconds(100);
+            sudo(deps.as_mut(), env, TgradeSudoMsg::EndBlock {}).unwrap();
+
+            assert_users(deps.as_ref(), Some(10), Some(15), None, None);
+            assert_eq!(list_locks(deps.as_ref(), USER1), vec![]);
+            assert_eq!(list_locks(deps.as_ref(), USER2).len(), 1);
+        }
+
+        #[test]
+        fn slashing_reduces_locks() {
+            let mut deps = mock_deps_tgrade();
+            do_instantiate(deps.as_mut(), 0);
+
+            let msg = ExecuteMsg::AddSlasher {
+                addr: "slasher".to_owned(),
+            };
+            execute(deps.as_mut(), mock_env(), mock_info(INIT_ADMIN, &[]), msg).unwrap();
+
+            bond_locked(deps.as_mut(), USER1, 10_000, Some(1_000)).unwrap();
+            let msg = ExecuteMsg::Slash {
+                addr: USER1.to_owned(),
+                portion: Decimal::percent(40),
+            };
+            execute(deps.as_mut(), mock_env(), mock_info("slasher", &[]), msg).unwrap();
+
+            assert_eq!(
+                list_locks(deps.as_ref(), USER1)[0].amount,
+                Uint128::new(6_000)
+            );
+            assert_users(deps.as_ref(), Some(9), None, None, None);
+        }
+
+        #[test]
+        fn expired_locks_do_not_multiply_points() {
+            let mut deps = mock_deps_tgrade();
+            // expired locks are never released at end block
+            do_instantiate(deps.as_mut(), 0);
+
+            let msg = ExecuteMsg::AddSlasher {
+                addr: "slasher".to_owned(),
+            };
+            execute(deps.as_mut(), mock_env(), mock_info(INIT_ADMIN, &[]), msg).unwrap();
+
+            bond_locked(deps.as_mut(), USER1, 10_000, Some(100)).unwrap();
+            assert_users(deps.as_ref(), Some(12), None, None, None);
+
+            // points are recalculated after the lock expired, but before it is released
+            let mut env = mock_env();
+            env.block.time = env.block.time.plus_seconds(100);
+            let msg = ExecuteMsg::Slash {
+                addr: USER1.to_owned(),
+                portion: Decimal::percent(10),
+            };
+            execute(deps.as_mut(), env, mock_info("slasher", &[]), msg).unwrap();
+
+            assert_eq!(list_locks(deps.as_ref(), USER1).len(), 1);
+            assert_users(deps.as_ref(), Some(9), None, None, None);
+        }
+
+        #[test]
+        fn lock_with_other_multiplier_is_not_merged() {
+            let mut deps = mock_deps_tgrade();
+            do_instantiate(deps.as_mut(), 0);
+
+            // a lock in the same slot, created under other lock tiers
+            let unlock_at = Duration::new(100).after(&mock_env().block);
+            let lock = Lock {
+                addr: Addr::unchecked(USER1),
+                amount: Uint128::new(10_000),
+                multiplier: Decimal::percent(200),
+                unlock_at,
+            };
+            locks()
+                .save(
+                    &mut deps.storage,
+                    (&Addr::unchecked(USER1), unlock_at.as_key()),
+                    &lock,
+                )
+                .unwrap();
+
+            let err = bond_locked(deps.as_mut(), USER1, 10_000, Some(100)).unwrap_err();
+            assert_eq!(ContractError::LockMultiplierMismatch {}, err);
+            assert_eq!(list_locks(deps.as_ref(), USER1), vec![lock]);
+        }
+    }
+
+    mod instant_unbond {
+        use super::*;
+
+        const POOL: &str = "community_pool";
+
+        fn enable_instant_unbond(deps: DepsMut<TgradeQuery>, penalty: Decimal) {
+            let msg = MigrateMsg {
+                tokens_per_point: None,
+                min_bond: None,
+                unbonding_period: None,
+                auto_return_limit: None,
+                undelegations: None,
+                extra_denoms: None,
+                instant_unbond: Some(UnvalidatedInstantUnbond {
+                    penalty,
+                    recipient: POOL.to_owned(),
+                }),
+                disable_instant_unbond: false,
+            };
+            migrate(deps, mock_env(), msg).unwrap();
+        }
+
+        fn instant_unbond(
+            deps: DepsMut<TgradeQuery>,
+            addr: &str,
+            amount: u128,
+        ) -> Result<Response, ContractError> {
+            let msg = ExecuteMsg::InstantUnbond {
+                tokens: coin(amount, DENOM),
+            };
+            execute(deps, mock_env(), mock_info(addr, &[]), msg)
+        }
+
+        #[test]
+        fn disabled_by_default() {
+            let mut deps = mock_deps_tgrade();
+            default_instantiate(deps.as_mut());
+            bond_liquid(deps.as_mut(), 12_000, 0, 0, 1);
+
+            let err = instant_unbond(deps.as_mut(), USER1, 1_000).unwrap_err();
+            assert_eq!(ContractError::InstantUnbondDisabled {}, err);
+        }
+
+        #[test]
+        fn penalty_validation() {
+            let mut deps = mock_deps_tgrade();
+            default_instantiate(deps.as_mut());
+
+            for penalty in [Decimal::zero(), Decimal::one()] {
+                let msg = MigrateMsg {
+                    tokens_per_point: None,
+                    min_bond: None,
+                    unbonding_period: None,
+                    auto_return_limit: None,
+                    undelegations: None,
+                    extra_denoms: None,
+                    instant_unbond: Some(UnvalidatedInstantUnbond {
+                        penalty,
+                        recipient: POOL.to_owned(),
+                    }),
+                    disable_instant_unbond: false,
+                };
+                let err = migrate(deps.as_mut(), mock_env(), msg).unwrap_err();
+                assert_eq!(ContractError::InvalidPenalty(penalty), err);
+            }
+        }
+
+        #[test]
+        fn penalty_sent_to_recipient() {
+            let mut deps = mock_deps_tgrade();
+            default_instantiate(deps.as_mut());
+            enable_instant_unbond(deps.as_mut(), Decimal::percent(10));
+
+            bond(deps.as_mut(), (12_000, 3_000), (0, 0), (0, 0), 1);
+            assert_users(deps.as_ref(), Some(15), None, None, None);
+
+            let res = instant_unbond(deps.as_mut(), USER1, 4_000).unwrap();
+            assert_eq!(
+                res.messages[..2],
+                [
+                    SubMsg::new(BankMsg::Send {
+                        to_address: USER1.to_owned(),
+                        amount: coins(3_600, DENOM),
+                    }),
+                    SubMsg::new(WasmMsg::Execute {
+                        contract_addr: POOL.to_owned(),
+                        msg: to_binary(&DistributionMsg::DistributeRewards {}).unwrap(),
+                        funds: coins(400, DENOM),
+                    }),
+                ]
+            );
+
+            // points are removed immediately, and no claim is created
+            assert_stake_liquid(deps.as_ref(), 8_000, 0, 0);
+            assert_stake_vesting(deps.as_ref(), 3_000, 0, 0);
+            assert_users(deps.as_ref(), Some(11), None, None, None);
+            assert!(get_claims(deps.as_ref(), Addr::unchecked(USER1), None, None).is_empty());
+        }
+
+        #[test]
+        fn vesting_stake_excluded() {
+            let mut deps = mock_deps_tgrade();
+            default_instantiate(deps.as_mut());
+            enable_instant_unbond(deps.as_mut(), Decimal::percent(10));
+
+            bond(deps.as_mut(), (12_000, 3_000), (0, 0), (0, 0), 1);
+
+            let err = instant_unbond(deps.as_mut(), USER1, 12_001).unwrap_err();
+            assert_eq!(
+                ContractError::InsufficientLiquidStake(Uint128::new(12_000)),
+                err
+            );
+            assert_stake_liquid(deps.as_ref(), 12_000, 0, 0);
+            assert_stake_vesting(deps.as_ref(), 3_000, 0, 0);
+
+            instant_unbond(deps.as_mut(), USER1, 12_000).unwrap();
+            assert_stake_liquid(deps.as_ref(), 0, 0, 0);
+            assert_stake_vesting(deps.as_ref(), 3_000, 0, 0);
+        }
+
+        #[test]
+        fn only_main_denom() {
+            let mut deps = mock_deps_tgrade();
+            default_instantiate(deps.as_mut());
+            enable_instant_unbond(deps.as_mut(), Decimal::percent(10));
+
+            let msg = ExecuteMsg::InstantUnbond {
+                tokens: coin(1_000, "lp"),
+            };
+            let err = execute(deps.as_mut(), mock_env(), mock_info(USER1, &[]), msg).unwrap_err();
+            assert_eq!(ContractError::InvalidDenom {}, err);
+        }
+
+        #[test]
+        fn locked_liquid_stake_excluded() {
+            let mut deps = mock_deps_tgrade();
+            let msg = InstantiateMsg {
+                denom: DENOM.to_owned(),
+                tokens_per_point: TOKENS_PER_POINT,
+                min_bond: MIN_BOND,
+                unbonding_period: UNBONDING_DURATION,
+                admin: Some(INIT_ADMIN.into()),
+                preauths_hooks: 0,
+                preauths_slashing: 0,
+                auto_return_limit: 0,
+                extra_denoms: vec![],
+                lock_tiers: vec![LockTier {
+                    duration: Duration::new(100),
+                    multiplier: Decimal::percent(150),
+                }],
+                instant_unbond: Some(UnvalidatedInstantUnbond {
+                    penalty: Decimal::percent(10),
+                    recipient: POOL.to_owned(),
+                }),
+            };
+            instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+            // all the liquid stake is locked, the vesting stake is not
+            let msg = ExecuteMsg::Bond {
+                vesting_tokens: None,
+                lock_duration: Some(100),
+            };
+            let info = mock_info(USER1, &coins(10_000, DENOM));
+            execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+            bond(deps.as_mut(), (0, 5_000), (0, 0), (0, 0), 1);
+
+            let err = instant_unbond(deps.as_mut(), USER1, 1).unwrap_err();
+            assert_eq!(ContractError::LockedTokens(Uint128::zero()), err);
+
+            // once the lock expires, the liquid stake can be unbonded instantly
+            let mut env = mock_env();
+            env.block.time = env.block.time.plus_seconds(100);
+            let msg = ExecuteMsg::InstantUnbond {
+                tokens: coin(10_000, DENOM),
+            };
+            execute(deps.as_mut(), env, mock_info(USER1, &[]), msg).unwrap();
+            assert_stake_liquid(deps.as_ref(), 0, 0, 0);
+            assert_stake_vesting(deps.as_ref(), 5_000, 0, 0);
+        }
+
+        #[test]
+        fn disabled_by_migration() {
+            let mut deps = mock_deps_tgrade();
+            default_instantiate(deps.as_mut());
+            enable_instant_unbond(deps.as_mut(), Decimal::percent(10));
+            bond_liquid(deps.as_mut(), 12_000, 0, 0, 1);
+
+            let mut msg = MigrateMsg {
+                tokens_per_point: None,
+                min_bond: None,
+                unbonding_period: None,
+                auto_return_limit: None,
+                undelegations: None,
+                extra_denoms: None,
+                instant_unbond: Some(UnvalidatedInstantUnbond {
+                    penalty: Decimal::percent(20),
+                    recipient: POOL.to_owned(),
+                }),
+                disable_instant_unbond: true,
+            };
+            let err = migrate(deps.as_mut(), mock_env(), msg.clone()).unwrap_err();
+            assert_eq!(ContractError::InstantUnbondConflict {}, err);
+
+            msg.instant_unbond = None;
+            migrate(deps.as_mut(), mock_env(), msg).unwrap();
+            let err = instant_unbond(deps.as_mut(), USER1, 1_000).unwrap_err();
+            assert_eq!(ContractError::InstantUnbondDisabled {}, err);
+        }
+    }
+
+    mod export_import {
+        use super::*;
+
+        fn export_state(deps: DepsMut<TgradeQuery>) -> StakeState {
+            let res = sudo(deps, mock_env(), TgradeSudoMsg::Export {}).unwrap();
+            from_slice(&res.data.unwrap()).unwrap()
+        }
+
+        fn setup_state(deps: DepsMut<TgradeQuery>) {
+            let mut deps = deps;
+            default_instantiate(deps.branch());
+
+            let admin_info = mock_info(INIT_ADMIN, &[]);
+            let add_hook = ExecuteMsg::AddHook {
+                addr: "hook".to_owned(),
+            };
+            execute(deps.branch(), mock_env(), admin_info, add_hook).unwrap();
+            let add_slasher = ExecuteMsg::AddSlasher {
+                addr: "slasher".to_owned(),
+            };
+            execute(
+                deps.branch(),
+                mock_env(),
+                mock_info(USER1, &[]),
+                add_slasher,
+            )
+            .unwrap();
+
+            bond(deps.branch(), (12_000, 3_000), (7_500, 0), (0, 6_000), 1);
+            unbond(deps.branch(), 2_000, 1_000, 0, 2, 0);
+            let slash = ExecuteMsg::Slash {
+                addr: USER1.to_owned(),
+                portion: Decimal::percent(10),
+            };
+            execute(deps, mock_env(), mock_info("slasher", &[]), slash).unwrap();
+        }
+
+        #[test]
+        fn export_contains_all_state() {
+            let mut deps = mock_deps_tgrade();
+            setup_state(deps.as_mut());
+
+            let state = export_state(deps.as_mut());
+            assert_eq!(state.config, CONFIG.load(&deps.storage).unwrap());
+            assert_eq!(state.admin, Some(Addr::unchecked(INIT_ADMIN)));
+            assert_eq!(state.members.len(), 3);
+            assert_eq!(
+                state.stakes,
+                vec![
+                    StakeInfo {
+                        addr: Addr::unchecked(USER1),
+                        liquid: Uint128::new(9_000),
+                        vesting: Uint128::new(2_700),
+                        extra: vec![],
+                    },
+                    StakeInfo {
+                        addr: Addr::unchecked(USER2),
+                        liquid: Uint128::new(6_500),
+                        vesting: Uint128::zero(),
+                        extra: vec![],
+                    },
+                    StakeInfo {
+                        addr: Addr::unchecked(USER3),
+                        liquid: Uint128::zero(),
+                        vesting: Uint128::new(6_000),
+                        extra: vec![],
+                    },
+                ]
+            );
+            assert_eq!(state.claims.len(), 2);
+            assert_eq!(state.hooks, vec!["hook".to_owned()]);
+            assert_eq!(state.preauths_hooks, 1);
+            assert_eq!(state.preauths_slashing, 0);
+            assert_eq!(state.slashers, vec!["slasher".to_owned()]);
+            assert_eq!(state.slashes.len(), 1);
+        }
+
+        #[test]
+        fn import_works() {
+            let mut deps = mock_deps_tgrade();
+            setup_state(deps.as_mut());
+            let state = export_state(deps.as_mut());
+
+            let mut imported = mock_deps_tgrade();
+            sudo(
+                imported.as_mut(),
+                mock_env(),
+                TgradeSudoMsg::Import(state.clone()),
+            )
+            .unwrap();
+
+            assert_eq!(export_state(imported.as_mut()), state);
+            assert_users(imported.as_ref(), Some(11), Some(6), Some(6), None);
+            assert_eq!(
+                get_claims(imported.as_ref(), Addr::unchecked(USER1), None, None),
+                get_claims(deps.as_ref(), Addr::unchecked(USER1), None, None)
+            );
+
+            // slashing numbering is continued
+            let slash = ExecuteMsg::Slash {
+                addr: USER2.to_owned(),
+                portion: Decimal::percent(10),
+            };
+            let res = execute(
+                imported.as_mut(),
+                mock_env(),
+                mock_info("slasher", &[]),
+                slash,
+            )
+            .unwrap();
+            assert!(res
+                .attributes
+                .iter()
+                .any(|attr| attr.key == "slash_id" && attr.value == "2"));
+        }
+
+        #[test]
+        fn import_deletes_existing_entries() {
+            let mut deps = mock_deps_tgrade();
+            default_instantiate(deps.as_mut());
+            bond(deps.as_mut(), (12_000, 0), (0, 0), (0, 0), 1);
+            let state = export_state(deps.as_mut());
+
+            let mut imported = mock_deps_tgrade();
+            setup_state(imported.as_mut());
+            sudo(
+                imported.as_mut(),
+                mock_env(),
+                TgradeSudoMsg::Import(state.clone()),
+            )
+            .unwrap();
+
+            assert_eq!(export_state(imported.as_mut()), state);
+            assert_users(imported.as_ref(), Some(12), None, None, None);
+            assert!(get_claims(imported.as_ref(), Addr::unchecked(USER1), None, None).is_empty());
+        }
+
+        #[test]
+        fn pending_recalculation_is_preserved() {
+            let mut deps = mock_deps_tgrade();
+            setup_state(deps.as_mut());
+            let msg = MigrateMsg {
+                tokens_per_point: Some(Uint128::new(500)),
+                min_bond: None,
+                unbonding_period: None,
+                auto_return_limit: None,
+                undelegations: None,
+                extra_denoms: None,
+                instant_unbond: None,
+                disable_instant_unbond: false,
+            };
+            migrate(deps.as_mut(), mock_env(), msg).unwrap();
+            let msg = ExecuteMsg::RecalculatePoints { limit: Some(1) };
+            execute(deps.as_mut(), mock_env(), mock_info(USER1, &[]), msg).unwrap();
+
+            let state = export_state(deps.as_mut());
+            assert_eq!(
+                state.recalculation,
+                Some(Recalculation {
+                    start_after: Some(Addr::unchecked(USER1)),
+                    processed: 1,
+                })
+            );
+
+            let mut imported = mock_deps_tgrade();
+            sudo(
+                imported.as_mut(),
+                mock_env(),
+                TgradeSudoMsg::Import(state.clone()),
+            )
+            .unwrap();
+            assert_eq!(export_state(imported.as_mut()), state);
+
+            // importing a state without a recalculation drops the pending one
+            let mut state = state;
+            state.recalculation = None;
+            sudo(imported.as_mut(), mock_env(), TgradeSudoMsg::Import(state)).unwrap();
+            let msg = ExecuteMsg::RecalculatePoints { limit: None };
+            let err =
+                execute(imported.as_mut(), mock_env(), mock_info(USER1, &[]), msg).unwrap_err();
+            assert_eq!(ContractError::NoRecalculation {}, err);
+        }
+    }
+
+    mod recalculate_points {
+        use super::*;
+
+        fn recalculate(deps: DepsMut<TgradeQuery>, limit: u32) -> Result<Response, ContractError> {
+            let msg = ExecuteMsg::RecalculatePoints { limit: Some(limit) };
+            execute(deps, mock_env(), mock_info(USER1, &[]), msg)
+        }
+
+        fn query_progress(deps: Deps<TgradeQuery>) -> RecalculationResponse {
+            let raw = query(deps, mock_env(), QueryMsg::PointsRecalculation {}).unwrap();
+            from_slice(&raw).unwrap()
+        }
+
+        #[test]
+        fn nothing_to_recalculate() {
+            let mut deps = mock_deps_tgrade();
+            default_instantiate(deps.as_mut());
+
+            let err = recalculate(deps.as_mut(), 10).unwrap_err();
+            assert_eq!(ContractError::NoRecalculation {}, err);
+            assert_eq!(
+                query_progress(deps.as_ref()),
+                RecalculationResponse {
+                    pending: false,
+                    start_after: None,
+                    processed: 0,
+                }
+            );
+        }
+
+        #[test]
+        fn migration_recalculates_points_in_batches() {
+            let mut deps = mock_deps_tgrade();
+            default_instantiate(deps.as_mut());
+            let add_hook = ExecuteMsg::AddHook {
+                addr: "hook".to_owned(),
+            };
+            execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info(INIT_ADMIN, &[]),
+                add_hook,
+            )
+            .unwrap();
+
+            bond(deps.as_mut(), (12_000, 0), (7_500, 0), (0, 4_000), 1);
+            assert_users(deps.as_ref(), Some(12), Some(7), None, None);
+
+            let msg = MigrateMsg {
+                tokens_per_point: Some(Uint128::new(500)),
+                min_bond: Some(Uint128::new(4_000)),
+                unbonding_period: None,
+                auto_return_limit: None,
+                undelegations: None,
+                extra_denoms: None,
+                instant_unbond: None,
+                disable_instant_unbond: false,
+            };
+            migrate(deps.as_mut(), mock_env(), msg).unwrap();
+
+            // points are not changed until recalculated
+            assert_users(deps.as_ref(), Some(12), Some(7), None, None);
+            assert!(query_progress(deps.as_ref()).pending);
+
+            let err = recalculate(deps.as_mut(), 0).unwrap_err();
+            assert_eq!(ContractError::ZeroLimit {}, err);
+
+            let res = recalculate(deps.as_mut(), 2).unwrap();
+            assert_users(deps.as_ref(), Some(24), Some(15), None, None);
+            let diffs = vec![
+                MemberDiff::new(USER1, Some(12), Some(24)),
+                MemberDiff::new(USER2, Some(7), Some(15)),
+            ];
+            assert_eq!(
+                res.messages,
+                vec![SubMsg::new(
+                    MemberChangedHookMsg::new(diffs)
+                        .into_cosmos_msg("hook")
+                        .unwrap()
+                )]
+            );
+            assert_eq!(
+                query_progress(deps.as_ref()),
+                RecalculationResponse {
+                    pending: true,
+                    start_after: Some(USER2.to_owned()),
+                    processed: 2,
+                }
+            );
+
+            // vesting only staker is recalculated as well
+            recalculate(deps.as_mut(), 2).unwrap();
+            assert_users(deps.as_ref(), Some(24), Some(15), Some(8), None);
+            assert_eq!(
+                query_progress(deps.as_ref()),
+                RecalculationResponse {
+                    pending: false,
+                    start_after: None,
+                    processed: 0,
+                }
+            );
+
+            let err = recalculate(deps.as_mut(), 2).unwrap_err();
+            assert_eq!(ContractError::NoRecalculation {}, err);
+        }
+    }
 }
@@ -1,4 +1,4 @@
-use cosmwasm_std::StdError;
+use cosmwasm_std::{Decimal, StdError, Uint128};
 use thiserror::Error;
 
 use cw_controllers::AdminError;
@@ -33,12 +33,63 @@ pub enum ContractError {
     #[error("Sent unsupported denoms, must send '{0}' to stake")]
     ExtraDenoms(String),
 
+    #[error("Unsupported staking denom '{0}'")]
+    UnsupportedDenom(String),
+
+    #[error("Staking denom '{0}' is configured more than once")]
+    DuplicatedDenom(String),
+
+    #[error("Staking denom '{0}' must have non-zero tokens per point")]
+    InvalidTokensPerPoint(String),
+
+    #[error("Staking denom '{0}' cannot be removed")]
+    DenomRemoved(String),
+
+    #[error("Lock tiers must have unique non-zero durations, and multipliers of at least 1")]
+    InvalidLockTiers {},
+
+    #[error("No lockup tier for lock duration of {0} seconds")]
+    InvalidLockDuration(u64),
+
+    #[error("Only tokens of the main staking denom can be locked")]
+    LockedExtraDenoms {},
+
+    #[error("A lock with a different multiplier already unlocks at the same time")]
+    LockMultiplierMismatch {},
+
+    #[error("Cannot unbond locked tokens, only {0} can be unbonded")]
+    LockedTokens(Uint128),
+
+    #[error("Limit must be greater than zero")]
+    ZeroLimit {},
+
     #[error("Must send valid amount to unbond")]
     ZeroAmount {},
 
     #[error("Must send valid denom to unbond")]
     InvalidDenom {},
 
+    #[error("Not enough tokens unbonding to rebond {0}")]
+    InsufficientUnbonding(Uint128),
+
+    #[error("Instant unbonding is not enabled")]
+    InstantUnbondDisabled {},
+
+    #[error("Instant unbond penalty has to be between (0.0, 1.0), got {0}")]
+    InvalidPenalty(Decimal),
+
+    #[error("Instant unbonding cannot be both enabled and disabled")]
+    InstantUnbondConflict {},
+
+    #[error("Only liquid stake can be unbonded instantly, liquid stake is {0}")]
+    InsufficientLiquidStake(Uint128),
+
+    #[error("No points recalculation is pending")]
+    NoRecalculation {},
+
+    #[error("Vesting tokens can only be bonded for the sender")]
+    VestingBondFor {},
+
     #[error("No funds sent")]
     NoFunds {},
 
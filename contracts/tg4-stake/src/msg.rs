@@ -1,10 +1,15 @@
-use cosmwasm_std::{Coin, Decimal, Uint128};
+use cosmwasm_std::{to_binary, Api, Binary, Coin, Decimal, StdResult, Uint128, WasmMsg};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use tg_utils::{Duration, Expiration};
 
-pub use crate::claim::Claim;
+pub use crate::claim::{Claim, DailyRelease};
+use crate::error::ContractError;
+pub use crate::state::{InstantUnbond, Lock, LockTier, SlashRecord, StakeDenom};
 use tg4::Member;
+use tg_bindings::TgradeMsg;
+
+type CosmosMsg = cosmwasm_std::CosmosMsg<TgradeMsg>;
 
 const fn default_auto_return_limit() -> u64 {
     20
@@ -28,26 +33,93 @@ pub struct InstantiateMsg {
     #[serde(default)]
     pub preauths_slashing: u64,
     /// Limits how much claims would be automatically returned at end of block, 20 by default.
-    /// Setting this to 0 disables auto returning claims.
+    /// The same limit applies to expired locks being released at end of block.
+    /// Setting this to 0 disables auto returning claims and releasing locks (expired locks
+    /// are then only released when their owner bonds or unbonds).
     #[serde(default = "default_auto_return_limit")]
     pub auto_return_limit: u64,
+    /// Additional denoms accepted as (liquid only) stake, each with its own `tokens_per_point`
+    /// and `min_bond`. Membership points are the sum of points from all staked denoms.
+    #[serde(default)]
+    pub extra_denoms: Vec<StakeDenom>,
+    /// Lockup tiers: tokens bonded with a `lock_duration` of at least the tier's duration earn
+    /// points multiplied by the tier's multiplier (at least 1.0).
+    #[serde(default)]
+    pub lock_tiers: Vec<LockTier>,
+    /// Enables `InstantUnbond` on the given terms.
+    #[serde(default)]
+    pub instant_unbond: Option<UnvalidatedInstantUnbond>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
+pub struct UnvalidatedInstantUnbond {
+    /// Portion of the unbonded tokens taken as the penalty, between (0.0, 1.0)
+    pub penalty: Decimal,
+    /// The unvalidated address of the contract receiving the penalty. It has to accept
+    /// the `DistributeRewards {}` message, as the community pool does.
+    pub recipient: String,
+}
+
+impl UnvalidatedInstantUnbond {
+    /// Validates the recipient address and the penalty.
+    pub fn validate(self, api: &dyn Api) -> Result<InstantUnbond, ContractError> {
+        if self.penalty.is_zero() || self.penalty >= Decimal::one() {
+            return Err(ContractError::InvalidPenalty(self.penalty));
+        }
+        Ok(InstantUnbond {
+            penalty: self.penalty,
+            recipient: api.addr_validate(&self.recipient)?,
+        })
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
 #[serde(rename_all = "snake_case")]
 pub enum ExecuteMsg {
     /// Bond will bond all staking tokens sent with the message and update membership points.
-    /// The optional `vesting_tokens` will be staked (delegated) as well, if set.
-    Bond { vesting_tokens: Option<Coin> },
+    /// Tokens of any accepted staking denom can be sent together.
+    /// The optional `vesting_tokens` will be staked (delegated) as well, if set. They have to be
+    /// in the main staking denom.
+    /// The optional `lock_duration` (in seconds) locks the bonded tokens, so they cannot be
+    /// unbonded before it passes, in exchange for the points multiplier of the longest lockup
+    /// tier not exceeding it. Only tokens of the main staking denom can be locked.
+    Bond {
+        vesting_tokens: Option<Coin>,
+        lock_duration: Option<u64>,
+    },
+    /// BondFor will bond all staking tokens sent with the message on behalf of `beneficiary`.
+    /// Points, claims and the right to unbond belong to the beneficiary afterwards.
+    /// The optional `vesting_tokens` can only be set if the beneficiary is the sender, as they
+    /// are delegated from (and returned to) the sender's vesting account.
+    BondFor {
+        beneficiary: String,
+        vesting_tokens: Option<Coin>,
+    },
     /// Unbond will start the unbonding process for the given number of tokens.
     /// The sender immediately loses points from these tokens, and can claim them
     /// back to his wallet after `unbonding_period`.
     /// Tokens will be unbonded from the liquid stake first, and then from the vesting stake
-    /// if available.
+    /// if available (vesting stake exists only in the main staking denom).
     Unbond { tokens: Coin },
     /// Claim is used to claim your native and vesting tokens that you previously "unbonded"
     /// after the contract-defined waiting period (eg. 1 week)
     Claim {},
+    /// Rebond cancels the unbonding process for the given number of tokens, which are still
+    /// being unbonded (their claims are not mature yet). Pending claims are consumed starting
+    /// from the newest one, and the sender immediately regains points from these tokens.
+    /// Liquid and vesting tokens are bonded back to the liquid and vesting stake respectively.
+    Rebond { tokens: Coin },
+    /// InstantUnbond unbonds the given number of liquid tokens immediately, skipping the
+    /// unbonding period. The configured penalty portion is sent to the penalty recipient, and
+    /// the rest is sent back to the sender. The sender immediately loses points from these
+    /// tokens. Vesting stake cannot be unbonded this way.
+    InstantUnbond { tokens: Coin },
+
+    /// Recomputes the points of up to `limit` stakers, continuing a points recalculation started
+    /// by a migration of the points configuration. Can be called by anyone until all stakers
+    /// are processed. Membership changes are sent to the hooks in a single message.
+    /// `limit` must not be zero.
+    RecalculatePoints { limit: Option<u32> },
 
     /// Change the admin
     UpdateAdmin { admin: Option<String> },
@@ -55,6 +127,11 @@ pub enum ExecuteMsg {
     AddHook { addr: String },
     /// Remove a hook. Must be called by Admin
     RemoveHook { addr: String },
+    /// Add a new hook to be informed of all stake changes with `StakeChangedHookMsg`.
+    /// Must be called by Admin
+    AddStakeHook { addr: String },
+    /// Remove a stake hook. Must be called by Admin
+    RemoveStakeHook { addr: String },
     /// Add a new slasher. Must be called by Admin
     AddSlasher { addr: String },
     /// Remove a slasher. Must be called by Admin
@@ -77,13 +154,39 @@ pub enum QueryMsg {
         limit: Option<u32>,
         start_after: Option<Expiration>,
     },
-    /// Shows the number of liquid and vesting tokens currently staked by this address.
+    /// Shows pending claims of all addresses, ordered by their release time and then address.
+    /// Returns ClaimsResponse.
+    ListPendingClaims {
+        /// Release time and address of the last claim returned in the previous page
+        start_after: Option<PendingClaimCursor>,
+        limit: Option<u32>,
+    },
+    /// Shows totals of pending claims of all addresses, aggregated by the day of release.
+    /// Returns PendingReleasesResponse.
+    PendingReleasesByDay {
+        /// `next` cursor returned by the previous page
+        start_after: Option<PendingClaimCursor>,
+        /// Maximum number of claims aggregated in this page
+        limit: Option<u32>,
+    },
+    /// Shows the number of liquid and vesting tokens currently staked by this address,
+    /// including the liquid stake in additional denoms.
     /// Returns StakedResponse.
     Staked { address: String },
+    /// Shows the locked stake of this address. Returns LocksResponse.
+    ListLocks {
+        address: String,
+        start_after: Option<Expiration>,
+        limit: Option<u32>,
+    },
     /// Returns the unbonding period of this contract.
     /// Returns UnbondingPeriodResponse.
     UnbondingPeriod {},
 
+    /// Shows the progress of the points recalculation after a migration.
+    /// Returns RecalculationResponse.
+    PointsRecalculation {},
+
     /// Return AdminResponse
     Admin {},
     /// Returns TotalPointsResponse. This is the amount of tokens bonded divided by
@@ -106,18 +209,46 @@ pub enum QueryMsg {
     },
     /// Shows all registered hooks. Returns HooksResponse.
     Hooks {},
+    /// Shows all registered stake hooks. Returns HooksResponse.
+    StakeHooks {},
     /// Return the current number of preauths. Returns PreauthResponse.
     Preauths {},
     /// Returns information (bool) about whether a given address is an active slasher
     IsSlasher { addr: String },
     /// Returns all active slashers as a vector of addresses.
     ListSlashers {},
+    /// Returns the slashing history of this address, oldest first. Returns SlashesResponse.
+    ListSlashes {
+        address: String,
+        /// Id of the last slashing returned in the previous page
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    /// Returns the slashing history of all addresses, oldest first. Returns SlashesResponse.
+    ListAllSlashes {
+        /// Id of the last slashing returned in the previous page
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
 pub struct StakedResponse {
     pub liquid: Coin,
     pub vesting: Coin,
+    /// Liquid stake in additional staking denoms
+    #[serde(default)]
+    pub extra: Vec<Coin>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct LocksResponse {
+    pub locks: Vec<Lock>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct SlashesResponse {
+    pub slashes: Vec<SlashRecord>,
 }
 
 #[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
@@ -135,6 +266,31 @@ pub struct ClaimsResponse {
     pub claims: Vec<Claim>,
 }
 
+/// Position of a claim in the queue of pending claims
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct PendingClaimCursor {
+    pub release_at: Expiration,
+    pub addr: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct PendingReleasesResponse {
+    /// Totals by day. The last day may continue in the next page.
+    pub days: Vec<DailyRelease>,
+    /// Position to continue from, `None` when all pending claims were aggregated
+    pub next: Option<PendingClaimCursor>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct RecalculationResponse {
+    /// Whether there are stakers left to recompute
+    pub pending: bool,
+    /// Last address recomputed so far
+    pub start_after: Option<String>,
+    /// Number of addresses recomputed so far
+    pub processed: u64,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
 pub struct Undelegation {
     pub addr: String,
@@ -149,6 +305,77 @@ pub struct MigrateMsg {
     pub unbonding_period: Option<u64>,
     pub auto_return_limit: Option<u64>,
     pub undelegations: Option<Vec<Undelegation>>,
+    /// Replaces the additional staking denoms. Already configured denoms cannot be removed.
+    /// Changing `tokens_per_point`, `min_bond` or `extra_denoms` starts the recalculation of
+    /// all stakers' points, see `ExecuteMsg::RecalculatePoints`.
+    pub extra_denoms: Option<Vec<StakeDenom>>,
+    /// Enables `InstantUnbond`, or changes its terms.
+    pub instant_unbond: Option<UnvalidatedInstantUnbond>,
+    /// Disables `InstantUnbond`, cannot be combined with `instant_unbond`.
+    #[serde(default)]
+    pub disable_instant_unbond: bool,
+}
+
+/// Kind of the stake change, which also gives the direction of the deltas
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, JsonSchema, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum StakeChangeKind {
+    /// Tokens were bonded (or rebonded), the stake increased
+    Bond,
+    /// Tokens were unbonded, the stake decreased
+    Unbond,
+    /// Unbonded tokens were released from claims, the stake is not affected
+    Claim,
+    /// Tokens were slashed, the stake decreased. Slashed claims are not included
+    Slash,
+}
+
+/// Message sent to the stake hooks on every change of stake
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
+pub struct StakeChangedHookMsg {
+    pub addr: String,
+    pub kind: StakeChangeKind,
+    /// Amount of liquid tokens changed
+    pub liquid_delta: Uint128,
+    /// Amount of vesting tokens changed
+    pub vesting_delta: Uint128,
+    /// Amounts of additional denoms tokens changed
+    #[serde(default)]
+    pub extra_delta: Vec<Coin>,
+}
+
+impl StakeChangedHookMsg {
+    /// serializes the message
+    pub fn into_binary(self) -> StdResult<Binary> {
+        let msg = StakeChangedExecuteMsg::StakeChanged(self);
+        to_binary(&msg)
+    }
+
+    /// creates a cosmos_msg sending this struct to the named contract
+    pub fn into_cosmos_msg<T: Into<String>>(self, contract_addr: T) -> StdResult<CosmosMsg> {
+        let msg = self.into_binary()?;
+        let execute = WasmMsg::Execute {
+            contract_addr: contract_addr.into(),
+            msg,
+            funds: vec![],
+        };
+        Ok(execute.into())
+    }
+}
+
+// This is just a helper to properly serialize the above message
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
+#[serde(rename_all = "snake_case")]
+enum StakeChangedExecuteMsg {
+    StakeChanged(StakeChangedHookMsg),
+}
+
+/// Messages sent by this contract to an external contract
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum DistributionMsg {
+    /// Message sent to the instant unbond penalty recipient with the penalty
+    DistributeRewards {},
 }
 
 #[cfg(test)]
@@ -1,10 +1,20 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use crate::claim::Claims;
-use cosmwasm_std::{Addr, Uint128};
-use cw_storage_plus::{Item, Map};
-use tg_utils::Duration;
+use crate::claim::{Claim, Claims};
+use crate::error::ContractError;
+use cosmwasm_std::Order::Ascending;
+use cosmwasm_std::{
+    to_binary, Addr, Coin, Decimal, Deps, DepsMut, Env, Response, StdResult, Timestamp, Uint128,
+};
+use cw2::{get_contract_version, set_contract_version, ContractVersion};
+use cw_storage_plus::{Index, IndexList, IndexedMap, Item, Map, MultiIndex};
+use tg4::{Member, MemberInfo};
+use tg_bindings::{TgradeMsg, TgradeQuery};
+use tg_utils::{
+    members, Duration, Expiration, Hooks, ADMIN, HOOKS, PREAUTH_HOOKS, PREAUTH_SLASHING, SLASHERS,
+    TOTAL,
+};
 
 /// Builds a claims map as it cannot be done in const time
 pub fn claims() -> Claims<'static> {
@@ -21,8 +31,404 @@ pub struct Config {
     pub unbonding_period: Duration,
     /// limits of how much claims can be automatically returned at end of block
     pub auto_return_limit: u64,
+    /// additional denoms accepted as liquid stake
+    #[serde(default)]
+    pub extra_denoms: Vec<StakeDenom>,
+    /// lockup tiers, sorted by duration
+    #[serde(default)]
+    pub lock_tiers: Vec<LockTier>,
+    /// instant unbonding terms, disabled if not set
+    #[serde(default)]
+    pub instant_unbond: Option<InstantUnbond>,
+}
+
+impl Config {
+    /// Returns the configuration of an additional staking denom, if it is accepted
+    pub fn extra_denom(&self, denom: &str) -> Option<&StakeDenom> {
+        self.extra_denoms.iter().find(|d| d.denom == denom)
+    }
+
+    /// Returns the multiplier of the longest lockup tier not exceeding `lock_duration`
+    pub fn lock_multiplier(&self, lock_duration: u64) -> Option<Decimal> {
+        self.lock_tiers
+            .iter()
+            .rev()
+            .find(|tier| tier.duration.seconds() <= lock_duration)
+            .map(|tier| tier.multiplier)
+    }
+}
+
+/// Tokens locked for at least `duration` have their points multiplied by `multiplier`
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
+pub struct LockTier {
+    pub duration: Duration,
+    pub multiplier: Decimal,
+}
+
+/// Terms of unbonding liquid stake without waiting for the unbonding period
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
+pub struct InstantUnbond {
+    /// Portion of the unbonded tokens taken as the penalty, between (0.0, 1.0)
+    pub penalty: Decimal,
+    /// Contract receiving the penalty with a `DistributeRewards {}` message
+    pub recipient: Addr,
+}
+
+/// Additional staking denom, contributing to membership points on its own terms
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
+pub struct StakeDenom {
+    pub denom: String,
+    pub tokens_per_point: Uint128,
+    /// minimum amount of this denom to be staked for it to count towards points
+    pub min_bond: Uint128,
 }
 
 pub const CONFIG: Item<Config> = Item::new("config");
 pub const STAKE: Map<&Addr, Uint128> = Map::new("stake");
 pub const STAKE_VESTING: Map<&Addr, Uint128> = Map::new("vesting_stake");
+/// Liquid stake in additional denoms, indexed by `(addr, denom)`
+pub const STAKE_EXTRA: Map<(&Addr, &str), Uint128> = Map::new("extra_stake");
+
+/// Part of the stake (in the main denom) which cannot be unbonded before `unlock_at`,
+/// earning a points multiplier in exchange
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
+pub struct Lock {
+    pub addr: Addr,
+    pub amount: Uint128,
+    pub multiplier: Decimal,
+    pub unlock_at: Expiration,
+}
+
+pub struct LockIndexes<'a> {
+    // Last type param defines the pk deserialization type
+    pub unlock_at: MultiIndex<'a, u64, Lock, (Addr, u64)>,
+}
+
+impl<'a> IndexList<Lock> for LockIndexes<'a> {
+    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<Lock>> + '_> {
+        let v: Vec<&dyn Index<Lock>> = vec![&self.unlock_at];
+        Box::new(v.into_iter())
+    }
+}
+
+/// Locks are indexed by `(addr, unlock_at)`, and additionally by `unlock_at`, so expired locks
+/// can be found for all addresses.
+pub fn locks<'a>() -> IndexedMap<'a, (&'a Addr, u64), Lock, LockIndexes<'a>> {
+    let indexes = LockIndexes {
+        unlock_at: MultiIndex::new(|_, lock| lock.unlock_at.as_key(), "locks", "locks__unlock"),
+    };
+    IndexedMap::new("locks", indexes)
+}
+
+/// Record of a single slashing of a member
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
+pub struct SlashRecord {
+    /// Sequential id of the slashing, contract-wide
+    pub id: u64,
+    /// Slashed address
+    pub addr: Addr,
+    /// Slasher which executed the slashing
+    pub slasher: Addr,
+    pub height: u64,
+    pub time: Timestamp,
+    pub portion: Decimal,
+    /// Liquid stake removed
+    pub liquid: Uint128,
+    /// Vesting stake removed
+    pub vesting: Uint128,
+    /// Liquid stake in additional denoms removed
+    pub extra: Vec<Coin>,
+    /// Liquid tokens removed from pending claims
+    pub claims_liquid: Uint128,
+    /// Vesting tokens removed from pending claims
+    pub claims_vesting: Uint128,
+    /// Tokens of additional denoms removed from pending claims
+    pub claims_extra: Vec<Coin>,
+}
+
+pub struct SlashIndexes<'a> {
+    pub addr: MultiIndex<'a, Addr, SlashRecord, u64>,
+}
+
+impl<'a> IndexList<SlashRecord> for SlashIndexes<'a> {
+    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<SlashRecord>> + '_> {
+        let v: Vec<&dyn Index<SlashRecord>> = vec![&self.addr];
+        Box::new(v.into_iter())
+    }
+}
+
+/// Slashing history, indexed by slashing id, and additionally by slashed address.
+pub fn slashes<'a>() -> IndexedMap<'a, u64, SlashRecord, SlashIndexes<'a>> {
+    let indexes = SlashIndexes {
+        addr: MultiIndex::new(|_, slash| slash.addr.clone(), "slashes", "slashes__addr"),
+    };
+    IndexedMap::new("slashes", indexes)
+}
+
+/// Number of slashings recorded so far, used to assign their ids
+pub const SLASH_COUNT: Item<u64> = Item::new("slash_count");
+
+/// Progress of recomputing the points of all stakers, pending after the points configuration
+/// was migrated
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug, Default)]
+pub struct Recalculation {
+    /// Last address recomputed so far
+    pub start_after: Option<Addr>,
+    /// Number of addresses recomputed so far
+    pub processed: u64,
+}
+
+pub const RECALCULATION: Item<Recalculation> = Item::new("points_recalculation");
+
+/// Contracts informed about every change of stake, besides the membership hooks
+pub const STAKE_HOOKS: Hooks = Hooks::new("stake-hooks");
+
+/// Stake of a single address
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
+pub struct StakeInfo {
+    pub addr: Addr,
+    pub liquid: Uint128,
+    pub vesting: Uint128,
+    /// Liquid stake in additional denoms
+    pub extra: Vec<Coin>,
+}
+
+/// Export / Import state
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct StakeState {
+    pub contract_version: ContractVersion,
+    pub admin: Option<Addr>,
+    pub config: Config,
+    pub members: Vec<Member>,
+    pub stakes: Vec<StakeInfo>,
+    pub claims: Vec<Claim>,
+    pub locks: Vec<Lock>,
+    pub hooks: Vec<String>,
+    #[serde(default)]
+    pub stake_hooks: Vec<String>,
+    pub preauths_hooks: u64,
+    pub preauths_slashing: u64,
+    pub slashers: Vec<String>,
+    pub slashes: Vec<SlashRecord>,
+    /// Pending recalculation of points, if any
+    #[serde(default)]
+    pub recalculation: Option<Recalculation>,
+}
+
+/// Export state
+pub fn export(deps: Deps<TgradeQuery>) -> Result<Response<TgradeMsg>, ContractError> {
+    // Stake state items
+    let mut state = StakeState {
+        contract_version: get_contract_version(deps.storage)?,
+        admin: ADMIN.get(deps)?,
+        config: CONFIG.load(deps.storage)?,
+        members: vec![],
+        stakes: vec![],
+        claims: claims().all_claims(deps.storage)?,
+        locks: vec![],
+        hooks: HOOKS.list_hooks(deps.storage)?,
+        stake_hooks: STAKE_HOOKS.list_hooks(deps.storage)?,
+        preauths_hooks: PREAUTH_HOOKS.get_auth(deps.storage)?,
+        preauths_slashing: PREAUTH_SLASHING.get_auth(deps.storage)?,
+        slashers: SLASHERS.list_slashers(deps.storage)?,
+        slashes: vec![],
+        recalculation: RECALCULATION.may_load(deps.storage)?,
+    };
+
+    // Member items
+    state.members = members()
+        .range(deps.storage, None, None, Ascending)
+        .map(|r| {
+            let (addr, info) = r?;
+            Ok(Member {
+                addr: addr.to_string(),
+                points: info.points,
+                start_height: info.start_height,
+            })
+        })
+        .collect::<StdResult<_>>()?;
+
+    // Stake items, an address may have any of liquid, vesting or extra stake
+    let mut addrs: Vec<Addr> = STAKE
+        .keys(deps.storage, None, None, Ascending)
+        .chain(STAKE_VESTING.keys(deps.storage, None, None, Ascending))
+        .chain(
+            STAKE_EXTRA
+                .keys(deps.storage, None, None, Ascending)
+                .map(|key| key.map(|(addr, _)| addr)),
+        )
+        .collect::<StdResult<_>>()?;
+    addrs.sort();
+    addrs.dedup();
+    state.stakes = addrs
+        .into_iter()
+        .map(|addr| {
+            let extra = STAKE_EXTRA
+                .prefix(&addr)
+                .range(deps.storage, None, None, Ascending)
+                .map(|r| {
+                    let (denom, amount) = r?;
+                    Ok(Coin { denom, amount })
+                })
+                .collect::<StdResult<_>>()?;
+            Ok(StakeInfo {
+                liquid: STAKE.may_load(deps.storage, &addr)?.unwrap_or_default(),
+                vesting: STAKE_VESTING
+                    .may_load(deps.storage, &addr)?
+                    .unwrap_or_default(),
+                extra,
+                addr,
+            })
+        })
+        .collect::<StdResult<_>>()?;
+
+    // Lock items
+    state.locks = locks()
+        .range(deps.storage, None, None, Ascending)
+        .map(|r| r.map(|(_, lock)| lock))
+        .collect::<StdResult<_>>()?;
+
+    // Slashing history items
+    state.slashes = slashes()
+        .range(deps.storage, None, None, Ascending)
+        .map(|r| r.map(|(_, slash)| slash))
+        .collect::<StdResult<_>>()?;
+
+    Ok(Response::new().set_data(to_binary(&state)?))
+}
+
+/// Import state
+pub fn import(
+    mut deps: DepsMut<TgradeQuery>,
+    env: Env,
+    state: StakeState,
+) -> Result<Response<TgradeMsg>, ContractError> {
+    // Stake state items
+    set_contract_version(
+        deps.storage,
+        state.contract_version.contract,
+        state.contract_version.version,
+    )?;
+    ADMIN.set(deps.branch(), state.admin)?;
+    CONFIG.save(deps.storage, &state.config)?;
+    PREAUTH_HOOKS.set_auth(deps.storage, state.preauths_hooks)?;
+    PREAUTH_SLASHING.set_auth(deps.storage, state.preauths_slashing)?;
+
+    // Member items
+    // Delete all existing members
+    let addrs = members()
+        .keys(deps.storage, None, None, Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    for addr in addrs.iter() {
+        members().remove(deps.storage, addr, env.block.height)?;
+    }
+    // Import members, and their total points
+    let mut total = 0;
+    for member in &state.members {
+        let info = MemberInfo {
+            points: member.points,
+            start_height: member.start_height,
+        };
+        members().save(
+            deps.storage,
+            &Addr::unchecked(&member.addr),
+            &info,
+            env.block.height,
+        )?;
+        total += member.points;
+    }
+    TOTAL.save(deps.storage, &total)?;
+
+    // Stake items
+    // Delete all existing stakes
+    let addrs = STAKE
+        .keys(deps.storage, None, None, Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    for addr in addrs.iter() {
+        STAKE.remove(deps.storage, addr);
+    }
+    let addrs = STAKE_VESTING
+        .keys(deps.storage, None, None, Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    for addr in addrs.iter() {
+        STAKE_VESTING.remove(deps.storage, addr);
+    }
+    let keys = STAKE_EXTRA
+        .keys(deps.storage, None, None, Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    for (addr, denom) in keys.iter() {
+        STAKE_EXTRA.remove(deps.storage, (addr, denom));
+    }
+    // Import stakes
+    for stake in &state.stakes {
+        if !stake.liquid.is_zero() {
+            STAKE.save(deps.storage, &stake.addr, &stake.liquid)?;
+        }
+        if !stake.vesting.is_zero() {
+            STAKE_VESTING.save(deps.storage, &stake.addr, &stake.vesting)?;
+        }
+        for extra in &stake.extra {
+            STAKE_EXTRA.save(deps.storage, (&stake.addr, &extra.denom), &extra.amount)?;
+        }
+    }
+
+    // Claim items
+    claims().import_claims(deps.storage, &state.claims)?;
+
+    // Lock items
+    // Delete all existing locks
+    let keys = locks()
+        .keys(deps.storage, None, None, Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    for (addr, unlock_at) in keys.iter() {
+        locks().remove(deps.storage, (addr, *unlock_at))?;
+    }
+    // Import locks
+    for lock in &state.locks {
+        locks().save(deps.storage, (&lock.addr, lock.unlock_at.as_key()), lock)?;
+    }
+
+    // Hook items
+    for hook in HOOKS.list_hooks(deps.storage)? {
+        HOOKS.remove_hook(deps.storage, Addr::unchecked(hook))?;
+    }
+    for hook in state.hooks {
+        HOOKS.add_hook(deps.storage, Addr::unchecked(hook))?;
+    }
+    for hook in STAKE_HOOKS.list_hooks(deps.storage)? {
+        STAKE_HOOKS.remove_hook(deps.storage, Addr::unchecked(hook))?;
+    }
+    for hook in state.stake_hooks {
+        STAKE_HOOKS.add_hook(deps.storage, Addr::unchecked(hook))?;
+    }
+
+    // Slasher items
+    SLASHERS.instantiate(deps.storage)?;
+    for slasher in state.slashers {
+        SLASHERS.add_slasher(deps.storage, Addr::unchecked(slasher))?;
+    }
+
+    // Slashing history items
+    // Delete all existing slashings
+    let ids = slashes()
+        .keys(deps.storage, None, None, Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    for id in ids {
+        slashes().remove(deps.storage, id)?;
+    }
+    // Import slashings, continuing their numbering
+    let mut slash_count = 0;
+    for slash in &state.slashes {
+        slashes().save(deps.storage, slash.id, slash)?;
+        slash_count = slash_count.max(slash.id);
+    }
+    SLASH_COUNT.save(deps.storage, &slash_count)?;
+
+    // Recalculation items
+    match state.recalculation {
+        Some(recalculation) => RECALCULATION.save(deps.storage, &recalculation)?,
+        None => RECALCULATION.remove(deps.storage),
+    }
+
+    Ok(Response::default())
+}
@@ -87,6 +87,9 @@ impl SuiteBuilder {
                     preauths_hooks: 0,
                     preauths_slashing: 1,
                     halflife: None,
+                    halflife_reduction: None,
+                    decay_mode: None,
+                    halflife_batch: None,
                     denom: "ENGAGEMENT".to_owned(),
                 },
                 &[],
@@ -106,6 +109,9 @@ impl SuiteBuilder {
                     preauths_hooks: 0,
                     preauths_slashing: 1,
                     halflife: None,
+                    halflife_reduction: None,
+                    decay_mode: None,
+                    halflife_batch: None,
                     denom: self.group_token.clone(),
                 },
                 &[],
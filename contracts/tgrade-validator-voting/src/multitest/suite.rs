@@ -90,6 +90,9 @@ impl SuiteBuilder {
                     preauths_hooks: 0,
                     preauths_slashing: 1,
                     halflife: None,
+                    halflife_reduction: None,
+                    decay_mode: None,
+                    halflife_batch: None,
                     denom: "ENGAGEMENT".to_owned(),
                 },
                 &[],
@@ -109,6 +112,9 @@ impl SuiteBuilder {
                     preauths_hooks: 0,
                     preauths_slashing: 1,
                     halflife: None,
+                    halflife_reduction: None,
+                    decay_mode: None,
+                    halflife_batch: None,
                     denom: "GROUP".to_owned(),
                 },
                 &[],
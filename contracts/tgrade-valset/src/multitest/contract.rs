@@ -398,6 +398,9 @@ mod instantiate {
             preauths_hooks: 0,
             preauths_slashing: 1,
             auto_return_limit: 0,
+            extra_denoms: vec![],
+            lock_tiers: vec![],
+            instant_unbond: None,
         };
         let stake_addr = app
             .instantiate_contract(
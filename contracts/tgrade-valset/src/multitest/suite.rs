@@ -252,6 +252,9 @@ impl SuiteBuilder {
                         preauths_hooks: 0,
                         preauths_slashing: 1,
                         halflife: None,
+                        halflife_reduction: None,
+                        decay_mode: None,
+                        halflife_batch: None,
                         denom: denom.clone(),
                     },
                     &[],
@@ -277,6 +280,9 @@ impl SuiteBuilder {
                         preauths_hooks: 0,
                         preauths_slashing: 1,
                         auto_return_limit: 0,
+                        extra_denoms: vec![],
+                        lock_tiers: vec![],
+                        instant_unbond: None,
                     },
                     &[],
                     "group",
@@ -320,6 +326,9 @@ impl SuiteBuilder {
                         preauths_hooks: 0,
                         preauths_slashing: 1,
                         halflife: config.halflife,
+                        halflife_reduction: None,
+                        decay_mode: None,
+                        halflife_batch: None,
                         denom: denom.clone(),
                     },
                     &[],
@@ -639,6 +648,7 @@ impl Suite {
                 &tg4_engagement::msg::ExecuteMsg::WithdrawRewards {
                     owner: None,
                     receiver: None,
+                    denom: None,
                 },
                 &[],
             )
@@ -657,6 +667,7 @@ impl Suite {
             &tg4_engagement::msg::ExecuteMsg::WithdrawRewards {
                 owner: None,
                 receiver: None,
+                denom: None,
             },
             &[],
         )
@@ -801,6 +812,7 @@ impl Suite {
             self.membership.clone(),
             &tg4_stake::msg::ExecuteMsg::Bond {
                 vesting_tokens: None,
+                lock_duration: None,
             },
             stake,
         )
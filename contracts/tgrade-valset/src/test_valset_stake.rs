@@ -85,6 +85,7 @@ fn instantiate_stake(app: &mut BasicApp<TgradeMsg>) -> Addr {
         preauths_hooks: 0,
         preauths_slashing: 1,
         auto_return_limit: 0,
+        extra_denoms: vec![],
     };
     app.instantiate_contract(
         stake_id,
@@ -69,6 +69,9 @@ impl SuiteBuilder {
                     preauths_hooks: 0,
                     preauths_slashing: 0,
                     halflife: None,
+                    halflife_reduction: None,
+                    decay_mode: None,
+                    halflife_batch: None,
                     denom: "poe-coin".to_string(),
                 },
                 &[],